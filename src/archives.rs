@@ -0,0 +1,145 @@
+use std::{io::{BufReader, Read}, path::Component};
+
+use crate::*;
+
+const ARCHIVE_SUFFIXES : [&str; 4] = [".zip", ".tar", ".tar.gz", ".tgz"];
+
+// A file of an archive, with its path inside the archive and the size and modification time of its header
+struct ArchiveEntry {
+    path: PathBuf,
+    bytes: usize,
+    modified: Option<SystemTime>
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|x| x.to_str()) else { return false };
+    let name = name.to_lowercase();
+    ARCHIVE_SUFFIXES.iter().any(|x| name.ends_with(x))
+}
+
+// Reads the files of the target archives, with the same rules that the producers use for the dirs, and adds them to the
+// metadata of their languages and to the injector along with their contents. Their paths are the ones of the archives
+// joined with their paths inside them, like if they were extracted, so the ignore files and the .gitattributes of the
+// dirs above an archive apply to them, but the ones inside it don't.
+// Only the entries that pass the filters are read, so the rest of the archive is only skipped over.
+pub fn find_archive_files(context: &TraversalContext, files_present: &mut FilesPresent) -> Result<(), String> {
+    let TraversalContext {config, extension_lang_map, ..} = context;
+    let mut languages_metadata_map = context.languages_metadata_map.lock().unwrap();
+    for target in config.dirs.iter().map(Path::new).filter(|x| x.is_file() && is_archive(x)) {
+        let dir = target.parent().unwrap_or(Path::new(""));
+        let gitignore_stack = if config.no_gitignore {None} else {GitignoreStack::of_dir(dir)};
+        let gitattributes_stack = if config.no_gitattributes {None} else {GitattributesStack::extended(dir, GitattributesStack::for_root_dir(dir))};
+        let mut add_entry = |entry: ArchiveEntry, reader: &mut dyn Read| -> Result<(), String> {
+            let Some(relative_path) = normalized(&entry.path) else { return Ok(()) };
+            let mut components = relative_path.components().rev();
+            components.next();
+            if !config.should_search_in_dotted && components.any(|x| x.as_os_str().to_str().is_none_or(|x| x.starts_with('.'))) {
+                return Ok(());
+            }
+            if config.max_depth.is_some_and(|x| relative_path.components().count() > x) {
+                return Ok(());
+            }
+            let path = target.join(&relative_path);
+            files_present.total_files += 1;
+
+            let attributes = gitattributes_stack.as_ref().map(|x| x.attributes_of(&path)).unwrap_or_default();
+            let Some(lang_name) = find_language_of_file(extension_lang_map, &path, &attributes) else { return Ok(()) };
+            // Unlike in the traversal, the dirs of the file inside the archive haven't been checked already
+            if let Some(reason) = context.exclusion_reason(&path, target, gitignore_stack.as_deref(), &attributes, entry.bytes, entry.modified) {
+                files_present.exclude(reason);
+                return Ok(());
+            }
+
+            // The buffer grows with what is actually read, instead of trusting the size of the header
+            let mut contents = Vec::new();
+            reader.read_to_end(&mut contents).map_err(|x| x.to_string())?;
+            files_present.relevant_files += 1;
+            languages_metadata_map.get_mut(lang_name.as_ref()).unwrap().add_file_meta(contents.len());
+            context.files_injector.push(ParsableFile::with_contents(path, lang_name, contents));
+            Ok(())
+        };
+
+        let result = if target.to_string_lossy().to_lowercase().ends_with(".zip") {
+            read_zip_entries(target, &mut add_entry)
+        } else {
+            read_tar_entries(target, &mut add_entry)
+        };
+        if let Err(x) = result {
+            return Err(format!("'{}': {x}", target.display()));
+        }
+    }
+
+    Ok(())
+}
+
+// The relative paths without the leading "./", while the ones that would be extracted outside of the archive are skipped
+fn normalized(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(x) => normalized.push(x),
+            Component::CurDir => (),
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None
+        }
+    }
+    (!normalized.as_os_str().is_empty()).then_some(normalized)
+}
+
+// The modification times of the zip entries have no timezone, so they are not taken into account
+fn read_zip_entries(path: &Path, add_entry: &mut impl FnMut(ArchiveEntry, &mut dyn Read) -> Result<(), String>) -> Result<(), String> {
+    let file = File::open(path).map_err(|x| x.to_string())?;
+    let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|x| x.to_string())?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|x| x.to_string())?;
+        if !entry.is_file() {
+            continue;
+        }
+        let entry_path = PathBuf::from(entry.name());
+        let bytes = entry.size() as usize;
+        add_entry(ArchiveEntry{path: entry_path, bytes, modified: None}, &mut entry)?;
+    }
+
+    Ok(())
+}
+
+// The gzipped ones are decompressed while they are read
+fn read_tar_entries(path: &Path, add_entry: &mut impl FnMut(ArchiveEntry, &mut dyn Read) -> Result<(), String>) -> Result<(), String> {
+    let file = BufReader::new(File::open(path).map_err(|x| x.to_string())?);
+    let name = path.to_string_lossy().to_lowercase();
+    let reader: Box<dyn Read> = if name.ends_with(".tar") {Box::new(file)} else {Box::new(flate2::read::GzDecoder::new(file))};
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().map_err(|x| x.to_string())? {
+        let mut entry = entry.map_err(|x| x.to_string())?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let entry_path = entry.path().map_err(|x| x.to_string())?.into_owned();
+        let modified = entry.header().mtime().ok().map(|x| SystemTime::UNIX_EPOCH + Duration::from_secs(x));
+        let bytes = entry.size() as usize;
+        add_entry(ArchiveEntry{path: entry_path, bytes, modified}, &mut entry)?;
+    }
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_archives_are_recognised_by_their_names() {
+        assert!(is_archive(Path::new("/a/vendor.zip")));
+        assert!(is_archive(Path::new("release-1.0.TAR.GZ")));
+        assert!(is_archive(Path::new("a.tgz")));
+        assert!(is_archive(Path::new("a.tar")));
+        assert!(!is_archive(Path::new("a.gz")));
+        assert!(!is_archive(Path::new("zip")));
+        assert!(!is_archive(Path::new("a.rs")));
+
+        assert_eq!(Some(PathBuf::from("src/a.rs")), normalized(Path::new("./src/./a.rs")));
+        assert_eq!(None, normalized(Path::new("../a.rs")));
+        assert_eq!(None, normalized(Path::new("/etc/a.rs")));
+        assert_eq!(None, normalized(Path::new("./")));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+
+// The author that git blame assigns to the lines of the working tree that aren't committed yet
+pub const NOT_COMMITTED_YET : &str = "Not Committed Yet";
+
+#[derive(Debug,PartialEq,Default,Clone)]
+pub struct AuthorStats {
+    pub code_lines: usize,
+    pub extra_lines: usize
+}
+
+#[derive(Debug,PartialEq,Default)]
+pub struct Authorship {
+    // Both of them sorted by the code lines, descending
+    pub authors: Vec<(String,AuthorStats)>,
+    pub languages: Vec<(String,Vec<(String,AuthorStats)>)>,
+    // The files that git couldn't blame, like the untracked ones
+    pub unblamed_files: usize
+}
+
+impl AuthorStats {
+    fn add(&mut self, other: &AuthorStats) {
+        self.code_lines += other.code_lines;
+        self.extra_lines += other.extra_lines;
+    }
+}
+
+// Every line is attributed to the author of its last change, as a code line if its number is among the given ones,
+// or else as an extra line. With a revision the file is blamed as it was at that revision, else as it is in the working tree.
+pub fn blame(path: &Path, rev: Option<&str>, mailmap: Option<&str>, code_line_numbers: &[usize]) -> Result<HashMap<String,AuthorStats>, String> {
+    // With a revision, the dir of the file may not exist in the working tree anymore
+    let Some(dir) = path.ancestors().skip(1).find(|x| x.is_dir()) else { return Err(format!("no dir of '{}' exists", path.display())) };
+    let path_str = path.to_string_lossy();
+    let mailmap_option = mailmap.map(|x| format!("mailmap.file={x}"));
+    let mut args = Vec::with_capacity(8);
+    if let Some(x) = &mailmap_option {
+        args.extend(["-c", x.as_str()]);
+    }
+    args.extend(["blame", "--line-porcelain"]);
+    args.extend(rev);
+    args.extend(["--", path_str.as_ref()]);
+    let output = git::run_git(dir, &args)?;
+
+    // Every line starts with a header of "<commit> <original line> <final line> [<lines of the group>]",
+    // followed by the info of its commit and finally by the line itself, after a tab
+    let mut authors: HashMap<String,AuthorStats> = HashMap::new();
+    let mut final_line = 0;
+    let mut author = None;
+    for line in output.split(|x| *x == b'\n') {
+        if line.first() == Some(&b'\t') {
+            let stats = authors.entry(author.take().unwrap_or_default()).or_default();
+            if code_line_numbers.binary_search(&final_line).is_ok() {
+                stats.code_lines += 1;
+            } else {
+                stats.extra_lines += 1;
+            }
+        } else if let Some(name) = line.strip_prefix(b"author ") {
+            author = Some(String::from_utf8_lossy(name).into_owned());
+        } else if author.is_none() && let Some(number) = std::str::from_utf8(line).ok().and_then(|x| x.split(' ').nth(2)) {
+            final_line = number.parse().unwrap_or(0);
+        }
+    }
+
+    Ok(authors)
+}
+
+pub fn find_authorship(files_details: &[FileDetails]) -> Authorship {
+    let mut authors: HashMap<&str,AuthorStats> = HashMap::new();
+    let mut languages: HashMap<&str,HashMap<&str,AuthorStats>> = HashMap::new();
+    let mut authorship = Authorship::default();
+    for details in files_details {
+        let Some(file_authors) = &details.authors else {
+            authorship.unblamed_files += 1;
+            continue;
+        };
+        for (author, stats) in file_authors {
+            authors.entry(author).or_default().add(stats);
+            languages.entry(&details.language_name).or_default().entry(author).or_default().add(stats);
+        }
+    }
+
+    authorship.authors = sorted_by_code_lines(authors);
+    let mut languages = languages.into_iter().map(|(name, x)| (name.to_owned(), sorted_by_code_lines(x))).collect::<Vec<_>>();
+    languages.sort_by_cached_key(|x| (std::cmp::Reverse(x.1.iter().map(|x| x.1.code_lines).sum::<usize>()), x.0.clone()));
+    authorship.languages = languages;
+
+    authorship
+}
+
+fn sorted_by_code_lines(authors: HashMap<&str,AuthorStats>) -> Vec<(String,AuthorStats)> {
+    let mut authors = authors.into_iter().map(|(name, stats)| (name.to_owned(), stats)).collect::<Vec<_>>();
+    authors.sort_by(|a,b| b.1.code_lines.cmp(&a.1.code_lines).then_with(|| b.1.extra_lines.cmp(&a.1.extra_lines)).then_with(|| a.0.cmp(&b.0)));
+    authors
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_lines_are_attributed_to_their_authors() {
+        let root = git::temp_repository("authors_test");
+        let commit = |name: &str, email: &str| {
+            git::run_git(&root, &["-c", &format!("user.name={name}"), "-c", &format!("user.email={email}"), "commit", "-q", "-a", "-m", "x"]).unwrap();
+        };
+        let path = root.join("a.py");
+        fs::write(&path, "x = 1\n").unwrap();
+        git::run_git(&root, &["add", "-A"]).unwrap();
+        commit("Alice", "alice@home");
+        fs::write(&path, "x = 1\n# comment\ny = 2\n").unwrap();
+        commit("alice", "alice@work");
+        fs::write(&path, "x = 1\n# comment\ny = 2\nz = 3\n").unwrap();
+
+        let authors = blame(&path, None, None, &[1, 3, 4]).unwrap();
+        assert_eq!(AuthorStats{code_lines: 1, extra_lines: 0}, authors["Alice"]);
+        assert_eq!(AuthorStats{code_lines: 1, extra_lines: 1}, authors["alice"]);
+        assert_eq!(AuthorStats{code_lines: 1, extra_lines: 0}, authors[NOT_COMMITTED_YET]);
+
+        let mailmap = root.join("mailmap");
+        fs::write(&mailmap, "Alice <alice@home> <alice@work>\n").unwrap();
+        let authors = blame(&path, Some("HEAD"), mailmap.to_str(), &[1, 3]).unwrap();
+        assert_eq!(vec![("Alice", &AuthorStats{code_lines: 2, extra_lines: 1})], authors.iter().map(|x| (x.0.as_str(), x.1)).collect::<Vec<_>>());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,140 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::*;
+
+// In the order that GitHub looks for them, only the first one that exists is used
+const CODEOWNERS_LOCATIONS : [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+#[derive(Debug)]
+pub struct CodeOwners {
+    pub path: PathBuf,
+    root: PathBuf,
+    matcher: Gitignore,
+    // The index of the rule of every pattern and its owners. A pattern that appears more than once
+    // keeps its last rule, which is also the one that the matcher returns.
+    rules_of_patterns: HashMap<String,(usize,Vec<String>)>
+}
+
+#[derive(Debug,PartialEq,Default,Clone)]
+pub struct OwnerStats {
+    pub files: usize,
+    pub lines: usize,
+    pub code_lines: usize
+}
+
+#[derive(Debug,PartialEq,Default)]
+pub struct Ownership {
+    // The CODEOWNERS files that were found in the target dirs
+    pub codeowners_paths: Vec<PathBuf>,
+    // A file with many owners is counted for each one of them
+    pub owners: HashMap<String,OwnerStats>,
+    pub unowned: OwnerStats,
+    // Of all the files, each one counted once
+    pub total_code_lines: usize,
+    // Sorted by the code lines, descending
+    pub unowned_files: Vec<(PathBuf,usize)>
+}
+
+impl CodeOwners {
+    pub fn find(target_dir: &Path) -> Option<CodeOwners> {
+        let path = CODEOWNERS_LOCATIONS.iter().map(|x| target_dir.join(x)).find(|x| x.is_file())?;
+        let contents = fs::read_to_string(&path).ok()?;
+        Some(CodeOwners::parse(&contents, path, target_dir))
+    }
+
+    // Every rule is a pattern followed by its owners. The rules that can't be parsed are skipped, like GitHub does.
+    pub fn parse(contents: &str, path: PathBuf, root: &Path) -> CodeOwners {
+        let mut builder = GitignoreBuilder::new(root);
+        let mut rules_of_patterns = HashMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            // The section headers of GitLab
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') || line.starts_with("^[") {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let pattern = words.next().unwrap();
+            let owners = words.take_while(|x| !x.starts_with('#')).map(|x| x.to_owned()).collect::<Vec<_>>();
+            if builder.add_line(None, pattern).is_ok() {
+                rules_of_patterns.insert(pattern.to_owned(), (index, owners));
+            }
+        }
+
+        CodeOwners {
+            path,
+            root: root.to_path_buf(),
+            matcher: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+            rules_of_patterns
+        }
+    }
+
+    // Unlike a .gitignore, where the rules that match a file take precedence over the ones that match its dirs,
+    // the last rule that matches either of them wins. A rule without owners leaves the files that it matches unowned.
+    pub fn owners_of(&self, path: &Path) -> &[String] {
+        let mut last_rule: Option<&(usize,Vec<String>)> = None;
+        let mut is_dir = false;
+        for current in path.ancestors().take_while(|x| x.starts_with(&self.root) && *x != self.root) {
+            if let ignore::Match::Ignore(glob) = self.matcher.matched(current, is_dir)
+                && let Some(rule) = self.rules_of_patterns.get(glob.original())
+                && last_rule.is_none_or(|x| x.0 < rule.0) {
+                last_rule = Some(rule);
+            }
+            is_dir = true;
+        }
+        last_rule.map_or(&[], |x| x.1.as_slice())
+    }
+}
+
+impl OwnerStats {
+    fn add_file(&mut self, details: &FileDetails) {
+        self.files += 1;
+        self.lines += details.lines;
+        self.code_lines += details.code_lines;
+    }
+}
+
+// The files of a target dir are owned according to the CODEOWNERS of the innermost target dir that contains them
+pub fn find_ownership(files_details: &[FileDetails], target_dirs: &[String]) -> Ownership {
+    let mut all_code_owners = target_dirs.iter().filter_map(|x| CodeOwners::find(Path::new(x))).collect::<Vec<_>>();
+    all_code_owners.sort_by_key(|x| std::cmp::Reverse(x.root.components().count()));
+
+    let mut ownership = Ownership {
+        codeowners_paths: all_code_owners.iter().map(|x| x.path.clone()).collect(),
+        ..Default::default()
+    };
+    for details in files_details {
+        ownership.total_code_lines += details.code_lines;
+        let owners = all_code_owners.iter().find(|x| details.path.starts_with(&x.root)).map_or(&[] as &[String], |x| x.owners_of(&details.path));
+        if owners.is_empty() {
+            ownership.unowned.add_file(details);
+            ownership.unowned_files.push((details.path.clone(), details.code_lines));
+        }
+        for owner in owners {
+            ownership.owners.entry(owner.clone()).or_default().add_file(details);
+        }
+    }
+    ownership.unowned_files.sort_by(|a,b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    ownership
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_last_matching_rule_owns_a_file() {
+        let contents = "# comment\n*       @everyone\n*.rs    @rustaceans @reviewers # inline comment\n/docs/  @writers\n\
+                src/generated/\n[Section]\nsrc/*.rs @core\n";
+        let code_owners = CodeOwners::parse(contents, PathBuf::from("/repo/CODEOWNERS"), Path::new("/repo"));
+
+        assert_eq!(&["@everyone".to_owned()], code_owners.owners_of(Path::new("/repo/README.md")));
+        assert_eq!(&["@rustaceans".to_owned(), "@reviewers".to_owned()], code_owners.owners_of(Path::new("/repo/lib/a.rs")));
+        assert_eq!(&["@core".to_owned()], code_owners.owners_of(Path::new("/repo/src/a.rs")));
+        assert_eq!(&["@writers".to_owned()], code_owners.owners_of(Path::new("/repo/docs/guide/a.rs")));
+        assert!(code_owners.owners_of(Path::new("/repo/src/generated/a.rs")).is_empty());
+        assert!(code_owners.owners_of(Path::new("/other/a.rs")).is_empty());
+    }
+}
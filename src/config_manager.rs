@@ -1,15 +1,17 @@
-use std::{path::Path};
+use std::{path::Path, time::{Duration, SystemTime}};
 
+use chrono::{DateTime, FixedOffset, Local, NaiveDate};
 use colored::{ColoredString, Colorize};
 
-use crate::{Color, Formatted, GitignoreStack, io_handler, message_printer, utils};
+use crate::{Color, Formatted, GitignoreStack, git, io_handler, message_printer, utils};
 
 // Application version, to be displayed at startup and with --help command
-pub const VERSION_ID : &str = "v2.0.1";
+pub const VERSION_ID : &str = "v2.1.0";
 
 // command flags
 pub const DIRS               :&str   = "dirs";
 pub const EXCLUDE            :&str   = "exclude";
+pub const INCLUDE            :&str   = "include";
 pub const LANGUAGES          :&str   = "languages";
 pub const EXCLUDE_LANGUAGES  :&str   = "exclude-languages";
 pub const THREADS            :&str   = "threads";
@@ -19,6 +21,38 @@ pub const SHOW_FAULTY_FILES  :&str   = "show-faulty-files";
 pub const NO_KEYWORDS        :&str   = "no-keywords";
 pub const NO_VISUAL          :&str   = "no-visual";
 pub const NO_GITIGNORE       :&str   = "no-gitignore";
+pub const NO_GITATTRIBUTES   :&str   = "no-gitattributes";
+pub const FOLLOW_SYMLINKS    :&str   = "follow-symlinks";
+pub const MAX_DEPTH          :&str   = "max-depth";
+pub const MAX_FILE_SIZE      :&str   = "max-file-size";
+pub const MIN_FILE_SIZE      :&str   = "min-file-size";
+pub const MODIFIED_SINCE     :&str   = "modified-since";
+pub const EXCLUSION_REASONS  :&str   = "exclusion-reasons";
+pub const FAIL_ON_TRAVERSAL_ERRORS :&str   = "fail-on-traversal-errors";
+pub const PER_FILE           :&str   = "per-file";
+pub const INDENT_WIDTH       :&str   = "indent-width";
+pub const HYGIENE            :&str   = "hygiene";
+pub const DISTRIBUTION       :&str   = "distribution";
+pub const COLUMN_LIMIT       :&str   = "column-limit";
+pub const DUPLICATE_FILES    :&str   = "duplicate-files";
+pub const DEDUPE_FILES       :&str   = "dedupe-files";
+pub const DUPLICATES         :&str   = "duplicates";
+pub const ESTIMATION         :&str   = "estimation";
+pub const COCOMO             :&str   = "cocomo";
+pub const SALARY             :&str   = "salary";
+pub const TEST_PATTERNS      :&str   = "test-patterns";
+pub const OWNERS             :&str   = "owners";
+pub const AUTHORS            :&str   = "authors";
+pub const MAILMAP            :&str   = "mailmap";
+pub const HOTSPOTS           :&str   = "hotspots";
+pub const CHURN_WINDOW       :&str   = "churn-window";
+pub const REV                :&str   = "rev";
+pub const BACKFILL           :&str   = "backfill";
+pub const DIFF               :&str   = "diff";
+pub const CHANGED_SINCE      :&str   = "changed-since";
+pub const STAGED             :&str   = "staged";
+pub const FILES_FROM         :&str   = "files-from";
+pub const RESPECT_IGNORES    :&str   = "respect-ignores";
 pub const COLORS             :&str   = "colors";
 pub const COLOR_PALETTE      :&str   = "color-palette";
 pub const LOG                :&str   = "log";
@@ -38,6 +72,24 @@ pub const MAX_CONSUMERS_VALUE : usize = 30;
 pub const MIN_CONSUMERS_VALUE : usize = 1;
 pub const MIN_COMPARE_LEVEL   : usize = 0;
 pub const MAX_COMPARE_LEVEL   : usize = 10;
+pub const MIN_PER_FILE_ENTRIES: usize = 0;
+pub const MAX_PER_FILE_ENTRIES: usize = 100;
+// used when the per-file command is given without a number
+pub const PER_FILE_ENTRIES_IF_UNSPECIFIED: usize = 10;
+pub const MIN_INDENT_WIDTH    : usize = 1;
+pub const MAX_INDENT_WIDTH    : usize = 16;
+pub const MIN_COLUMN_LIMIT    : usize = 1;
+pub const MAX_COLUMN_LIMIT    : usize = 1000;
+pub const MIN_DUPLICATE_LINES : usize = 0;
+pub const MAX_DUPLICATE_LINES : usize = 100;
+pub const MIN_SALARY          : usize = 1;
+pub const MAX_SALARY          : usize = 100_000_000;
+pub const MIN_CHURN_WINDOW    : usize = 1;
+pub const MAX_CHURN_WINDOW    : usize = 36_500;
+pub const MIN_MAX_DEPTH       : usize = 1;
+pub const MAX_MAX_DEPTH       : usize = 10_000;
+pub const MIN_BACKFILL_STEP   : usize = 1;
+pub const MAX_BACKFILL_STEP   : usize = 100_000;
 
 // default config values
 const DEF_BRACES_AS_CODE    : bool    = false;
@@ -46,14 +98,36 @@ const DEF_SHOW_FAULTY_FILES : bool    = false;
 const DEF_NO_VISUAL         : bool    = false;
 const DEF_NO_KEYWORDS       : bool    = false;
 const DEF_NO_GITIGNORE      : bool    = false;
+const DEF_NO_GITATTRIBUTES  : bool    = false;
+const DEF_FOLLOW_SYMLINKS   : bool    = false;
 const DEF_COMPARE_LEVEL     : usize   = 1;
-
+const DEF_PER_FILE          : usize   = 0;
+const DEF_INDENT_WIDTH      : usize   = 4;
+const DEF_HYGIENE           : bool    = false;
+const DEF_DISTRIBUTION      : bool    = false;
+const DEF_COLUMN_LIMIT      : usize   = 120;
+const DEF_DUPLICATE_FILES   : bool    = false;
+const DEF_DEDUPE_FILES      : bool    = false;
+const DEF_DUPLICATES        : usize   = 0;
+const DEF_ESTIMATION        : bool    = false;
+// The average annual salary of a developer, as used by scc
+const DEF_SALARY            : usize   = 56286;
+const DEF_TEST_PATTERNS     : &[&str] = &["**/tests/**"];
+const DEF_OWNERS            : bool    = false;
+const DEF_AUTHORS           : bool    = false;
+
+const DEF_STAGED            : bool    = false;
+const DEF_CHURN_WINDOW      : usize   = 365;
+const DEF_EXCLUSION_REASONS : bool    = false;
+const DEF_FAIL_ON_TRAVERSAL_ERRORS : bool    = false;
+const DEF_RESPECT_IGNORES   : bool    = false;
 
 #[derive(Debug,PartialEq,Clone)]
 pub struct Configuration {
     pub version: &'static str,
     pub dirs: Vec<String>,
     pub exclude_dirs: Vec<String>,
+    pub include_patterns: Vec<String>,
     pub languages_of_interest: Vec<String>,
     pub excluded_languages: Vec<String>,
     pub threads: Threads,
@@ -63,6 +137,58 @@ pub struct Configuration {
     pub no_keywords: bool,
     pub no_visual: bool,
     pub no_gitignore: bool,
+    pub no_gitattributes: bool,
+    pub follow_symlinks: bool,
+    // Relative to every target dir, None if unlimited
+    pub max_depth: Option<usize>,
+    // In bytes, None if unlimited
+    pub max_file_size: Option<usize>,
+    // In bytes, None if unlimited
+    pub min_file_size: Option<usize>,
+    // None if the modification time of the files doesn't matter
+    pub modified_since: Option<ModifiedSince>,
+    pub exclusion_reasons: bool,
+    pub fail_on_traversal_errors: bool,
+    // The number of files to show in the per-file ranking, 0 disables it
+    pub per_file: usize,
+    // The number of spaces that make up one nesting level, for languages without block symbols
+    pub indent_width: usize,
+    pub hygiene: bool,
+    pub distribution: bool,
+    // Lines longer than this are counted as long lines
+    pub column_limit: usize,
+    pub duplicate_files: bool,
+    // Files with identical contents are counted only once in the stats
+    pub dedupe_files: bool,
+    // The min number of consecutive code lines of a duplicated block, 0 disables the detection
+    pub duplicates: usize,
+    pub estimation: bool,
+    pub cocomo: CocomoCoefficients,
+    // The annual salary that the estimated cost is based on
+    pub salary: usize,
+    // Globs of the files that contain only test code, on top of the ones of each language
+    pub test_patterns: Vec<String>,
+    pub owners: bool,
+    pub authors: bool,
+    // A mailmap file, applied along with the .mailmap of the repository when blaming
+    pub mailmap: Option<String>,
+    // Ranks the files by their churn multiplied by this metric, None if disabled
+    pub hotspots: Option<HotspotMetric>,
+    pub churn_window: usize,
+    // The git revision whose files are counted instead of the ones of the working tree
+    pub rev: Option<String>,
+    // The commits whose stats are added to the log, instead of the stats of the target dirs
+    pub backfill: Option<Backfill>,
+    // The two sides whose stats are compared per language, instead of counting the target dirs
+    pub diff: Option<(DiffTarget, DiffTarget)>,
+    // Only the files that changed since this git revision are counted, including the untracked ones
+    pub changed_since: Option<String>,
+    // Only the files that are staged in git are counted
+    pub staged: bool,
+    // The file with the list of the files to count, or "-" for the standard input
+    pub files_from: Option<String>,
+    // The listed files of '--files-from' are checked against the ignore files and the linguist attributes
+    pub respect_ignores: bool,
     pub colors: Vec<Color>,
     pub log: LogOption,
     pub compare_level: usize,
@@ -76,6 +202,44 @@ pub struct LogOption {
     pub name: Option<String>
 }
 
+#[derive(Debug,PartialEq,Clone)]
+pub enum Backfill {
+    Tags,
+    // A git revision range and the step of the selected commits in it
+    Range(String, usize)
+}
+
+#[derive(Debug,PartialEq,Clone)]
+pub enum DiffTarget {
+    Dir(String),
+    Rev(String)
+}
+
+// The start of the period in which the counted files were last modified
+#[derive(Debug,PartialEq,Clone)]
+pub enum ModifiedSince {
+    Date(DateTime<FixedOffset>),
+    // Both of them end at the time of the run
+    Days(usize),
+    Hours(usize)
+}
+
+// What the churn of a file is multiplied by, to rank the hotspots
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub enum HotspotMetric {
+    Size,
+    Complexity
+}
+
+// Effort = a * KLOC^b person-months, Schedule = c * Effort^d months
+#[derive(Debug,PartialEq,Clone)]
+pub struct CocomoCoefficients {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64
+}
+
 #[derive(Debug,PartialEq,Clone)]
 pub struct Threads {
     pub producers: usize,
@@ -97,7 +261,9 @@ pub enum ArgParsingError {
     InvalidValueInConfig(String,String),
     InvalidGlobPattern(String),
     NoGlobMatches(String),
-    AllGlobMatchesIgnored(String)
+    AllGlobMatchesIgnored(String),
+    InvalidRevision(String,String),
+    IncompatibleCommands(String,String)
 }
 
 // Empty line argument is not supposed to be allowed, since this check is being performed in main
@@ -109,7 +275,7 @@ pub fn create_config_from_args(line: &str) -> Result<Configuration, ArgParsingEr
 }
 
 pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilder, ArgParsingError> {
-    let mut dirs = None;
+    let mut config_builder = ConfigurationBuilder::default();
     let mut options = line.split("--");
     // The target paths can be given before these flags are parsed, so they are detected up front
     let respect_gitignore = !line.contains(&(String::from("--") + NO_GITIGNORE));
@@ -122,7 +288,7 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
         match parse_dirs(options.next().unwrap(), respect_gitignore, dotted_are_targetable) {
             Ok(x) => {
                 if !x.is_empty() {
-                    dirs = Some(x);
+                    config_builder.dirs = Some(x);
                 }
             },
             Err(x) => {
@@ -132,17 +298,13 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
     }
 
     let mut custom_config = None;
-    let (mut exclude_dirs, mut languages_of_interest, mut excluded_languages, mut threads, mut braces_as_code,
-         mut search_in_dotted, mut show_faulty_files, mut config_name_to_save, mut no_visual, mut log,
-         mut compare_level, mut config_name_to_load, mut no_keywords, mut no_gitignore, mut colors, mut color_palette)
-         = (None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None);
     for command in options {
         let (command_name, arguments) = match command.find(" ") {
             Some(index) => command.split_at(index),
             None => (command.trim(), "")
         };
         if command_name == DIRS {
-            if dirs.is_some() {
+            if config_builder.dirs.is_some() {
                 return Err(ArgParsingError::DoublePath);
             }
 
@@ -152,7 +314,7 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
                     message_printer::print_help_message_for_command(DIRS);
                     return Err(ArgParsingError::IncorrectCommandArgs(DIRS.to_owned()));
                 }
-                dirs = Some(x)
+                config_builder.dirs = Some(x)
             } else {
                 return Err(parse_result.err().unwrap());
             }
@@ -162,26 +324,33 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
                 message_printer::print_help_message_for_command(EXCLUDE);
                 return Err(ArgParsingError::IncorrectCommandArgs(EXCLUDE.to_owned()));
             }
-            exclude_dirs = Some(vec);
+            config_builder.exclude_dirs = Some(vec);
+        } else if command_name == INCLUDE {
+            let vec = utils::parse_paths_to_vec(arguments);
+            if vec.is_empty() || utils::build_include_matcher(&vec, &[]).is_err() {
+                message_printer::print_help_message_for_command(INCLUDE);
+                return Err(ArgParsingError::IncorrectCommandArgs(INCLUDE.to_owned()));
+            }
+            config_builder.include_patterns = Some(vec);
         } else if command_name == LANGUAGES {
             let vec = utils::parse_languages_to_vec(arguments);
             if vec.is_empty() {
                 message_printer::print_help_message_for_command(LANGUAGES);
                 return Err(ArgParsingError::IncorrectCommandArgs(LANGUAGES.to_owned()));
             }    
-            languages_of_interest = Some(vec);
+            config_builder.languages_of_interest = Some(vec);
         } else if command_name == EXCLUDE_LANGUAGES {
             let vec = utils::parse_languages_to_vec(arguments);
             if vec.is_empty() {
                 message_printer::print_help_message_for_command(EXCLUDE_LANGUAGES);
                 return Err(ArgParsingError::IncorrectCommandArgs(EXCLUDE_LANGUAGES.to_owned()));
             }    
-            excluded_languages = Some(vec);
+            config_builder.excluded_languages = Some(vec);
         } else if command_name == THREADS {
             let threads_values = utils::parse_two_usize_values(arguments,
                     MIN_PRODUCERS_VALUE, MAX_PRODUCERS_VALUE, MIN_CONSUMERS_VALUE, MAX_CONSUMERS_VALUE);
             if let Some(_threads) = threads_values {
-                threads = Some(Threads::from(_threads));
+                config_builder.threads = Some(Threads::from(_threads));
             } else {
                 message_printer::print_help_message_for_command(THREADS);
                 return Err(ArgParsingError::IncorrectCommandArgs(THREADS.to_owned()))
@@ -191,40 +360,277 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
                 message_printer::print_help_message_for_command(BRACES_AS_CODE);
                 return Err(ArgParsingError::UnexpectedCommandArgs(BRACES_AS_CODE.to_owned()))
             }
-            braces_as_code = Some(true)
+            config_builder.braces_as_code = Some(true)
         } else if command_name == SEARCH_IN_DOTTED {
             if has_any_args(command) {
                 message_printer::print_help_message_for_command(SEARCH_IN_DOTTED);
                 return Err(ArgParsingError::UnexpectedCommandArgs(SEARCH_IN_DOTTED.to_owned()))
             }
-            search_in_dotted = Some(true)
+            config_builder.should_search_in_dotted = Some(true)
         } else if command_name == SHOW_FAULTY_FILES {
             if has_any_args(command) {
                 message_printer::print_help_message_for_command(SHOW_FAULTY_FILES);
                 return Err(ArgParsingError::UnexpectedCommandArgs(SHOW_FAULTY_FILES.to_owned()))
             }
-            show_faulty_files = Some(true);
+            config_builder.should_show_faulty_files = Some(true);
         } else if command_name == NO_KEYWORDS {
             if has_any_args(command) {
                 message_printer::print_help_message_for_command(NO_VISUAL);
                 return Err(ArgParsingError::UnexpectedCommandArgs(NO_KEYWORDS.to_owned()))
             }
-            no_keywords = Some(true);
+            config_builder.no_keywords = Some(true);
         } else if command_name == NO_VISUAL {
             if has_any_args(command) {
                 message_printer::print_help_message_for_command(NO_VISUAL);
                 return Err(ArgParsingError::UnexpectedCommandArgs(NO_VISUAL.to_owned()))
             }
-            no_visual = Some(true);
+            config_builder.no_visual = Some(true);
         } else if command_name == NO_GITIGNORE {
             if has_any_args(command) {
                 message_printer::print_help_message_for_command(NO_GITIGNORE);
                 return Err(ArgParsingError::UnexpectedCommandArgs(NO_GITIGNORE.to_owned()))
             }
-            no_gitignore = Some(true);
+            config_builder.no_gitignore = Some(true);
+        } else if command_name == NO_GITATTRIBUTES {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(NO_GITATTRIBUTES);
+                return Err(ArgParsingError::UnexpectedCommandArgs(NO_GITATTRIBUTES.to_owned()))
+            }
+            config_builder.no_gitattributes = Some(true);
+        } else if command_name == FOLLOW_SYMLINKS {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(FOLLOW_SYMLINKS);
+                return Err(ArgParsingError::UnexpectedCommandArgs(FOLLOW_SYMLINKS.to_owned()))
+            }
+            config_builder.follow_symlinks = Some(true);
+        } else if command_name == MAX_DEPTH {
+            match utils::parse_usize_value(arguments, MIN_MAX_DEPTH, MAX_MAX_DEPTH) {
+                Some(x) => config_builder.max_depth = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(MAX_DEPTH);
+                    return Err(ArgParsingError::IncorrectCommandArgs(MAX_DEPTH.to_owned()))
+                }
+            }
+        } else if command_name == MAX_FILE_SIZE {
+            match utils::parse_size_value(arguments) {
+                Some(x) => config_builder.max_file_size = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(MAX_FILE_SIZE);
+                    return Err(ArgParsingError::IncorrectCommandArgs(MAX_FILE_SIZE.to_owned()))
+                }
+            }
+        } else if command_name == MIN_FILE_SIZE {
+            match utils::parse_size_value(arguments) {
+                Some(x) => config_builder.min_file_size = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(MIN_FILE_SIZE);
+                    return Err(ArgParsingError::IncorrectCommandArgs(MIN_FILE_SIZE.to_owned()))
+                }
+            }
+        } else if command_name == MODIFIED_SINCE {
+            match ModifiedSince::parse(arguments) {
+                Some(x) => config_builder.modified_since = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(MODIFIED_SINCE);
+                    return Err(ArgParsingError::IncorrectCommandArgs(MODIFIED_SINCE.to_owned()))
+                }
+            }
+        } else if command_name == EXCLUSION_REASONS {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(EXCLUSION_REASONS);
+                return Err(ArgParsingError::UnexpectedCommandArgs(EXCLUSION_REASONS.to_owned()))
+            }
+            config_builder.exclusion_reasons = Some(true);
+        } else if command_name == FAIL_ON_TRAVERSAL_ERRORS {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(FAIL_ON_TRAVERSAL_ERRORS);
+                return Err(ArgParsingError::UnexpectedCommandArgs(FAIL_ON_TRAVERSAL_ERRORS.to_owned()))
+            }
+            config_builder.fail_on_traversal_errors = Some(true);
+        } else if command_name == PER_FILE {
+            if !has_any_args(command) {
+                config_builder.per_file = Some(PER_FILE_ENTRIES_IF_UNSPECIFIED);
+            } else {
+                match utils::parse_usize_value(arguments, MIN_PER_FILE_ENTRIES, MAX_PER_FILE_ENTRIES) {
+                    Some(x) => config_builder.per_file = Some(x),
+                    None => {
+                        message_printer::print_help_message_for_command(PER_FILE);
+                        return Err(ArgParsingError::IncorrectCommandArgs(PER_FILE.to_owned()))
+                    }
+                }
+            }
+        } else if command_name == INDENT_WIDTH {
+            match utils::parse_usize_value(arguments, MIN_INDENT_WIDTH, MAX_INDENT_WIDTH) {
+                Some(x) => config_builder.indent_width = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(INDENT_WIDTH);
+                    return Err(ArgParsingError::IncorrectCommandArgs(INDENT_WIDTH.to_owned()))
+                }
+            }
+        } else if command_name == HYGIENE {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(HYGIENE);
+                return Err(ArgParsingError::UnexpectedCommandArgs(HYGIENE.to_owned()))
+            }
+            config_builder.hygiene = Some(true);
+        } else if command_name == DISTRIBUTION {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(DISTRIBUTION);
+                return Err(ArgParsingError::UnexpectedCommandArgs(DISTRIBUTION.to_owned()))
+            }
+            config_builder.distribution = Some(true);
+        } else if command_name == COLUMN_LIMIT {
+            match utils::parse_usize_value(arguments, MIN_COLUMN_LIMIT, MAX_COLUMN_LIMIT) {
+                Some(x) => config_builder.column_limit = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(COLUMN_LIMIT);
+                    return Err(ArgParsingError::IncorrectCommandArgs(COLUMN_LIMIT.to_owned()))
+                }
+            }
+        } else if command_name == DUPLICATE_FILES {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(DUPLICATE_FILES);
+                return Err(ArgParsingError::UnexpectedCommandArgs(DUPLICATE_FILES.to_owned()))
+            }
+            config_builder.duplicate_files = Some(true);
+        } else if command_name == DEDUPE_FILES {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(DEDUPE_FILES);
+                return Err(ArgParsingError::UnexpectedCommandArgs(DEDUPE_FILES.to_owned()))
+            }
+            config_builder.dedupe_files = Some(true);
+        } else if command_name == DUPLICATES {
+            match utils::parse_usize_value(arguments, MIN_DUPLICATE_LINES, MAX_DUPLICATE_LINES) {
+                Some(x) => config_builder.duplicates = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(DUPLICATES);
+                    return Err(ArgParsingError::IncorrectCommandArgs(DUPLICATES.to_owned()))
+                }
+            }
+        } else if command_name == ESTIMATION {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(ESTIMATION);
+                return Err(ArgParsingError::UnexpectedCommandArgs(ESTIMATION.to_owned()))
+            }
+            config_builder.estimation = Some(true);
+        } else if command_name == COCOMO {
+            match utils::parse_positive_f64_values(arguments, 4) {
+                Some(x) => config_builder.cocomo = Some(CocomoCoefficients::from(&x)),
+                None => {
+                    message_printer::print_help_message_for_command(COCOMO);
+                    return Err(ArgParsingError::IncorrectCommandArgs(COCOMO.to_owned()))
+                }
+            }
+        } else if command_name == SALARY {
+            match utils::parse_usize_value(arguments, MIN_SALARY, MAX_SALARY) {
+                Some(x) => config_builder.salary = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(SALARY);
+                    return Err(ArgParsingError::IncorrectCommandArgs(SALARY.to_owned()))
+                }
+            }
+        } else if command_name == TEST_PATTERNS {
+            let vec = utils::parse_paths_to_vec(arguments);
+            if vec.is_empty() || utils::build_exclude_matcher(&vec).is_err() {
+                message_printer::print_help_message_for_command(TEST_PATTERNS);
+                return Err(ArgParsingError::IncorrectCommandArgs(TEST_PATTERNS.to_owned()));
+            }
+            config_builder.test_patterns = Some(vec);
+        } else if command_name == OWNERS {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(OWNERS);
+                return Err(ArgParsingError::UnexpectedCommandArgs(OWNERS.to_owned()))
+            }
+            config_builder.owners = Some(true);
+        } else if command_name == AUTHORS {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(AUTHORS);
+                return Err(ArgParsingError::UnexpectedCommandArgs(AUTHORS.to_owned()))
+            }
+            config_builder.authors = Some(true);
+        } else if command_name == MAILMAP {
+            let value = arguments.trim();
+            if value.is_empty() || !Path::new(value).is_file() {
+                message_printer::print_help_message_for_command(MAILMAP);
+                return Err(ArgParsingError::IncorrectCommandArgs(MAILMAP.to_owned()))
+            }
+            config_builder.mailmap = Some(convert_to_absolute(value));
+        } else if command_name == HOTSPOTS {
+            let value = arguments.trim();
+            if value.is_empty() {
+                config_builder.hotspots = Some(HotspotMetric::Size);
+            } else {
+                match HotspotMetric::parse(value) {
+                    Some(x) => config_builder.hotspots = Some(x),
+                    None => {
+                        message_printer::print_help_message_for_command(HOTSPOTS);
+                        return Err(ArgParsingError::IncorrectCommandArgs(HOTSPOTS.to_owned()))
+                    }
+                }
+            }
+        } else if command_name == CHURN_WINDOW {
+            match utils::parse_usize_value(arguments, MIN_CHURN_WINDOW, MAX_CHURN_WINDOW) {
+                Some(x) => config_builder.churn_window = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(CHURN_WINDOW);
+                    return Err(ArgParsingError::IncorrectCommandArgs(CHURN_WINDOW.to_owned()))
+                }
+            }
+        } else if command_name == REV {
+            let value = arguments.trim();
+            if value.is_empty() || value.contains(char::is_whitespace) {
+                message_printer::print_help_message_for_command(REV);
+                return Err(ArgParsingError::IncorrectCommandArgs(REV.to_owned()))
+            }
+            config_builder.rev = Some(value.to_owned());
+        } else if command_name == BACKFILL {
+            match Backfill::parse(arguments) {
+                Some(x) => config_builder.backfill = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(BACKFILL);
+                    return Err(ArgParsingError::IncorrectCommandArgs(BACKFILL.to_owned()))
+                }
+            }
+        } else if command_name == DIFF {
+            match DiffTarget::parse_pair(arguments) {
+                Some(x) => config_builder.diff = Some(x),
+                None => {
+                    message_printer::print_help_message_for_command(DIFF);
+                    return Err(ArgParsingError::IncorrectCommandArgs(DIFF.to_owned()))
+                }
+            }
+        } else if command_name == CHANGED_SINCE {
+            let value = arguments.trim();
+            if value.is_empty() || value.contains(char::is_whitespace) {
+                message_printer::print_help_message_for_command(CHANGED_SINCE);
+                return Err(ArgParsingError::IncorrectCommandArgs(CHANGED_SINCE.to_owned()))
+            }
+            config_builder.changed_since = Some(value.to_owned());
+        } else if command_name == STAGED {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(STAGED);
+                return Err(ArgParsingError::UnexpectedCommandArgs(STAGED.to_owned()))
+            }
+            config_builder.staged = Some(true);
+        } else if command_name == FILES_FROM {
+            let value = arguments.trim();
+            if value.is_empty() {
+                message_printer::print_help_message_for_command(FILES_FROM);
+                return Err(ArgParsingError::IncorrectCommandArgs(FILES_FROM.to_owned()))
+            }
+            if value != "-" && !Path::new(value).is_file() {
+                return Err(ArgParsingError::InvalidPath(value.to_owned()))
+            }
+            config_builder.files_from = Some(value.to_owned());
+        } else if command_name == RESPECT_IGNORES {
+            if has_any_args(command) {
+                message_printer::print_help_message_for_command(RESPECT_IGNORES);
+                return Err(ArgParsingError::UnexpectedCommandArgs(RESPECT_IGNORES.to_owned()))
+            }
+            config_builder.respect_ignores = Some(true);
         } else if command_name == COLORS {
             match utils::parse_colors_to_vec(arguments) {
-                Some(x) => colors = Some(x),
+                Some(x) => config_builder.colors = Some(x),
                 None => {
                     message_printer::print_help_message_for_command(COLORS);
                     return Err(ArgParsingError::IncorrectCommandArgs(COLORS.to_owned()))
@@ -236,13 +642,13 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
                 message_printer::print_help_message_for_command(COLOR_PALETTE);
                 return Err(ArgParsingError::IncorrectCommandArgs(COLOR_PALETTE.to_owned()))
             }
-            color_palette = Some(name.to_owned());
+            config_builder.color_palette = Some(name.to_owned());
         } else if command_name == LOG {
             let value = arguments.trim();
             if value.is_empty() {
-                log = Some(LogOption::new(None));
+                config_builder.log = Some(LogOption::new(None));
             } else {
-                log = Some(LogOption::new(Some(value.to_owned())));
+                config_builder.log = Some(LogOption::new(Some(value.to_owned())));
             }
         } else if command_name == COMPRARE_LEVEL {
             let compare_num = utils::parse_usize_value(arguments, MIN_COMPARE_LEVEL, MAX_COMPARE_LEVEL);
@@ -250,7 +656,7 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
                 message_printer::print_help_message_for_command(COMPRARE_LEVEL);
                 return Err(ArgParsingError::IncorrectCommandArgs(COMPRARE_LEVEL.to_owned()))
             } else {
-                compare_level = compare_num
+                config_builder.compare_level = compare_num
             }
         } else if command_name == LOAD {
             let config_name = arguments.trim();
@@ -270,7 +676,7 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
                     }
                 }
                 custom_config = Some((options, invalid_fields));
-                config_name_to_load = Some(config_name.to_owned());
+                config_builder.config_name_to_load = Some(config_name.to_owned());
             } else {
                 return Err(ArgParsingError::NonExistantConfig(config_name.to_owned()))
             }
@@ -280,17 +686,17 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
                 message_printer::print_help_message_for_command(SAVE);
                 return Err(ArgParsingError::IncorrectCommandArgs(SAVE.to_owned()))
             }
-            config_name_to_save = Some(name.to_owned());
+            config_builder.config_name_to_save = Some(name.to_owned());
         } else {
             return Err(ArgParsingError::UnrecognisedCommand(command.to_owned()));
         }
     }
 
-    print_warnings_for_commands_that_need_a_loaded_configuration(&config_name_to_save, &config_name_to_load, &log, &compare_level);
-    
-    let mut config_builder = ConfigurationBuilder::new(dirs, exclude_dirs, languages_of_interest, excluded_languages, threads, braces_as_code,
-        search_in_dotted, show_faulty_files, no_keywords, no_visual, no_gitignore, colors, color_palette, log, compare_level,
-        config_name_to_save, config_name_to_load);
+    print_warnings_for_commands_that_need_a_loaded_configuration(&config_builder);
+    if config_builder.config_name_to_save.is_none() && config_builder.config_name_to_load.is_none() {
+        config_builder.backfill = None;
+    }
+
 
     if let Some((custom, invalid_fields)) = custom_config {
         let config_name = config_builder.config_name_to_load.clone().unwrap_or_default();
@@ -298,6 +704,12 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
         config_builder.add_missing_fields(custom);
     }
 
+    // Like the min and max threads, the range of the file sizes can't be empty
+    if let (Some(min), Some(max)) = (config_builder.min_file_size, config_builder.max_file_size) && min > max {
+        message_printer::print_help_message_for_command(MIN_FILE_SIZE);
+        return Err(ArgParsingError::IncorrectCommandArgs(MIN_FILE_SIZE.to_owned()));
+    }
+
     if let Some(name) = &config_builder.config_name_to_save {
         if config_builder.dirs.is_none() {
             config_builder.dirs = Some(parse_working_dir_as_target_dir()?);
@@ -327,6 +739,27 @@ pub fn create_config_builder_from_args(line: &str) -> Result<ConfigurationBuilde
         config_builder.dirs = Some(parse_working_dir_as_target_dir()?);
     }
 
+    // Each one of them decides on its own which files are counted
+    let file_selections = [(REV, config_builder.rev.is_some()), (BACKFILL, config_builder.backfill.is_some()), (DIFF, config_builder.diff.is_some()),
+            (CHANGED_SINCE, config_builder.changed_since.is_some()), (STAGED, config_builder.staged == Some(true)),
+            (FILES_FROM, config_builder.files_from.is_some())];
+    let selected = file_selections.iter().filter(|x| x.1).map(|x| x.0).collect::<Vec<_>>();
+    if let [a, b, ..] = selected.as_slice() {
+        return Err(ArgParsingError::IncompatibleCommands(a.to_string(), b.to_string()));
+    }
+
+    let diff_revisions = config_builder.diff.iter().flat_map(|(a, b)| [a, b]).filter_map(|x| match x {
+        DiffTarget::Rev(rev) => Some(rev),
+        DiffTarget::Dir(_) => None
+    });
+    for rev in config_builder.rev.iter().chain(diff_revisions).chain(config_builder.changed_since.iter()) {
+        for dir in config_builder.dirs.as_ref().unwrap() {
+            if let Err(x) = git::verify_revision(Path::new(dir), rev) {
+                return Err(ArgParsingError::InvalidRevision(rev.clone(), x));
+            }
+        }
+    }
+
     Ok(config_builder)
 }
 
@@ -342,7 +775,33 @@ fn resolve_invalid_config_fields(config_builder: &ConfigurationBuilder, invalid_
             NO_KEYWORDS => config_builder.no_keywords.is_some(),
             NO_VISUAL => config_builder.no_visual.is_some(),
             NO_GITIGNORE => config_builder.no_gitignore.is_some(),
+            NO_GITATTRIBUTES => config_builder.no_gitattributes.is_some(),
+            FOLLOW_SYMLINKS => config_builder.follow_symlinks.is_some(),
+            MAX_DEPTH => config_builder.max_depth.is_some(),
+            MAX_FILE_SIZE => config_builder.max_file_size.is_some(),
+            MIN_FILE_SIZE => config_builder.min_file_size.is_some(),
+            MODIFIED_SINCE => config_builder.modified_since.is_some(),
+            EXCLUSION_REASONS => config_builder.exclusion_reasons.is_some(),
+            FAIL_ON_TRAVERSAL_ERRORS => config_builder.fail_on_traversal_errors.is_some(),
+            PER_FILE => config_builder.per_file.is_some(),
+            INDENT_WIDTH => config_builder.indent_width.is_some(),
+            HYGIENE => config_builder.hygiene.is_some(),
+            DISTRIBUTION => config_builder.distribution.is_some(),
+            COLUMN_LIMIT => config_builder.column_limit.is_some(),
+            DUPLICATE_FILES => config_builder.duplicate_files.is_some(),
+            DEDUPE_FILES => config_builder.dedupe_files.is_some(),
+            DUPLICATES => config_builder.duplicates.is_some(),
+            ESTIMATION => config_builder.estimation.is_some(),
+            COCOMO => config_builder.cocomo.is_some(),
+            SALARY => config_builder.salary.is_some(),
+            TEST_PATTERNS => config_builder.test_patterns.is_some(),
+            OWNERS => config_builder.owners.is_some(),
+            AUTHORS => config_builder.authors.is_some(),
+            MAILMAP => config_builder.mailmap.is_some(),
+            HOTSPOTS => config_builder.hotspots.is_some(),
+            CHURN_WINDOW => config_builder.churn_window.is_some(),
             EXCLUDE => config_builder.exclude_dirs.is_some(),
+            INCLUDE => config_builder.include_patterns.is_some(),
             COLORS => config_builder.colors.is_some(),
             COLOR_PALETTE => config_builder.color_palette.is_some(),
             _ => false
@@ -359,15 +818,18 @@ fn resolve_invalid_config_fields(config_builder: &ConfigurationBuilder, invalid_
     Ok(())
 }
 
-fn print_warnings_for_commands_that_need_a_loaded_configuration(config_name_to_save: &Option<String>, config_name_to_load: &Option<String>,
-        log: &Option<LogOption>, compare_level: &Option<usize>) 
-{
-    if config_name_to_load.is_none() {
-        if let Some(log) = log && config_name_to_save.is_none() && log.should_log {
+fn print_warnings_for_commands_that_need_a_loaded_configuration(config_builder: &ConfigurationBuilder) {
+    let config_name_to_save = &config_builder.config_name_to_save;
+    if config_builder.config_name_to_load.is_none() {
+        if config_builder.backfill.is_some() && config_name_to_save.is_none() {
+            println!("\n{}","'--backfill' command will be ignored, since no config file was specified.".yellow());
+        }
+
+        if let Some(log) = &config_builder.log && config_name_to_save.is_none() && log.should_log {
             println!("\n{}","'--log' command will be ignored, since no config file was specified.".yellow());
         }
 
-        if compare_level.is_some() {
+        if config_builder.compare_level.is_some() {
             println!("\n{}","'--compare' command will be ignored, since no config file was specified for loading.".yellow());
         }
     }
@@ -451,10 +913,11 @@ fn convert_to_absolute(s: &str) -> String {
 }
 
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Default)]
 pub struct ConfigurationBuilder {
     pub dirs:                     Option<Vec<String>>,
     pub exclude_dirs:             Option<Vec<String>>,
+    pub include_patterns:         Option<Vec<String>>,
     pub languages_of_interest:    Option<Vec<String>>,
     pub excluded_languages:       Option<Vec<String>>,
     pub threads:                  Option<Threads>,
@@ -464,6 +927,38 @@ pub struct ConfigurationBuilder {
     pub no_keywords:              Option<bool>,
     pub no_visual:                Option<bool>,
     pub no_gitignore:             Option<bool>,
+    pub no_gitattributes:         Option<bool>,
+    pub follow_symlinks:          Option<bool>,
+    pub max_depth:                Option<usize>,
+    pub max_file_size:            Option<usize>,
+    pub min_file_size:            Option<usize>,
+    pub modified_since:           Option<ModifiedSince>,
+    pub exclusion_reasons:        Option<bool>,
+    pub fail_on_traversal_errors: Option<bool>,
+    pub per_file:                 Option<usize>,
+    pub indent_width:             Option<usize>,
+    pub hygiene:                  Option<bool>,
+    pub distribution:             Option<bool>,
+    pub column_limit:             Option<usize>,
+    pub duplicate_files:          Option<bool>,
+    pub dedupe_files:             Option<bool>,
+    pub duplicates:               Option<usize>,
+    pub estimation:               Option<bool>,
+    pub cocomo:                   Option<CocomoCoefficients>,
+    pub salary:                   Option<usize>,
+    pub test_patterns:            Option<Vec<String>>,
+    pub owners:                   Option<bool>,
+    pub authors:                  Option<bool>,
+    pub mailmap:                  Option<String>,
+    pub hotspots:                 Option<HotspotMetric>,
+    pub churn_window:             Option<usize>,
+    pub rev:                      Option<String>,
+    pub backfill:                 Option<Backfill>,
+    pub diff:                     Option<(DiffTarget, DiffTarget)>,
+    pub changed_since:            Option<String>,
+    pub staged:                   Option<bool>,
+    pub files_from:               Option<String>,
+    pub respect_ignores:          Option<bool>,
     pub colors:                   Option<Vec<Color>>,
     pub color_palette:            Option<String>,
     pub log:                      Option<LogOption>,
@@ -473,36 +968,10 @@ pub struct ConfigurationBuilder {
 }
 
 impl ConfigurationBuilder {
-    pub fn new(dirs: Option<Vec<String>>, exclude_dirs: Option<Vec<String>>, languages_of_interest: Option<Vec<String>>, excluded_languages: Option<Vec<String>>,
-             threads: Option<Threads>, braces_as_code: Option<bool>, should_search_in_dotted: Option<bool>, should_show_faulty_files: Option<bool>, no_keywords: Option<bool>,
-             no_visual: Option<bool>, no_gitignore: Option<bool>, colors: Option<Vec<Color>>, color_palette: Option<String>, log: Option<LogOption>,
-             compare_level: Option<usize>, config_name_to_save: Option<String>, config_name_to_load: Option<String>)
-    -> ConfigurationBuilder
-    {
-        ConfigurationBuilder {
-            dirs,
-            exclude_dirs,
-            languages_of_interest,
-            excluded_languages,
-            threads,
-            braces_as_code,
-            should_search_in_dotted,
-            should_show_faulty_files,
-            no_keywords,
-            no_visual,
-            no_gitignore,
-            colors,
-            color_palette,
-            log,
-            compare_level,
-            config_name_to_save,
-            config_name_to_load
-        }
-    }
-
     pub fn add_missing_fields(&mut self, config: Self) -> &mut Self {
         if self.dirs.is_none() {self.dirs = config.dirs};
         if self.exclude_dirs.is_none() {self.exclude_dirs = config.exclude_dirs};
+        if self.include_patterns.is_none() {self.include_patterns = config.include_patterns};
         if self.languages_of_interest.is_none() {self.languages_of_interest = config.languages_of_interest};
         if self.excluded_languages.is_none() {self.excluded_languages = config.excluded_languages};
         if self.threads.is_none() {self.threads = config.threads};
@@ -512,6 +981,31 @@ impl ConfigurationBuilder {
         if self.no_keywords.is_none() {self.no_keywords = config.no_keywords};
         if self.no_visual.is_none() {self.no_visual = config.no_visual};
         if self.no_gitignore.is_none() {self.no_gitignore = config.no_gitignore};
+        if self.no_gitattributes.is_none() {self.no_gitattributes = config.no_gitattributes};
+        if self.follow_symlinks.is_none() {self.follow_symlinks = config.follow_symlinks};
+        if self.max_depth.is_none() {self.max_depth = config.max_depth};
+        if self.max_file_size.is_none() {self.max_file_size = config.max_file_size};
+        if self.min_file_size.is_none() {self.min_file_size = config.min_file_size};
+        if self.modified_since.is_none() {self.modified_since = config.modified_since};
+        if self.exclusion_reasons.is_none() {self.exclusion_reasons = config.exclusion_reasons};
+        if self.fail_on_traversal_errors.is_none() {self.fail_on_traversal_errors = config.fail_on_traversal_errors};
+        if self.per_file.is_none() {self.per_file = config.per_file};
+        if self.indent_width.is_none() {self.indent_width = config.indent_width};
+        if self.hygiene.is_none() {self.hygiene = config.hygiene};
+        if self.distribution.is_none() {self.distribution = config.distribution};
+        if self.column_limit.is_none() {self.column_limit = config.column_limit};
+        if self.duplicate_files.is_none() {self.duplicate_files = config.duplicate_files};
+        if self.dedupe_files.is_none() {self.dedupe_files = config.dedupe_files};
+        if self.duplicates.is_none() {self.duplicates = config.duplicates};
+        if self.estimation.is_none() {self.estimation = config.estimation};
+        if self.cocomo.is_none() {self.cocomo = config.cocomo};
+        if self.salary.is_none() {self.salary = config.salary};
+        if self.test_patterns.is_none() {self.test_patterns = config.test_patterns};
+        if self.owners.is_none() {self.owners = config.owners};
+        if self.authors.is_none() {self.authors = config.authors};
+        if self.mailmap.is_none() {self.mailmap = config.mailmap};
+        if self.hotspots.is_none() {self.hotspots = config.hotspots};
+        if self.churn_window.is_none() {self.churn_window = config.churn_window};
         if self.colors.is_none() {self.colors = config.colors};
         if self.color_palette.is_none() {self.color_palette = config.color_palette};
         if self.compare_level.is_none() {self.compare_level = config.compare_level};
@@ -520,10 +1014,18 @@ impl ConfigurationBuilder {
     }
 
     pub fn has_missing_fields(&self) -> bool {
-        self.exclude_dirs.is_none() || self.languages_of_interest.is_none() ||
+        self.exclude_dirs.is_none() || self.include_patterns.is_none() || self.languages_of_interest.is_none() ||
         self.threads.is_none() || self.braces_as_code.is_none() || self.should_search_in_dotted.is_none() ||
         self.should_show_faulty_files.is_none() || self.no_visual.is_none() || self.no_gitignore.is_none() ||
-        self.colors.is_none() || self.color_palette.is_none() || self.log.is_none() || self.compare_level.is_none()
+        self.no_gitattributes.is_none() || self.follow_symlinks.is_none() || self.max_depth.is_none() ||
+        self.max_file_size.is_none() || self.min_file_size.is_none() || self.modified_since.is_none() ||
+        self.exclusion_reasons.is_none() || self.fail_on_traversal_errors.is_none() || self.per_file.is_none() ||
+        self.indent_width.is_none() || self.hygiene.is_none() || self.distribution.is_none() || self.column_limit.is_none() ||
+        self.duplicate_files.is_none() || self.dedupe_files.is_none() || self.duplicates.is_none() ||
+        self.estimation.is_none() || self.cocomo.is_none() || self.salary.is_none() || self.test_patterns.is_none() ||
+        self.owners.is_none() || self.authors.is_none() || self.mailmap.is_none() || self.hotspots.is_none() ||
+        self.churn_window.is_none() || self.colors.is_none() || self.color_palette.is_none() || self.log.is_none() ||
+        self.compare_level.is_none()
     }
 
     pub fn build(&self) -> Configuration {
@@ -531,6 +1033,7 @@ impl ConfigurationBuilder {
             version: VERSION_ID,
             dirs: self.dirs.clone().unwrap(),
             exclude_dirs: (self.exclude_dirs).clone().unwrap_or_default(),
+            include_patterns: (self.include_patterns).clone().unwrap_or_default(),
             languages_of_interest: (self.languages_of_interest).clone().unwrap_or_default(),
             excluded_languages: (self.excluded_languages).clone().unwrap_or_default(),
             threads: self.threads.clone().unwrap_or_default(),
@@ -540,6 +1043,38 @@ impl ConfigurationBuilder {
             no_keywords: self.no_keywords.unwrap_or(DEF_NO_KEYWORDS),
             no_visual: self.no_visual.unwrap_or(DEF_NO_VISUAL),
             no_gitignore: self.no_gitignore.unwrap_or(DEF_NO_GITIGNORE),
+            no_gitattributes: self.no_gitattributes.unwrap_or(DEF_NO_GITATTRIBUTES),
+            follow_symlinks: self.follow_symlinks.unwrap_or(DEF_FOLLOW_SYMLINKS),
+            max_depth: self.max_depth,
+            max_file_size: self.max_file_size,
+            min_file_size: self.min_file_size,
+            modified_since: self.modified_since.clone(),
+            exclusion_reasons: self.exclusion_reasons.unwrap_or(DEF_EXCLUSION_REASONS),
+            fail_on_traversal_errors: self.fail_on_traversal_errors.unwrap_or(DEF_FAIL_ON_TRAVERSAL_ERRORS),
+            per_file: self.per_file.unwrap_or(DEF_PER_FILE),
+            indent_width: self.indent_width.unwrap_or(DEF_INDENT_WIDTH),
+            hygiene: self.hygiene.unwrap_or(DEF_HYGIENE),
+            distribution: self.distribution.unwrap_or(DEF_DISTRIBUTION),
+            column_limit: self.column_limit.unwrap_or(DEF_COLUMN_LIMIT),
+            duplicate_files: self.duplicate_files.unwrap_or(DEF_DUPLICATE_FILES),
+            dedupe_files: self.dedupe_files.unwrap_or(DEF_DEDUPE_FILES),
+            duplicates: self.duplicates.unwrap_or(DEF_DUPLICATES),
+            estimation: self.estimation.unwrap_or(DEF_ESTIMATION),
+            cocomo: self.cocomo.clone().unwrap_or_default(),
+            salary: self.salary.unwrap_or(DEF_SALARY),
+            test_patterns: self.test_patterns.clone().unwrap_or_else(|| DEF_TEST_PATTERNS.iter().map(|x| x.to_string()).collect()),
+            owners: self.owners.unwrap_or(DEF_OWNERS),
+            authors: self.authors.unwrap_or(DEF_AUTHORS),
+            mailmap: self.mailmap.clone(),
+            hotspots: self.hotspots,
+            churn_window: self.churn_window.unwrap_or(DEF_CHURN_WINDOW),
+            rev: self.rev.clone(),
+            backfill: self.backfill.clone(),
+            diff: self.diff.clone(),
+            changed_since: self.changed_since.clone(),
+            staged: self.staged.unwrap_or(DEF_STAGED),
+            files_from: self.files_from.clone(),
+            respect_ignores: self.respect_ignores.unwrap_or(DEF_RESPECT_IGNORES),
             colors: self.colors.clone().unwrap_or_default(),
             log: self.log.clone().unwrap_or_default(),
             compare_level: self.compare_level.unwrap_or(DEF_COMPARE_LEVEL),
@@ -555,6 +1090,7 @@ impl Configuration {
             version: VERSION_ID,
             dirs,
             exclude_dirs: Vec::new(),
+            include_patterns: Vec::new(),
             languages_of_interest: Vec::new(),
             excluded_languages: Vec::new(),
             threads: Threads::default(),
@@ -564,6 +1100,38 @@ impl Configuration {
             no_keywords: DEF_NO_KEYWORDS,
             no_visual: DEF_NO_VISUAL,
             no_gitignore: DEF_NO_GITIGNORE,
+            no_gitattributes: DEF_NO_GITATTRIBUTES,
+            follow_symlinks: DEF_FOLLOW_SYMLINKS,
+            max_depth: None,
+            max_file_size: None,
+            min_file_size: None,
+            modified_since: None,
+            exclusion_reasons: DEF_EXCLUSION_REASONS,
+            fail_on_traversal_errors: DEF_FAIL_ON_TRAVERSAL_ERRORS,
+            per_file: DEF_PER_FILE,
+            indent_width: DEF_INDENT_WIDTH,
+            hygiene: DEF_HYGIENE,
+            distribution: DEF_DISTRIBUTION,
+            column_limit: DEF_COLUMN_LIMIT,
+            duplicate_files: DEF_DUPLICATE_FILES,
+            dedupe_files: DEF_DEDUPE_FILES,
+            duplicates: DEF_DUPLICATES,
+            estimation: DEF_ESTIMATION,
+            cocomo: CocomoCoefficients::default(),
+            salary: DEF_SALARY,
+            test_patterns: DEF_TEST_PATTERNS.iter().map(|x| x.to_string()).collect(),
+            owners: DEF_OWNERS,
+            authors: DEF_AUTHORS,
+            mailmap: None,
+            hotspots: None,
+            churn_window: DEF_CHURN_WINDOW,
+            rev: None,
+            backfill: None,
+            diff: None,
+            changed_since: None,
+            staged: DEF_STAGED,
+            files_from: None,
+            respect_ignores: DEF_RESPECT_IGNORES,
             colors: Vec::new(),
             log: LogOption::default(),
             compare_level: DEF_COMPARE_LEVEL,
@@ -585,6 +1153,11 @@ impl Configuration {
         self
     }
 
+    pub fn set_include_patterns(&mut self, include_patterns: Vec<String>) -> &mut Self {
+        self.include_patterns = include_patterns;
+        self
+    }
+
     pub fn set_languages_of_interest(&mut self, languages_of_interest: Vec<String>) -> &mut Self {
         self.languages_of_interest = languages_of_interest;
         self
@@ -625,6 +1198,166 @@ impl Configuration {
         self
     }
 
+    pub fn set_no_gitattributes(&mut self, no_gitattributes: bool) -> &mut Self {
+        self.no_gitattributes = no_gitattributes;
+        self
+    }
+
+    pub fn set_follow_symlinks(&mut self, follow_symlinks: bool) -> &mut Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) -> &mut Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn set_max_file_size(&mut self, max_file_size: Option<usize>) -> &mut Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    pub fn set_min_file_size(&mut self, min_file_size: Option<usize>) -> &mut Self {
+        self.min_file_size = min_file_size;
+        self
+    }
+
+    pub fn set_modified_since(&mut self, modified_since: Option<ModifiedSince>) -> &mut Self {
+        self.modified_since = modified_since;
+        self
+    }
+
+    pub fn set_exclusion_reasons(&mut self, exclusion_reasons: bool) -> &mut Self {
+        self.exclusion_reasons = exclusion_reasons;
+        self
+    }
+
+    pub fn set_fail_on_traversal_errors(&mut self, fail_on_traversal_errors: bool) -> &mut Self {
+        self.fail_on_traversal_errors = fail_on_traversal_errors;
+        self
+    }
+
+    pub fn set_per_file(&mut self, per_file: usize) -> &mut Self {
+        self.per_file = per_file;
+        self
+    }
+
+    pub fn set_indent_width(&mut self, indent_width: usize) -> &mut Self {
+        self.indent_width = indent_width;
+        self
+    }
+
+    pub fn set_hygiene(&mut self, hygiene: bool) -> &mut Self {
+        self.hygiene = hygiene;
+        self
+    }
+
+    pub fn set_distribution(&mut self, distribution: bool) -> &mut Self {
+        self.distribution = distribution;
+        self
+    }
+
+    pub fn set_column_limit(&mut self, column_limit: usize) -> &mut Self {
+        self.column_limit = column_limit;
+        self
+    }
+
+    pub fn set_duplicate_files(&mut self, duplicate_files: bool) -> &mut Self {
+        self.duplicate_files = duplicate_files;
+        self
+    }
+
+    pub fn set_dedupe_files(&mut self, dedupe_files: bool) -> &mut Self {
+        self.dedupe_files = dedupe_files;
+        self
+    }
+
+    pub fn set_duplicates(&mut self, duplicates: usize) -> &mut Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    pub fn set_estimation(&mut self, estimation: bool) -> &mut Self {
+        self.estimation = estimation;
+        self
+    }
+
+    pub fn set_cocomo(&mut self, a: f64, b: f64, c: f64, d: f64) -> &mut Self {
+        self.cocomo = CocomoCoefficients::new(a, b, c, d);
+        self
+    }
+
+    pub fn set_salary(&mut self, salary: usize) -> &mut Self {
+        self.salary = salary;
+        self
+    }
+
+    pub fn set_test_patterns(&mut self, test_patterns: Vec<String>) -> &mut Self {
+        self.test_patterns = test_patterns;
+        self
+    }
+
+    pub fn set_owners(&mut self, owners: bool) -> &mut Self {
+        self.owners = owners;
+        self
+    }
+
+    pub fn set_authors(&mut self, authors: bool) -> &mut Self {
+        self.authors = authors;
+        self
+    }
+
+    pub fn set_mailmap(&mut self, mailmap: Option<String>) -> &mut Self {
+        self.mailmap = mailmap;
+        self
+    }
+
+    pub fn set_hotspots(&mut self, hotspots: Option<HotspotMetric>) -> &mut Self {
+        self.hotspots = hotspots;
+        self
+    }
+
+    pub fn set_churn_window(&mut self, churn_window: usize) -> &mut Self {
+        self.churn_window = churn_window;
+        self
+    }
+
+    pub fn set_rev(&mut self, rev: Option<String>) -> &mut Self {
+        self.rev = rev;
+        self
+    }
+
+    pub fn set_backfill(&mut self, backfill: Option<Backfill>) -> &mut Self {
+        self.backfill = backfill;
+        self
+    }
+
+    pub fn set_diff(&mut self, diff: Option<(DiffTarget, DiffTarget)>) -> &mut Self {
+        self.diff = diff;
+        self
+    }
+
+    pub fn set_changed_since(&mut self, changed_since: Option<String>) -> &mut Self {
+        self.changed_since = changed_since;
+        self
+    }
+
+    pub fn set_staged(&mut self, staged: bool) -> &mut Self {
+        self.staged = staged;
+        self
+    }
+
+    pub fn set_files_from(&mut self, files_from: Option<String>) -> &mut Self {
+        self.files_from = files_from;
+        self
+    }
+
+    pub fn set_respect_ignores(&mut self, respect_ignores: bool) -> &mut Self {
+        self.respect_ignores = respect_ignores;
+        self
+    }
+
     pub fn set_colors(&mut self, colors: Vec<Color>) -> &mut Self {
         self.colors = colors;
         self
@@ -671,6 +1404,28 @@ impl Default for Threads {
     }
 }
 
+impl CocomoCoefficients {
+    pub fn new(a: f64, b: f64, c: f64, d: f64) -> Self {
+        CocomoCoefficients {
+            a,
+            b,
+            c,
+            d
+        }
+    }
+
+    pub fn from(values: &[f64]) -> Self {
+        CocomoCoefficients::new(values[0], values[1], values[2], values[3])
+    }
+}
+
+// The coefficients of the basic COCOMO model for "organic" projects
+impl Default for CocomoCoefficients {
+    fn default() -> Self {
+        CocomoCoefficients::new(2.4, 1.05, 2.5, 0.38)
+    }
+}
+
 impl LogOption {
     pub fn new(log_name: Option<String>) -> Self {
         LogOption {
@@ -680,6 +1435,95 @@ impl LogOption {
     }
 }
 
+impl Backfill {
+    pub fn parse(s: &str) -> Option<Backfill> {
+        let args = s.split_whitespace().collect::<Vec<_>>();
+        match args.as_slice() {
+            ["tags"] => Some(Backfill::Tags),
+            // A leading dash would be taken by git as an option
+            [range] if !range.starts_with('-') => Some(Backfill::Range(range.to_string(), 1)),
+            [range, step] if !range.starts_with('-') => utils::parse_usize_value(step, MIN_BACKFILL_STEP, MAX_BACKFILL_STEP)
+                    .map(|x| Backfill::Range(range.to_string(), x)),
+            _ => None
+        }
+    }
+}
+
+impl DiffTarget {
+    // An existing path is a dir, anything else is expected to be a revision of the git repositories of the target dirs
+    pub fn parse_pair(s: &str) -> Option<(DiffTarget, DiffTarget)> {
+        let targets = s.split_whitespace().map(|x| {
+            if Path::new(x).exists() {
+                Some(DiffTarget::Dir(convert_to_absolute(x)))
+            } else if !x.starts_with('-') {
+                Some(DiffTarget::Rev(x.to_owned()))
+            } else {
+                None
+            }
+        }).collect::<Option<Vec<_>>>()?;
+
+        match <[DiffTarget; 2]>::try_from(targets) {
+            Ok([a, b]) => Some((a, b)),
+            Err(_) => None
+        }
+    }
+}
+
+impl HotspotMetric {
+    pub fn parse(s: &str) -> Option<HotspotMetric> {
+        match s.trim().to_lowercase().as_str() {
+            "size" => Some(HotspotMetric::Size),
+            "complexity" => Some(HotspotMetric::Complexity),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            HotspotMetric::Size => "size",
+            HotspotMetric::Complexity => "complexity"
+        }
+    }
+}
+
+impl ModifiedSince {
+    // The weeks are kept as days
+    pub fn parse(s: &str) -> Option<ModifiedSince> {
+        let s = s.trim();
+        let period = |suffix: char| s.strip_suffix(suffix).and_then(|x| x.parse::<usize>().ok()).filter(|x| *x > 0);
+        if let Some(hours) = period('h') {
+            return Some(ModifiedSince::Hours(hours));
+        } else if let Some(days) = period('d') {
+            return Some(ModifiedSince::Days(days));
+        } else if let Some(weeks) = period('w') {
+            return Some(ModifiedSince::Days(weeks.checked_mul(7)?));
+        }
+
+        if let Ok(date_time) = DateTime::parse_from_rfc3339(s) {
+            return Some(ModifiedSince::Date(date_time));
+        }
+        let start_of_day = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0)?;
+        Some(ModifiedSince::Date(start_of_day.and_local_timezone(Local).earliest()?.fixed_offset()))
+    }
+
+    pub fn to_config_string(&self) -> String {
+        match self {
+            ModifiedSince::Date(x) => x.to_rfc3339(),
+            ModifiedSince::Days(x) => format!("{x}d"),
+            ModifiedSince::Hours(x) => format!("{x}h")
+        }
+    }
+
+    pub fn cutoff(&self) -> SystemTime {
+        let hours = match self {
+            ModifiedSince::Date(x) => return SystemTime::from(*x),
+            ModifiedSince::Days(x) => *x as u64 * 24,
+            ModifiedSince::Hours(x) => *x as u64
+        };
+        SystemTime::now().checked_sub(Duration::from_secs(hours * 3600)).unwrap_or(SystemTime::UNIX_EPOCH)
+    }
+}
+
 impl Formatted for ArgParsingError {
     fn formatted(&self) -> ColoredString {
         match self {
@@ -693,10 +1537,15 @@ impl Formatted for ArgParsingError {
             Self::IncorrectCommandArgs(p) => format!("Incorrect arguments provided for the command '--{p}'.").red(),
             Self::UnexpectedCommandArgs(p) => format!("Command '--{p}' does not expect any arguments.").red(),
             Self::NonExistantConfig(p) => format!("Configuration '{p}' does not exist.").red(),
-            Self::InvalidValueInConfig(cmd,conf) => format!("Invalid value for the command '--{cmd}', in config '{conf}'.\nFix the value in the config file, or override it by providing a valid '--{cmd}' argument.").red(),
+            Self::InvalidValueInConfig(cmd,conf) => format!("Invalid value for the command '--{cmd}', in config '{conf}'.\n\
+                    Fix the value in the config file, or override it by providing a valid '--{cmd}' argument.").red(),
             Self::InvalidGlobPattern(p) => format!("'{p}' is not a valid glob pattern.").red(),
             Self::NoGlobMatches(p) => format!("The pattern '{p}' did not match any existing directory or file.").red(),
-            Self::AllGlobMatchesIgnored(p) => format!("Everything that the pattern '{p}' matched is skipped, either because a .gitignore file ignores it, or because it is a dotted path.\nUse the '--no-gitignore' or '--search-in-dotted' commands to include it, or provide the paths explicitly.").red()
+            Self::AllGlobMatchesIgnored(p) => format!("Everything that the pattern '{p}' matched is skipped, either because a .gitignore file \
+                    ignores it, or because it is a dotted path.\n\
+                    Use the '--no-gitignore' or '--search-in-dotted' commands to include it, or provide the paths explicitly.").red(),
+            Self::InvalidRevision(rev,reason) => format!("The revision '{rev}' could not be resolved: {reason}").red(),
+            Self::IncompatibleCommands(a,b) => format!("The commands '--{a}' and '--{b}' can't be used together.").red()
         }
     }
 }
@@ -715,8 +1564,7 @@ mod tests {
     }
 
     fn new_conf(dir: &str) -> Configuration {
-        let mut builder = ConfigurationBuilder::new(Some(vec![convert_to_absolute(dir)]), None, None, None, None, None,
-                None, None, None, None, None, None, None, None, None, None, None);
+        let mut builder = ConfigurationBuilder {dirs: Some(vec![convert_to_absolute(dir)]), ..Default::default()};
         if let Ok((default_config, _)) = io_handler::parse_config_file(None, None) {
             builder.add_missing_fields(default_config);
         }
@@ -746,7 +1594,51 @@ mod tests {
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("exclude".to_owned())), create_config_from_args("./ --exclude"));
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("exclude".to_owned())), create_config_from_args("./ --exclude   --threads 4"));
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("exclude".to_owned())), create_config_from_args("./ --exclude [invalid"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("include".to_owned())), create_config_from_args("./ --include src/{a"));
         assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("no-gitignore".to_owned())), create_config_from_args("./ --no-gitignore a"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("per-file".to_owned())), create_config_from_args("./ --per-file a"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("per-file".to_owned())), create_config_from_args("./ --per-file 101"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("indent-width".to_owned())), create_config_from_args("./ --indent-width"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("indent-width".to_owned())), create_config_from_args("./ --indent-width 0"));
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("hygiene".to_owned())), create_config_from_args("./ --hygiene a"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("column-limit".to_owned())), create_config_from_args("./ --column-limit"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("column-limit".to_owned())), create_config_from_args("./ --column-limit 1001"));
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("duplicate-files".to_owned())), create_config_from_args("./ --duplicate-files a"));
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("dedupe-files".to_owned())), create_config_from_args("./ --dedupe-files 2"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("duplicates".to_owned())), create_config_from_args("./ --duplicates"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("duplicates".to_owned())), create_config_from_args("./ --duplicates 101"));
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("estimation".to_owned())), create_config_from_args("./ --estimation a"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("cocomo".to_owned())), create_config_from_args("./ --cocomo 3 1.12 2.5"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("cocomo".to_owned())), create_config_from_args("./ --cocomo 3 1.12 2.5 -0.35"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("salary".to_owned())), create_config_from_args("./ --salary 0"));
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("owners".to_owned())), create_config_from_args("./ --owners a"));
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("authors".to_owned())), create_config_from_args("./ --authors a"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("mailmap".to_owned())), create_config_from_args("./ --mailmap no_such.mailmap"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("hotspots".to_owned())), create_config_from_args("./ --hotspots churn"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("churn-window".to_owned())), create_config_from_args("./ --churn-window 0"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("rev".to_owned())), create_config_from_args("./ --rev"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("rev".to_owned())), create_config_from_args("./ --rev a b"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("backfill".to_owned())), create_config_from_args("./ --backfill"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("backfill".to_owned())), create_config_from_args("./ --backfill HEAD 0"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("backfill".to_owned())), create_config_from_args("./ --backfill -n 2"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("backfill".to_owned())), create_config_from_args("./ --backfill v1..v2 2 3"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("diff".to_owned())), create_config_from_args("./ --diff HEAD"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("diff".to_owned())), create_config_from_args("./ --diff HEAD -n"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("diff".to_owned())), create_config_from_args("./ --diff a b c"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("changed-since".to_owned())), create_config_from_args("./ --changed-since"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("changed-since".to_owned())), create_config_from_args("./ --changed-since a b"));
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("staged".to_owned())), create_config_from_args("./ --staged a"));
+        assert_eq!(Err(ArgParsingError::IncompatibleCommands("rev".to_owned(), "staged".to_owned())), create_config_from_args("./ --staged --rev HEAD"));
+        assert_eq!(*new_conf("./").set_files_from(Some("-".to_owned())),
+                create_config_from_args("./ --files-from - ").unwrap());
+        assert_eq!(Err(ArgParsingError::InvalidPath("missing.txt".to_owned())), create_config_from_args("./ --files-from missing.txt"));
+        assert_eq!(Err(ArgParsingError::IncompatibleCommands("rev".to_owned(), "files-from".to_owned())),
+                create_config_from_args("./ --files-from - --rev HEAD"));
+        assert_eq!(*new_conf("./").set_files_from(Some("-".to_owned())).set_respect_ignores(true),
+                create_config_from_args("./ --files-from - --respect-ignores").unwrap());
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("respect-ignores".to_owned())), create_config_from_args("./ --respect-ignores a"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("test-patterns".to_owned())), create_config_from_args("./ --test-patterns"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("test-patterns".to_owned())), create_config_from_args("./ --test-patterns a[b"));
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("colors".to_owned())), create_config_from_args("./ --colors"));
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("colors".to_owned())), create_config_from_args("./ --colors kaka"));
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("colors".to_owned())), create_config_from_args("./ --colors ff0000 ff0000 ff0000 ff0000 ff0000 ff0000"));
@@ -755,6 +1647,13 @@ mod tests {
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("save".to_owned())), create_config_from_args("./ --save"));
         assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("save".to_owned())), create_config_from_args("./ --save   "));
 
+        assert_eq!(Some(Backfill::Tags), Backfill::parse("tags"));
+        assert_eq!(Some(Backfill::Range("v1..v2".to_owned(), 1)), Backfill::parse("v1..v2"));
+        assert_eq!(Some(Backfill::Range("HEAD".to_owned(), 10)), Backfill::parse(" HEAD  10 "));
+        assert_eq!(Some((DiffTarget::Dir(convert_to_absolute("src")), DiffTarget::Rev("v1.0".to_owned()))), DiffTarget::parse_pair("src v1.0"));
+        // Without a config file there is no log to backfill
+        assert_eq!(None, create_config_from_args("./ --backfill tags").unwrap().backfill);
+
         assert_ne!(new_conf("../"), create_config_from_args(std::env::current_dir().unwrap().to_str().unwrap()).unwrap());
         assert_eq!(new_conf("./"), create_config_from_args(std::env::current_dir().unwrap().to_str().unwrap()).unwrap());
 
@@ -770,6 +1669,57 @@ mod tests {
                 create_config_from_args("./ --no-visual").unwrap());
         assert_eq!(*new_conf("./").set_no_gitignore(true),
                 create_config_from_args("./ --no-gitignore").unwrap());
+        assert_eq!(*new_conf("./").set_no_gitattributes(true),
+                create_config_from_args("./ --no-gitattributes").unwrap());
+        assert_eq!(*new_conf("./").set_follow_symlinks(true),
+                create_config_from_args("./ --follow-symlinks").unwrap());
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("follow-symlinks".to_owned())), create_config_from_args("./ --follow-symlinks a"));
+        assert_eq!(*new_conf("./").set_max_depth(Some(2)).set_max_file_size(Some(1_500_000)).set_min_file_size(Some(10)),
+                create_config_from_args("./ --max-depth 2 --max-file-size 1.5MB --min-file-size 10").unwrap());
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("max-depth".to_owned())), create_config_from_args("./ --max-depth 0"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("max-file-size".to_owned())), create_config_from_args("./ --max-file-size 2 TB"));
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("min-file-size".to_owned())),
+                create_config_from_args("./ --min-file-size 100 --max-file-size 10"));
+        assert_eq!(*new_conf("./").set_max_file_size(Some(10)).set_min_file_size(Some(10)),
+                create_config_from_args("./ --min-file-size 10 --max-file-size 10").unwrap());
+        assert_eq!(*new_conf("./").set_modified_since(Some(ModifiedSince::Days(14))).set_exclusion_reasons(true),
+                create_config_from_args("./ --modified-since 2w --exclusion-reasons").unwrap());
+        assert_eq!(*new_conf("./").set_fail_on_traversal_errors(true),
+                create_config_from_args("./ --fail-on-traversal-errors").unwrap());
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("fail-on-traversal-errors".to_owned())),
+                create_config_from_args("./ --fail-on-traversal-errors yes"));
+        assert_eq!(*new_conf("./").set_modified_since(Some(ModifiedSince::Date(DateTime::parse_from_rfc3339("2024-03-01T12:00:00+02:00").unwrap()))),
+                create_config_from_args("./ --modified-since 2024-03-01T12:00:00+02:00").unwrap());
+        assert_eq!(Err(ArgParsingError::IncorrectCommandArgs("modified-since".to_owned())), create_config_from_args("./ --modified-since yesterday"));
+        assert_eq!(*new_conf("./").set_per_file(10),
+                create_config_from_args("./ --per-file").unwrap());
+        assert_eq!(*new_conf("./").set_per_file(25),
+                create_config_from_args("./ --per-file 25").unwrap());
+        assert_eq!(*new_conf("./").set_indent_width(2),
+                create_config_from_args("./ --indent-width 2").unwrap());
+        assert_eq!(*new_conf("./").set_hygiene(true),
+                create_config_from_args("./ --hygiene").unwrap());
+        assert_eq!(*new_conf("./").set_distribution(true),
+                create_config_from_args("./ --distribution").unwrap());
+        assert_eq!(Err(ArgParsingError::UnexpectedCommandArgs("distribution".to_owned())), create_config_from_args("./ --distribution 10"));
+        assert_eq!(*new_conf("./").set_column_limit(80),
+                create_config_from_args("./ --column-limit 80").unwrap());
+        assert_eq!(*new_conf("./").set_duplicate_files(true).set_dedupe_files(true),
+                create_config_from_args("./ --duplicate-files --dedupe-files").unwrap());
+        assert_eq!(*new_conf("./").set_duplicates(6),
+                create_config_from_args("./ --duplicates 6").unwrap());
+        assert_eq!(*new_conf("./").set_estimation(true).set_cocomo(3.0, 1.12, 2.5, 0.35).set_salary(80000),
+                create_config_from_args("./ --estimation --cocomo 3 1.12 2.5 0.35 --salary 80000").unwrap());
+        assert_eq!(*new_conf("./").set_test_patterns(vec!["*_spec.lua".to_owned(), "**/testdata/**".to_owned()]),
+                create_config_from_args("./ --test-patterns *_spec.lua, **/testdata/**").unwrap());
+        assert_eq!(*new_conf("./").set_owners(true),
+                create_config_from_args("./ --owners").unwrap());
+        assert_eq!(*new_conf("./").set_authors(true),
+                create_config_from_args("./ --authors").unwrap());
+        assert_eq!(*new_conf("./").set_hotspots(Some(HotspotMetric::Size)),
+                create_config_from_args("./ --hotspots").unwrap());
+        assert_eq!(*new_conf("./").set_hotspots(Some(HotspotMetric::Complexity)).set_churn_window(30),
+                create_config_from_args("./ --hotspots complexity --churn-window 30").unwrap());
         assert_eq!(*new_conf("./").set_colors(vec![Color::TrueColor{r:255,g:136,b:0}, Color::BrightCyan]),
                 create_config_from_args("./ --colors ff8800 bright-cyan").unwrap());
         assert_eq!(*new_conf("./").set_should_show_faulty_files(true),
@@ -778,6 +1728,8 @@ mod tests {
                 create_config_from_args("./ --exclude a,b ,  c ").unwrap());
         assert_eq!(*new_conf("./").set_exclude_dirs(vec!["a/path".to_owned(),"b/path".to_owned()]),
                 create_config_from_args("./ --exclude \"a\\path\", \"b\\path\"").unwrap());
+        assert_eq!(*new_conf("./").set_include_patterns(vec!["src/**".to_owned(),"lib/**/*.rs".to_owned()]),
+                create_config_from_args("./ --include src/**, lib/**/*.rs").unwrap());
         assert_eq!(*new_conf("./").set_languages_of_interest(vec!["a".to_owned(),"b".to_owned(),"c".to_owned()]),
                 create_config_from_args("./ --languages a,b,c").unwrap());
         assert_eq!(*new_conf("./").set_languages_of_interest(vec!["a".to_owned()]),
@@ -4,21 +4,23 @@ use crossbeam_deque::Steal;
 
 use crate::*;
 
-pub fn start_parser_thread(id: usize, files_injector: Arc<Injector<ParsableFile>>, faulty_files: FaultyFilesListMut, finish_condition: Arc<AtomicBool>,
-        languages_content_info: ContentInfoMapMut, language_map: Arc<HashMap<String,Language>>, config: Arc<Configuration>) -> JoinHandle<()>
+pub fn start_parser_thread(id: usize, files_injector: Arc<Injector<ParsableFile>>, finish_condition: Arc<AtomicBool>, context: Arc<ParsingContext>)
+-> JoinHandle<()>
 {
     thread::Builder::new().name(id.to_string()).spawn(move || {
-        start_parsing_files(id, files_injector, faulty_files, finish_condition, languages_content_info, language_map, config);
+        start_parsing_files(id, files_injector, finish_condition, &context);
     }).unwrap()
 }
 
-pub fn start_parsing_files(_id: usize, files_injector: Arc<Injector<ParsableFile>>, faulty_files: FaultyFilesListMut, finish_condition: Arc<AtomicBool>,
-    languages_content_info: ContentInfoMapMut, language_map: Arc<HashMap<String,Language>>, config: Arc<Configuration>) 
-{
+pub fn start_parsing_files(_id: usize, files_injector: Arc<Injector<ParsableFile>>, finish_condition: Arc<AtomicBool>, context: &ParsingContext) {
+    let ParsingContext {config, language_map, faulty_files, languages_content_info, files_details, file_hashes, files_fingerprints} = context;
     let mut buf = String::with_capacity(150);
     let mut idle_iterations = 0u32;
     let mut keyword_matchers: HashMap<String, Option<file_parser::KeywordMatcher>> = HashMap::new();
+    let mut test_file_matchers: HashMap<String, globset::GlobSet> = HashMap::new();
     let mut local_content_info: HashMap<String, LanguageContentInfo> = HashMap::new();
+    let mut local_files_details: Vec<FileDetails> = Vec::new();
+    let mut local_files_fingerprints: Vec<FileFingerprints> = Vec::new();
     // let mut share = 0;
     loop {
         match files_injector.steal() {
@@ -34,16 +36,53 @@ pub fn start_parsing_files(_id: usize, files_injector: Arc<Injector<ParsableFile
                     keyword_matchers.insert(lang_name.to_owned(), built);
                 }
                 let keyword_matcher = keyword_matchers.get(lang_name).unwrap().as_ref();
-                match file_parser::parse_file(&parsable_file.path, lang_name, &mut buf, language_map.clone(), keyword_matcher, &config) {
-                    Ok(x) => {
-                        let keywords = &language_map.get(lang_name).unwrap().keywords;
+                let test_file_matcher = test_file_matchers.entry(lang_name.to_owned()).or_insert_with(|| {
+                    let language = language_map.get(lang_name).unwrap();
+                    let patterns = [language.test_patterns.as_slice(), config.test_patterns.as_slice()].concat();
+                    build_exclude_matcher(&patterns).unwrap_or_else(|_| globset::GlobSet::empty())
+                });
+                let parse_result = match &parsable_file.contents {
+                    Some(contents) => file_parser::parse_contents(contents, lang_name, language_map.clone(), keyword_matcher, config),
+                    None => file_parser::parse_file(&parsable_file.path, lang_name, &mut buf, language_map.clone(), keyword_matcher, config)
+                };
+                match parse_result {
+                    Ok(mut x) => {
+                        // Only the path inside the target dir, so that the dirs above it, like a parent 'tests' dir, don't match
+                        if test_file_matcher.is_match(get_path_inside_target_dir(&parsable_file.path, &config.dirs)) {
+                            x.test_code_lines = x.code_lines;
+                        }
+                        if let Some(content_hash) = x.content_hash {
+                            let mut file_hashes_guard = file_hashes.lock().unwrap();
+                            let paths = file_hashes_guard.entry((parsable_file.language_name.clone(), content_hash)).or_default();
+                            paths.push(parsable_file.path.clone());
+                            // The stats of the copies are identical, so it doesn't matter which one of them is counted
+                            if config.dedupe_files && paths.len() > 1 {
+                                continue;
+                            }
+                        }
+                        if !x.code_line_fingerprints.is_empty() {
+                            local_files_fingerprints.push(FileFingerprints::new(parsable_file.path.clone(), parsable_file.language_name.clone(),
+                                    std::mem::take(&mut x.code_line_fingerprints)));
+                        }
+                        if config.per_file > 0 || config.hygiene || config.owners || config.authors || config.hotspots.is_some()
+                                || config.diff.is_some() {
+                            let mut details = FileDetails::new(parsable_file.path.clone(), parsable_file.language_name.clone(), &x);
+                            if config.authors {
+                                details.authors = authors::blame(&parsable_file.path, config.rev.as_deref(), config.mailmap.as_deref(),
+                                        &x.code_line_numbers).ok();
+                            }
+                            local_files_details.push(details);
+                        }
+                        let language = language_map.get(lang_name).unwrap();
                         match local_content_info.get_mut(lang_name) {
-                            Some(info) => info.add_file_stats(x, keywords),
-                            None => { local_content_info.insert(lang_name.to_owned(), LanguageContentInfo::from_file_stats(x, keywords)); }
+                            Some(info) => info.add_file_stats(x, language),
+                            None => { local_content_info.insert(lang_name.to_owned(), LanguageContentInfo::from_file_stats(x, language)); }
                         }
                     },
                     Err(x) => faulty_files.lock().unwrap().push(FaultyFileDetails::new(
-                            parsable_file.path.to_str().unwrap().to_owned(),x,parsable_file.path.metadata().map_or(0, |m| m.len())))
+                            parsable_file.path.to_str().unwrap().to_owned(),x,parsable_file.contents.as_ref().map_or_else(
+                                    || parsable_file.path.metadata().map_or(0, |m| m.len()), |x| x.len() as u64),
+                            parsable_file.language_name.clone()))
                 }
             },
             Steal::Retry => {
@@ -70,5 +109,11 @@ pub fn start_parsing_files(_id: usize, files_injector: Arc<Injector<ParsableFile
             global_content_info_guard.get_mut(lang_name).unwrap().add_content_info(info);
         }
     }
+    if !local_files_details.is_empty() {
+        files_details.lock().unwrap().append(&mut local_files_details);
+    }
+    if !local_files_fingerprints.is_empty() {
+        files_fingerprints.lock().unwrap().append(&mut local_files_fingerprints);
+    }
     // println!("Thread {} finished, having done {} files.",_id,share);
 }
@@ -0,0 +1,100 @@
+use crate::*;
+
+// The stats of one of the two targets of a diff
+#[derive(Debug,Default)]
+pub struct DiffTargetStats {
+    pub content_info_map: HashMap<String,LanguageContentInfo>,
+    pub languages_metadata_map: HashMap<String,LanguageMetadata>,
+    // By their paths relative to the counted dirs
+    pub files: HashMap<PathBuf,(Arc<str>,Option<ContentHash>)>
+}
+
+#[derive(Debug,PartialEq)]
+pub struct LanguageDiff {
+    pub language_name: String,
+    pub added_files: usize,
+    pub removed_files: usize,
+    pub changed_files: usize,
+    pub old_metadata: LanguageMetadata,
+    pub new_metadata: LanguageMetadata,
+    pub old_content_info: LanguageContentInfo,
+    pub new_content_info: LanguageContentInfo
+}
+
+impl LanguageDiff {
+    pub fn has_changes(&self) -> bool {
+        self.added_files + self.removed_files + self.changed_files != 0
+    }
+
+    pub fn lines_delta(&self) -> i64 {
+        self.new_content_info.lines as i64 - self.old_content_info.lines as i64
+    }
+
+    pub fn code_lines_delta(&self) -> i64 {
+        self.new_content_info.code_lines as i64 - self.old_content_info.code_lines as i64
+    }
+}
+
+// Both targets are expected to have the content info and the metadata of every language. A file whose language
+// is different in the two targets counts as removed from the old language and added to the new one.
+pub fn find_language_diffs(mut old: DiffTargetStats, mut new: DiffTargetStats) -> Vec<LanguageDiff> {
+    let mut language_diffs = HashMap::new();
+    for (path, (language_name, content_hash)) in new.files.iter() {
+        let language_diff = language_diffs.entry(language_name.clone()).or_insert((0,0,0));
+        match old.files.get(path) {
+            Some((old_language_name, old_content_hash)) if old_language_name == language_name => {
+                if old_content_hash != content_hash {
+                    language_diff.2 += 1;
+                }
+            },
+            _ => language_diff.0 += 1
+        }
+    }
+    for (path, (language_name, _)) in old.files.iter() {
+        if new.files.get(path).is_none_or(|x| x.0 != *language_name) {
+            language_diffs.entry(language_name.clone()).or_insert((0,0,0)).1 += 1;
+        }
+    }
+
+    let mut language_diffs = new.languages_metadata_map.keys().cloned().collect::<Vec<_>>().into_iter().filter_map(|language_name| {
+        let (new_metadata, new_content_info) = (new.languages_metadata_map.remove(&language_name)?, new.content_info_map.remove(&language_name)?);
+        let (old_metadata, old_content_info) = (old.languages_metadata_map.remove(&language_name)?, old.content_info_map.remove(&language_name)?);
+        if old_metadata.files == 0 && new_metadata.files == 0 {
+            return None;
+        }
+
+        let (added_files, removed_files, changed_files) = language_diffs.get(language_name.as_str()).copied().unwrap_or_default();
+        Some(LanguageDiff{language_name, added_files, removed_files, changed_files, old_metadata, new_metadata, old_content_info, new_content_info})
+    }).collect::<Vec<_>>();
+    // The languages that changed the most first
+    language_diffs.sort_by(|a,b| b.lines_delta().abs().cmp(&a.lines_delta().abs())
+            .then_with(|| (b.added_files + b.removed_files + b.changed_files).cmp(&(a.added_files + a.removed_files + a.changed_files)))
+            .then_with(|| a.language_name.cmp(&b.language_name)));
+
+    language_diffs
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_are_matched_by_path_and_language() {
+        let content_hash = |x: &str| Some(ContentHash::of(x));
+        let target_stats = |files: &[(&str, &str, &str)], lines: usize| {
+            DiffTargetStats {
+                content_info_map: HashMap::from([("Python".to_owned(), LanguageContentInfo::new(lines, lines, HashMap::new()))]),
+                languages_metadata_map: HashMap::from([("Python".to_owned(), LanguageMetadata::new(files.len(), 0))]),
+                files: files.iter().map(|(path, lang, contents)| (PathBuf::from(path), (Arc::from(*lang), content_hash(contents)))).collect()
+            }
+        };
+        let old = target_stats(&[("a.py", "Python", "x = 1"), ("b.py", "Python", "y = 1"), ("c.py", "Python", "z = 1"), ("d.py", "Python", "w = 1")], 4);
+        let new = target_stats(&[("a.py", "Python", "x = 1"), ("b.py", "Python", "y = 2"), ("e.py", "Python", "v = 1"), ("d.py", "Other", "w = 1")], 10);
+
+        let language_diffs = find_language_diffs(old, new);
+        assert_eq!(1, language_diffs.len());
+        assert_eq!((1, 2, 1, 6), (language_diffs[0].added_files, language_diffs[0].removed_files, language_diffs[0].changed_files,
+                language_diffs[0].lines_delta()));
+    }
+}
@@ -0,0 +1,201 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::*;
+
+#[derive(Debug,PartialEq,Default)]
+pub struct DuplicatedCode {
+    // Only the languages with at least one duplicated line are present
+    pub duplicated_lines: HashMap<String,usize>,
+    // Sorted by the code lines that the extra copies add, descending
+    pub blocks: Vec<DuplicatedBlock>
+}
+
+// A block of consecutive code lines that appears in more than one location
+#[derive(Debug,PartialEq)]
+pub struct DuplicatedBlock {
+    pub code_lines: usize,
+    pub locations: Vec<BlockLocation>
+}
+
+#[derive(Debug,PartialEq)]
+pub struct BlockLocation {
+    pub path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize
+}
+
+// (file index, window index)
+type WindowLocation = (usize,usize);
+
+impl DuplicatedBlock {
+    pub fn extra_code_lines(&self) -> usize {
+        self.code_lines * (self.locations.len() - 1)
+    }
+
+    fn contains(&self, other: &DuplicatedBlock) -> bool {
+        other.locations.iter().all(|x| self.locations.iter()
+                .any(|y| x.path == y.path && x.start_line >= y.start_line && x.end_line <= y.end_line))
+    }
+}
+
+// Every window of min_lines consecutive code lines is fingerprinted, and the windows that appear more than once
+// are duplicated. The windows that follow each other in all the locations of a block are merged into it, as long
+// as the locations of the same file don't overlap.
+pub fn find_duplicated_code(files_fingerprints: &[FileFingerprints], min_lines: usize) -> DuplicatedCode {
+    if min_lines == 0 {
+        return DuplicatedCode::default();
+    }
+
+    let window_hashes = files_fingerprints.iter().map(|file| get_window_hashes(&file.lines, min_lines)).collect::<Vec<_>>();
+    let mut window_locations: HashMap<u64,Vec<WindowLocation>> = HashMap::new();
+    for (file_index, hashes) in window_hashes.iter().enumerate() {
+        for (window_index, hash) in hashes.iter().enumerate() {
+            window_locations.entry(*hash).or_default().push((file_index, window_index));
+        }
+    }
+    // The windows that overlap an earlier one of the same file aren't copies of it, like the windows of a run of
+    // identical lines, so they are dropped. The locations are sorted, so only the consecutive ones may overlap.
+    for locations in window_locations.values_mut() {
+        let mut last_kept: Option<WindowLocation> = None;
+        locations.retain(|(file_index, window_index)| {
+            let overlaps = last_kept.is_some_and(|(x, y)| x == *file_index && *window_index < y + min_lines);
+            if !overlaps {
+                last_kept = Some((*file_index, *window_index));
+            }
+            !overlaps
+        });
+    }
+    let are_disjoint = |locations: &[WindowLocation], lines: usize| locations.windows(2).all(|x| x[0].0 != x[1].0 || x[1].1 - x[0].1 >= lines);
+
+    let mut is_line_duplicated = files_fingerprints.iter().map(|x| vec![false; x.lines.len()]).collect::<Vec<_>>();
+    for (file_index, window_index) in window_locations.values().filter(|x| x.len() > 1).flatten() {
+        is_line_duplicated[*file_index][*window_index..*window_index + min_lines].fill(true);
+    }
+    let mut duplicated_lines = HashMap::new();
+    for (file, is_line_duplicated) in files_fingerprints.iter().zip(is_line_duplicated.iter()) {
+        let lines = is_line_duplicated.iter().filter(|x| **x).count();
+        if lines > 0 {
+            *duplicated_lines.entry(file.language_name.to_string()).or_insert(0) += lines;
+        }
+    }
+
+    let shifted = |locations: &[WindowLocation], forward: bool| -> Option<Vec<WindowLocation>> {
+        locations.iter().map(|(file_index, window_index)| {
+            let window_index = if forward {window_index + 1} else {window_index.checked_sub(1)?};
+            (window_index < window_hashes[*file_index].len()).then_some((*file_index, window_index))
+        }).collect()
+    };
+    let locations_of_window = |(file_index, window_index): WindowLocation| window_locations.get(&window_hashes[file_index][window_index]);
+
+    let mut blocks = Vec::new();
+    for locations in window_locations.values().filter(|x| x.len() > 1) {
+        // The block was already found through its first window
+        if let Some(previous) = shifted(locations, false)
+            && locations_of_window(previous[0]) == Some(&previous) {
+            continue;
+        }
+
+        // The next windows may also be duplicated in other locations, that form their own blocks
+        let mut last_locations = locations.clone();
+        while let Some(next) = shifted(&last_locations, true)
+            && let Some(next_window_locations) = locations_of_window(next[0])
+            && next.iter().all(|x| next_window_locations.binary_search(x).is_ok())
+            && are_disjoint(locations, next[0].1 - locations[0].1 + min_lines) {
+            last_locations = next;
+        }
+
+        let block_locations = locations.iter().zip(last_locations.iter()).map(|((file_index, first_window), (_, last_window))| {
+            let lines = &files_fingerprints[*file_index].lines;
+            BlockLocation {
+                path: files_fingerprints[*file_index].path.clone(),
+                start_line: lines[*first_window].line_number,
+                end_line: lines[last_window + min_lines - 1].line_number
+            }
+        }).collect();
+        blocks.push(DuplicatedBlock { code_lines: last_locations[0].1 - locations[0].1 + min_lines, locations: block_locations });
+    }
+    blocks.sort_by(|a,b| b.extra_code_lines().cmp(&a.extra_code_lines())
+            .then_with(|| (&a.locations[0].path, a.locations[0].start_line).cmp(&(&b.locations[0].path, b.locations[0].start_line))));
+
+    // A block that is contained in a bigger one is sorted after it
+    let mut maximal_blocks: Vec<DuplicatedBlock> = Vec::new();
+    for block in blocks {
+        if !maximal_blocks.iter().any(|x| x.contains(&block)) {
+            maximal_blocks.push(block);
+        }
+    }
+
+    DuplicatedCode {
+        duplicated_lines,
+        blocks: maximal_blocks
+    }
+}
+
+fn get_window_hashes(lines: &[LineFingerprint], min_lines: usize) -> Vec<u64> {
+    lines.windows(min_lines).map(|window| {
+        let mut hasher = DefaultHasher::new();
+        window.iter().for_each(|line| line.hash.hash(&mut hasher));
+        hasher.finish()
+    }).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_file(path: &str, language_name: &str, lines: &[&str]) -> FileFingerprints {
+        let fingerprints = lines.iter().enumerate().map(|(index, line)| LineFingerprint::of(line, index * 2 + 1)).collect();
+        FileFingerprints::new(PathBuf::from(path), Arc::from(language_name), fingerprints)
+    }
+
+    #[test]
+    fn test_finds_duplicated_blocks_across_files() {
+        let files = vec![
+            make_file("a.rs", "Rust", &["let a = 1;", "let b = 2;", "let c = 3;", "let d = 4;", "unique_a();"]),
+            make_file("b.rs", "Rust", &["unique_b();", "let  a = 1;", "let b = 2;", "let c = 3;", "let d =   4;"]),
+            make_file("c.rs", "Rust", &["let b = 2;", "let c = 3;", "other();"]),
+            make_file("d.py", "Python", &["x = 1", "y = 2"])
+        ];
+
+        let duplicated_code = find_duplicated_code(&files, 3);
+        assert_eq!(hashmap!["Rust".to_owned() => 8], duplicated_code.duplicated_lines);
+        assert_eq!(vec![DuplicatedBlock { code_lines: 4, locations: vec![
+            BlockLocation { path: PathBuf::from("a.rs"), start_line: 1, end_line: 7 },
+            BlockLocation { path: PathBuf::from("b.rs"), start_line: 3, end_line: 9 }
+        ]}], duplicated_code.blocks);
+
+        let duplicated_code = find_duplicated_code(&files, 2);
+        assert_eq!(hashmap!["Rust".to_owned() => 10], duplicated_code.duplicated_lines);
+        assert_eq!(vec![(4,2), (2,3)], duplicated_code.blocks.iter().map(|x| (x.code_lines, x.locations.len())).collect::<Vec<_>>());
+
+        assert_eq!(DuplicatedCode::default(), find_duplicated_code(&files, 5));
+        assert_eq!(DuplicatedCode::default(), find_duplicated_code(&files, 0));
+    }
+
+    #[test]
+    fn test_finds_repeated_blocks_in_the_same_file() {
+        let files = vec![make_file("a.rs", "Rust", &["f();", "g();", "h();", "f();", "g();", "h();", "f();"])];
+
+        // The block stops before its copies overlap, while its shifted copies (g h f) aren't reported again
+        let duplicated_code = find_duplicated_code(&files, 3);
+        assert_eq!(hashmap!["Rust".to_owned() => 7], duplicated_code.duplicated_lines);
+        assert_eq!(vec![(3,2)], duplicated_code.blocks.iter().map(|x| (x.code_lines, x.locations.len())).collect::<Vec<_>>());
+        assert_eq!((1,5), (duplicated_code.blocks[0].locations[0].start_line, duplicated_code.blocks[0].locations[0].end_line));
+        assert_eq!((7,11), (duplicated_code.blocks[0].locations[1].start_line, duplicated_code.blocks[0].locations[1].end_line));
+    }
+
+    #[test]
+    fn test_the_overlapping_windows_of_a_run_of_identical_lines_are_not_copies() {
+        let files = vec![make_file("a.rs", "Rust", &["x();"; 8])];
+        assert_eq!(DuplicatedCode::default(), find_duplicated_code(&files, 6));
+
+        let files = vec![make_file("a.rs", "Rust", &["x();"; 13])];
+        let duplicated_code = find_duplicated_code(&files, 6);
+        assert_eq!(hashmap!["Rust".to_owned() => 12], duplicated_code.duplicated_lines);
+        assert_eq!(vec![DuplicatedBlock { code_lines: 6, locations: vec![
+            BlockLocation { path: PathBuf::from("a.rs"), start_line: 1, end_line: 11 },
+            BlockLocation { path: PathBuf::from("a.rs"), start_line: 13, end_line: 23 }
+        ]}], duplicated_code.blocks);
+    }
+}
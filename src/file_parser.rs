@@ -13,6 +13,10 @@ pub struct LanguageFinders {
     comment_finders: Vec<memmem::Finder<'static>>,
     multiline_start_finder: Option<memmem::Finder<'static>>,
     multiline_end_finder: Option<memmem::Finder<'static>>,
+    function_finders: Vec<memmem::Finder<'static>>,
+    branch_finders: Vec<memmem::Finder<'static>>,
+    block_start_finder: Option<memmem::Finder<'static>>,
+    block_end_finder: Option<memmem::Finder<'static>>,
 }
 
 impl LanguageFinders {
@@ -22,6 +26,10 @@ impl LanguageFinders {
             comment_finders: language.comment_symbols.iter().map(|s| memmem::Finder::new(s.as_str()).into_owned()).collect(),
             multiline_start_finder: language.multiline_comment_start_symbol.as_ref().map(|s| memmem::Finder::new(s.as_str()).into_owned()),
             multiline_end_finder: language.multiline_comment_end_symbol.as_ref().map(|s| memmem::Finder::new(s.as_str()).into_owned()),
+            function_finders: language.function_declarations.iter().map(|s| memmem::Finder::new(s.as_str()).into_owned()).collect(),
+            branch_finders: language.branch_tokens.iter().map(|s| memmem::Finder::new(s.as_str()).into_owned()).collect(),
+            block_start_finder: language.block_symbols.first().map(|s| memmem::Finder::new(s.as_str()).into_owned()),
+            block_end_finder: language.block_symbols.get(1).map(|s| memmem::Finder::new(s.as_str()).into_owned()),
         }
     }
 }
@@ -32,6 +40,7 @@ fn finders_of(language: &Language) -> &LanguageFinders {
 
 pub struct KeywordMatcher {
     aliases_with_indices: Vec<(memmem::Finder<'static>, usize, usize)>,
+    comment_aliases_with_indices: Vec<(memmem::Finder<'static>, usize)>,
 }
 
 impl KeywordMatcher {
@@ -42,10 +51,16 @@ impl KeywordMatcher {
                 aliases_with_indices.push((memmem::Finder::new(alias.as_str()).into_owned(), alias.len(), keyword_index));
             }
         }
-        if aliases_with_indices.is_empty() {
+        let mut comment_aliases_with_indices = Vec::new();
+        for (keyword_index, keyword) in language.comment_keywords.iter().enumerate() {
+            for alias in &keyword.aliases {
+                comment_aliases_with_indices.push((memmem::Finder::new(alias.as_str()).into_owned(), keyword_index));
+            }
+        }
+        if aliases_with_indices.is_empty() && comment_aliases_with_indices.is_empty() {
             None
         } else {
-            Some(KeywordMatcher { aliases_with_indices })
+            Some(KeywordMatcher { aliases_with_indices, comment_aliases_with_indices })
         }
     }
 }
@@ -65,7 +80,7 @@ pub fn parse_file(path: &Path, lang_name: &str, buf: &mut String, language_map:
         return Err(x.to_string());
     }
 
-    let file_stats = parse_lines(buf, language_map.get(lang_name).unwrap(), keyword_matcher, config);
+    let file_stats = parse_contents_of(buf, language_map.get(lang_name).unwrap(), keyword_matcher, config);
 
     if buf.capacity() > MAX_RETAINED_FILE_BUFFER_BYTES {
         *buf = String::new();
@@ -74,17 +89,50 @@ pub fn parse_file(path: &Path, lang_name: &str, buf: &mut String, language_map:
     Ok(file_stats)
 }
 
+// For the files that are not read from the filesystem, like the blobs of a git revision
+pub fn parse_contents(contents: &[u8], lang_name: &str, language_map: Arc<HashMap<String,Language>>,
+    keyword_matcher: Option<&KeywordMatcher>, config: &Configuration)
+-> Result<FileStats,String>
+{
+    let contents = match str::from_utf8(contents) {
+        Ok(x) => x,
+        Err(x) => return Err(x.to_string())
+    };
+
+    Ok(parse_contents_of(contents, language_map.get(lang_name).unwrap(), keyword_matcher, config))
+}
+
+fn parse_contents_of(contents: &str, language: &Language, keyword_matcher: Option<&KeywordMatcher>, config: &Configuration) -> FileStats {
+    let mut file_stats = parse_lines(contents, language, keyword_matcher, config);
+    if config.duplicate_files || config.dedupe_files || config.diff.is_some() {
+        file_stats.content_hash = Some(ContentHash::of(contents));
+    }
+
+    file_stats
+}
+
 fn parse_lines(contents: &str, language: &Language, keyword_matcher: Option<&KeywordMatcher>, config: &Configuration)
 -> FileStats
 {
     let mut file_stats = match config.no_keywords {
         true => FileStats::default(),
-        false => FileStats::with_keywords(&language.keywords)
+        false => FileStats::with_all_keywords_of(language)
     };
     let mut is_comment_closed = true;
     let mut open_str_symbol = None;
-    for raw_line in contents.lines() {
+    let mut block_depth = 0;
+    let mut test_block = None;
+    let mut hygiene = config.hygiene.then(FileHygiene::default);
+    // Not str::lines(), so that the line endings are still visible for the hygiene stats
+    for line_with_ending in contents.split_inclusive('\n') {
+        let raw_line = strip_line_ending(line_with_ending);
         file_stats.incr_lines();
+        if let Some(hygiene) = &mut hygiene {
+            hygiene.add_line(line_with_ending, raw_line);
+        }
+        if is_long_line(raw_line, config.column_limit, config.indent_width) {
+            file_stats.incr_long_lines();
+        }
 
         let line = raw_line.trim();
         if line.is_empty() { continue; }
@@ -98,25 +146,80 @@ fn parse_lines(contents: &str, language: &Language, keyword_matcher: Option<&Key
             get_bounds_only_single_line_comments(line, language, &open_str_symbol)
         };
 
+        if !config.no_keywords && let Some(matcher) = keyword_matcher && !matcher.comment_aliases_with_indices.is_empty()
+            && (!is_comment_closed || line_info.is_comment_open_after || may_contain_comment(line, language))
+        {
+            add_comment_keywords_if_any(line, line_info.cleansed_string.as_deref(), matcher, &mut file_stats);
+        }
+
         is_comment_closed = !line_info.is_comment_open_after;
         open_str_symbol = line_info.open_str_sybol_after;
 
         if let Some(x) = line_info.cleansed_string {
             let cleansed = x.trim();
+            let depth_before = block_depth;
+            let depth = get_nesting_depth(raw_line, cleansed, language, &mut block_depth, config.indent_width);
+            let is_test_line = test_block.is_some() || starts_test_block(cleansed, language);
+            if is_test_line {
+                test_block = get_test_block_after(test_block, cleansed, language, depth_before, block_depth);
+            }
             if config.braces_as_code || cleansed.len() > 2 || (cleansed != "{" && cleansed != "}" && cleansed != "};") {
                 file_stats.incr_code_lines();
+                if is_test_line {
+                    file_stats.incr_test_code_lines();
+                }
+                file_stats.add_code_line_depth(depth);
+                add_functions_if_any(cleansed, language, &mut file_stats);
+                add_complexity_if_any(cleansed, language, &mut file_stats);
+                if config.duplicates > 0 {
+                    file_stats.code_line_fingerprints.push(LineFingerprint::of(cleansed, file_stats.lines));
+                }
+                if config.authors {
+                    file_stats.code_line_numbers.push(file_stats.lines);
+                }
                 if !config.no_keywords && let Some(matcher) = keyword_matcher {
                     add_keywords_if_any(cleansed, matcher, &mut file_stats);
                 }
             }
         } else {
-            if line_info.has_string_literal {file_stats.incr_code_lines();}
+            if line_info.has_string_literal {
+                file_stats.incr_code_lines();
+                if test_block.is_some() {
+                    file_stats.incr_test_code_lines();
+                }
+                file_stats.add_code_line_depth(get_nesting_depth(raw_line, "", language, &mut block_depth, config.indent_width));
+                if config.authors {
+                    file_stats.code_line_numbers.push(file_stats.lines);
+                }
+            }
         }
     }
 
+    if let Some(mut hygiene) = hygiene {
+        hygiene.has_final_newline = contents.is_empty() || contents.ends_with('\n');
+        file_stats.hygiene = Some(hygiene);
+    }
+
     file_stats
 }
 
+// A tab is as wide as an indentation level. Every character can be at most that wide,
+// so the columns are only counted for the lines that may exceed the limit.
+fn is_long_line(line: &str, column_limit: usize, tab_width: usize) -> bool {
+    if line.len() * tab_width.max(1) <= column_limit {
+        return false;
+    }
+    let columns = line.chars().map(|c| if c == '\t' {tab_width} else {1}).sum::<usize>();
+    columns > column_limit
+}
+
+fn strip_line_ending(line: &str) -> &str {
+    match line.strip_suffix('\n') {
+        Some(x) => x.strip_suffix('\r').unwrap_or(x),
+        None => line
+    }
+}
+
 
 // cleansed_string can contain normal code string or curly braces or strings
 #[derive(Debug, PartialEq)]
@@ -508,6 +611,107 @@ fn add_keywords_if_any(cleansed: &str, matcher: &KeywordMatcher, file_stats: &mu
     }
 }
 
+// Functions are not keywords, so they are counted even when keyword counting is disabled
+fn add_functions_if_any(cleansed: &str, language: &Language, file_stats: &mut FileStats) {
+    for function_finder in &finders_of(language).function_finders {
+        file_stats.add_functions(count_word_occurrences(cleansed, function_finder));
+    }
+}
+
+// Every branch token adds one more path through the code. Only the code is searched,
+// so tokens inside strings and comments don't count.
+fn add_complexity_if_any(cleansed: &str, language: &Language, file_stats: &mut FileStats) {
+    for branch_finder in &finders_of(language).branch_finders {
+        file_stats.add_complexity(count_word_occurrences(cleansed, branch_finder));
+    }
+}
+
+// Languages with block symbols are measured by how many blocks are open, the rest by their indentation.
+// A line that starts by closing a block, belongs to the outer one.
+fn get_nesting_depth(raw_line: &str, cleansed: &str, language: &Language, block_depth: &mut usize, indent_width: usize) -> usize {
+    let finders = finders_of(language);
+    let (Some(start_finder), Some(end_finder)) = (&finders.block_start_finder, &finders.block_end_finder) else {
+        return get_indentation_depth(raw_line, indent_width);
+    };
+
+    let depth = if cleansed.as_bytes().starts_with(end_finder.needle()) {block_depth.saturating_sub(1)} else {*block_depth};
+    *block_depth = (*block_depth + start_finder.find_iter(cleansed.as_bytes()).count())
+            .saturating_sub(end_finder.find_iter(cleansed.as_bytes()).count());
+    depth
+}
+
+// Test blocks are only recognised in the languages with block symbols
+fn starts_test_block(cleansed: &str, language: &Language) -> bool {
+    finders_of(language).block_start_finder.is_some() && language.test_blocks.iter().any(|x| cleansed.starts_with(x.as_str()))
+}
+
+// A test block lasts from its marker until the block that follows it is closed. The block depth that it started from
+// is kept, along with whether its block was opened yet. An item without a block, like 'use super::*;', ends it too.
+fn get_test_block_after(test_block: Option<(usize,bool)>, cleansed: &str, language: &Language, depth_before: usize, depth_after: usize)
+-> Option<(usize,bool)>
+{
+    let (start_depth, was_opened) = test_block.unwrap_or((depth_before, false));
+    let start_finder = finders_of(language).block_start_finder.as_ref().unwrap();
+    let is_opened = was_opened || start_finder.find(cleansed.as_bytes()).is_some();
+    if (is_opened && depth_after <= start_depth) || (!is_opened && cleansed.ends_with(';')) {
+        None
+    } else {
+        Some((start_depth, is_opened))
+    }
+}
+
+// A tab is always one level
+fn get_indentation_depth(raw_line: &str, indent_width: usize) -> usize {
+    let (mut tabs, mut spaces) = (0, 0);
+    for c in raw_line.bytes() {
+        match c {
+            b'\t' => tabs += 1,
+            b' ' => spaces += 1,
+            _ => break
+        }
+    }
+    tabs + spaces / indent_width
+}
+
+fn may_contain_comment(line: &str, language: &Language) -> bool {
+    let finders = finders_of(language);
+    finders.comment_finders.iter().any(|f| f.find(line.as_bytes()).is_some())
+        || finders.multiline_start_finder.as_ref().is_some_and(|f| f.find(line.as_bytes()).is_some())
+}
+
+// Whatever is in the line but not in its cleansed code, is inside a comment.
+// String literals are not part of the cleansed string either, so a marker inside a string
+// on a line that also has a comment, is counted too.
+fn add_comment_keywords_if_any(line: &str, cleansed: Option<&str>, matcher: &KeywordMatcher, file_stats: &mut FileStats) {
+    for (alias_finder, keyword_index) in &matcher.comment_aliases_with_indices {
+        let in_line = count_word_occurrences(line, alias_finder);
+        if in_line == 0 {continue;}
+
+        let in_code = cleansed.map_or(0, |x| count_word_occurrences(x, alias_finder));
+        if in_line > in_code {
+            file_stats.add_comment_keyword(*keyword_index, in_line - in_code);
+        }
+    }
+}
+
+// A word boundary is only required on the sides where the word itself starts or ends with an identifier character,
+// so that symbolic tokens can be counted when attached to identifiers.
+fn count_word_occurrences(text: &str, word_finder: &memmem::Finder<'_>) -> usize {
+    fn is_identifier_byte(byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || byte == b'_'
+    }
+
+    let word = word_finder.needle();
+    if word.is_empty() {return 0;}
+    let check_start = is_identifier_byte(word[0]);
+    let check_end = is_identifier_byte(word[word.len()-1]);
+    let bytes = text.as_bytes();
+    word_finder.find_iter(bytes).filter(|&i| {
+        (!check_start || i == 0 || !is_identifier_byte(bytes[i-1]))
+            && (!check_end || i + word.len() >= bytes.len() || !is_identifier_byte(bytes[i + word.len()]))
+    }).count()
+}
+
 fn count_alias_occurrences(cleansed: &str, alias_finder: &memmem::Finder<'_>, alias_len: usize, keyword_index: usize, file_stats: &mut FileStats) {
     fn is_acceptable_prefix(prefix: &str) -> bool {
         prefix.is_empty() || prefix.ends_with(' ') || prefix.ends_with('}') || prefix.ends_with('{') || prefix.ends_with(',')
@@ -576,6 +780,7 @@ pub fn get_str_indices_and_symbols(line: &str, language: &Language, open_str_sym
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn add_non_intersecting(indices_1: &mut Vec<usize>, indices_2: &mut Vec<usize>, symbols_1: &mut Vec<u8>, symbols_2: &mut Vec<u8>,
             open_str_symbol: &Option<String>, merged_indices: &mut Vec<usize>, merged_symbols: &mut Vec<u8>, language: &Language)
     {
@@ -863,7 +1068,13 @@ mod tests {
         comment_symbols : vec!["//".to_owned()],
         multiline_comment_start_symbol : Some("/*".to_owned()),
         multiline_comment_end_symbol : Some("*/".to_owned()),
+        function_declarations : vec![],
+        branch_tokens : vec![],
+        block_symbols : vec![],
+        test_patterns : vec![],
+        test_blocks : vec![],
         keywords : vec![CLASS.clone(),INTERFACE.clone()],
+        comment_keywords : vec![],
         finders : std::sync::OnceLock::new()
     });
 
@@ -874,7 +1085,13 @@ mod tests {
         comment_symbols : vec!["//".to_owned(),"#".to_owned()],
         multiline_comment_start_symbol : Some("/*".to_owned()),
         multiline_comment_end_symbol : Some("*/".to_owned()),
+        function_declarations : vec![],
+        branch_tokens : vec![],
+        block_symbols : vec![],
+        test_patterns : vec![],
+        test_blocks : vec![],
         keywords : vec![CLASS.clone()],
+        comment_keywords : vec![],
         finders : std::sync::OnceLock::new()
     });
 
@@ -885,7 +1102,13 @@ mod tests {
         comment_symbols : vec!["#".to_owned()],
         multiline_comment_start_symbol : None,
         multiline_comment_end_symbol : None,
+        function_declarations : vec![],
+        branch_tokens : vec![],
+        block_symbols : vec![],
+        test_patterns : vec![],
+        test_blocks : vec![],
         keywords : vec![CLASS.clone()],
+        comment_keywords : vec![],
         finders : std::sync::OnceLock::new()
     });
 
@@ -896,7 +1119,13 @@ mod tests {
         comment_symbols : vec!["//".to_owned()],
         multiline_comment_start_symbol : Some("/*".to_owned()),
         multiline_comment_end_symbol : Some("*/".to_owned()),
+        function_declarations : vec![],
+        branch_tokens : vec![],
+        block_symbols : vec![],
+        test_patterns : vec![],
+        test_blocks : vec![],
         keywords : vec![STRUCT.clone(),ENUM.clone(),TRAIT.clone()],
+        comment_keywords : vec![],
         finders : std::sync::OnceLock::new()
     });
 
@@ -909,8 +1138,15 @@ mod tests {
         KeywordMatcher::build(LANGUAGE_MAP_REF.get(lang_name).unwrap())
     }
 
-    fn content_info_of(stats: FileStats, lang_name: &str) -> LanguageContentInfo {
-        LanguageContentInfo::from_file_stats(stats, &LANGUAGE_MAP_REF.get(lang_name).unwrap().keywords)
+    // Every code line has a depth and the file is recorded with its lines.
+    // The depths themselves are checked in their own test
+    fn content_info_of(mut stats: FileStats, lang_name: &str) -> LanguageContentInfo {
+        assert_eq!(stats.code_lines, stats.depth_histogram.iter().sum::<usize>());
+        stats.depth_histogram.clear();
+        let mut content_info = LanguageContentInfo::from_file_stats(stats, LANGUAGE_MAP_REF.get(lang_name).unwrap());
+        assert_eq!(vec![content_info.lines], content_info.file_lines);
+        content_info.file_lines.clear();
+        content_info
     }
 
     #[test]
@@ -1011,7 +1247,145 @@ mod tests {
         assert_eq!(make_file_stats(0,0), file_stats);
     }
 
-    fn make_file_stats(class_occurances: usize, interface_occurances: usize) -> FileStats {
+    #[test]
+    fn finds_comment_keywords_only_inside_comments() {
+        fn comment_keywords_of(contents: &str, config: &Configuration) -> HashMap<String,usize> {
+            let rust = LANGUAGE_MAP_REF.get("Rust").unwrap();
+            let stats = parse_lines(contents, rust, matcher_for("Rust").as_ref(), config);
+            LanguageContentInfo::from_file_stats(stats, rust).comment_keyword_occurences
+        }
+
+        let config = Configuration::new(vec!["a".to_owned()]);
+        assert_eq!(hashmap!("TODO".to_owned()=>1), comment_keywords_of("let x = 5; // TODO: remove", &config));
+        assert_eq!(hashmap!("TODO".to_owned()=>1, "FIXME".to_owned()=>1), comment_keywords_of("/* TODO\n  FIXME */ let a = 1;", &config));
+        assert_eq!(hashmap!("SAFETY".to_owned()=>1), comment_keywords_of("// SAFETY: checked above\nunsafe { x() }", &config));
+        assert_eq!(hashmap!("XXX".to_owned()=>1), comment_keywords_of("let TODO = 1; // XXX", &config));
+        assert_eq!(hashmap!(), comment_keywords_of("let TODO = 1;\nlet s = \"FIXME\";", &config));
+        assert_eq!(hashmap!(), comment_keywords_of("// TODOS and NOTODO", &config));
+
+        let mut config = Configuration::new(vec!["a".to_owned()]);
+        config.set_should_not_count_keywords(true);
+        assert_eq!(hashmap!(), comment_keywords_of("// TODO", &config));
+    }
+
+    #[test]
+    fn counts_function_declarations_in_code_only() {
+        fn functions_of(lang_name: &str, contents: &str, config: &Configuration) -> usize {
+            parse_lines(contents, LANGUAGE_MAP_REF.get(lang_name).unwrap(), matcher_for(lang_name).as_ref(), config).functions
+        }
+
+        let config = Configuration::new(vec!["a".to_owned()]);
+        assert_eq!(2, functions_of("Rust", "pub fn a() {}\n    fn b(x: i32) -> i32 {\n x }", &config));
+        assert_eq!(0, functions_of("Rust", "// fn a() {}\nlet s = \"fn b()\";\nlet fn_name = 1;", &config));
+        assert_eq!(1, functions_of("Python", "def a():\n    # def b():\n    return 'def c()'", &config));
+        assert_eq!(0, functions_of("Java", "public void a() {}", &config));
+
+        let mut config = Configuration::new(vec!["a".to_owned()]);
+        config.set_should_not_count_keywords(true);
+        assert_eq!(1, functions_of("GO", "func main() {", &config));
+    }
+
+    #[test]
+    fn counts_branch_tokens_in_code_only() {
+        fn complexity_of(lang_name: &str, contents: &str) -> usize {
+            parse_lines(contents, LANGUAGE_MAP_REF.get(lang_name).unwrap(), matcher_for(lang_name).as_ref(),
+                 &Configuration::new(vec!["a".to_owned()])).complexity
+        }
+
+        assert_eq!(4, complexity_of("Java", "if (a && b || c) {\n} else if (d) {"));
+        assert_eq!(1, complexity_of("Java", "x = a ? b : c; // if (a) while (b)\nString s = \"for (;;)\";"));
+        assert_eq!(0, complexity_of("Java", "int iff = 1;\nformat(); notify();"));
+        assert_eq!(3, complexity_of("Python", "if a and b:\n    pass\nelif c:"));
+        // The closures and the ?Sized bounds of Rust aren't branches
+        assert_eq!(2, complexity_of("Rust", "let f = move || if a && b { c } else { d };\nthread::spawn(|| run());"));
+        assert_eq!(0, complexity_of("Rust", "fn a<T: ?Sized>(x: &T) -> Result<(), E> { b(x)?; Ok(()) }"));
+        assert_eq!(0, complexity_of("CSS", "a:hover { color: red; }"));
+    }
+
+    #[test]
+    fn measures_nesting_depth_of_code_lines() {
+        fn depths_of(lang_name: &str, contents: &str, config: &Configuration) -> Vec<usize> {
+            parse_lines(contents, LANGUAGE_MAP_REF.get(lang_name).unwrap(), matcher_for(lang_name).as_ref(), config).depth_histogram
+        }
+
+        let config = Configuration::new(vec!["a".to_owned()]);
+        let java = "class A {\n  void a() {\n    if (b) { c(); }\n    String s = \"{{\"; // {\n  }\n}";
+        assert_eq!(vec![1,1,2], depths_of("Java", java, &config));
+        assert_eq!(vec![2,2,2], depths_of("Java", java, Configuration::new(vec!["a".to_owned()]).set_braces_as_code(true)));
+
+        let python = "def a():\n    if b:\n        c()\n\td()\n  e()";
+        assert_eq!(vec![2,2,1], depths_of("Python", python, &config));
+        assert_eq!(vec![1,2,1,0,1], depths_of("Python", python, Configuration::new(vec!["a".to_owned()]).set_indent_width(2)));
+    }
+
+    #[test]
+    fn counts_lines_longer_than_the_column_limit() {
+        let mut config = Configuration::new(vec!["a".to_owned()]);
+        config.set_column_limit(10);
+        let contents = format!("{}\n{}\n\tabcdef\n\t\t\tabc\n{}\n", "a".repeat(10), "a".repeat(11), "α".repeat(10));
+        assert_eq!(2, parse_lines(&contents, LANGUAGE_MAP_REF.get("Python").unwrap(), None, &config).long_lines);
+    }
+
+    #[test]
+    fn measures_hygiene_only_when_requested() {
+        fn hygiene_of(contents: &str, config: &Configuration) -> Option<FileHygiene> {
+            parse_lines(contents, LANGUAGE_MAP_REF.get("Python").unwrap(), matcher_for("Python").as_ref(), config).hygiene
+        }
+
+        let config = Configuration::new(vec!["a".to_owned()]);
+        assert_eq!(None, hygiene_of("def a():\n    pass\n", &config));
+
+        let mut config = Configuration::new(vec!["a".to_owned()]);
+        config.set_hygiene(true);
+        let hygiene = hygiene_of("def a():\r\n    b = 1  \n\tpass\n  \n\n", &config).unwrap();
+        assert_eq!(FileHygiene {lf_lines: 4, crlf_lines: 1, space_indented_lines: 1, tab_indented_lines: 1, trailing_whitespace_lines: 2,
+                has_final_newline: true}, hygiene);
+        assert_eq!((LineEnding::Mixed, Indentation::Mixed), (hygiene.line_ending(), hygiene.indentation()));
+
+        let hygiene = hygiene_of("def a():\r\n\tpass", &config).unwrap();
+        assert_eq!((LineEnding::CRLF, Indentation::Tabs, false), (hygiene.line_ending(), hygiene.indentation(), hygiene.has_final_newline));
+        assert!(hygiene_of("", &config).unwrap().has_final_newline);
+    }
+
+    #[test]
+    fn fingerprints_code_lines_only_when_requested() {
+        fn fingerprints_of(contents: &str, config: &Configuration) -> Vec<LineFingerprint> {
+            parse_lines(contents, LANGUAGE_MAP_REF.get("Rust").unwrap(), matcher_for("Rust").as_ref(), config).code_line_fingerprints
+        }
+
+        let contents = "let a = 1; // one\n\n// comment\nlet  b =\t\"x\";\n";
+        let config = Configuration::new(vec!["a".to_owned()]);
+        assert!(fingerprints_of(contents, &config).is_empty());
+
+        let mut config = Configuration::new(vec!["a".to_owned()]);
+        config.set_duplicates(3);
+        assert_eq!(vec![LineFingerprint::of("let a = 1;", 1), LineFingerprint::of("let b = ;", 4)], fingerprints_of(contents, &config));
+        assert_ne!(LineFingerprint::of("let a = 1;", 1), LineFingerprint::of("let a = 2;", 1));
+    }
+
+    #[test]
+    fn counts_the_code_lines_of_test_blocks() {
+        fn test_code_lines_of(contents: &str, lang_name: &str) -> (usize, usize) {
+            let config = Configuration::new(vec!["a".to_owned()]);
+            let stats = parse_lines(contents, LANGUAGE_MAP_REF.get(lang_name).unwrap(), matcher_for(lang_name).as_ref(), &config);
+            (stats.test_code_lines, stats.code_lines)
+        }
+
+        let contents = "fn main() {\n    run();\n}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n\n    #[test]\n    fn a() {\n        \"x\";\n    }\n}\nfn after() {}\n";
+        assert_eq!((6, 9), test_code_lines_of(contents, "Rust"));
+
+        // An item without a block ends the test block on its line
+        let contents = "#[cfg(test)]\nuse std::fs;\nfn main() {}\n#[cfg(test)] mod tests { fn a() {} }\nfn after() {}\n";
+        assert_eq!((3, 5), test_code_lines_of(contents, "Rust"));
+
+        // The signature of the item may span many lines before its block is opened
+        let contents = "#[cfg(test)]\nfn helper(\n    a: u8,\n) -> u8 {\n    a\n}\nfn main() {}\n";
+        assert_eq!((5, 6), test_code_lines_of(contents, "Rust"));
+
+        assert_eq!((0, 2), test_code_lines_of("#[cfg(test)]\nfn main() {}\n", "C"));
+    }
+
+        fn make_file_stats(class_occurances: usize, interface_occurances: usize) -> FileStats {
         fn get_keyword_map(class_occurances: usize, interface_occurances: usize) -> Vec<usize> {
             vec![class_occurances, interface_occurances]
         }
@@ -1019,7 +1393,17 @@ mod tests {
         FileStats {
             lines: 0,
             code_lines: 0,
-            keyword_occurences : get_keyword_map(class_occurances, interface_occurances)
+            test_code_lines: 0,
+            functions: 0,
+            complexity: 0,
+            depth_histogram: Vec::new(),
+            hygiene: None,
+            content_hash: None,
+            code_line_fingerprints: Vec::new(),
+            code_line_numbers: Vec::new(),
+            long_lines: 0,
+            keyword_occurences : get_keyword_map(class_occurances, interface_occurances),
+            comment_keyword_occurences : Vec::new()
         }
     }
 
@@ -0,0 +1,553 @@
+use std::{collections::HashSet, io::{self, BufRead, BufReader, Read, Write}, process::{Command, Stdio}, thread};
+
+use chrono::FixedOffset;
+
+use crate::{*, config_manager::Backfill};
+
+// A file of a git revision, with the path that it would have in the working tree
+#[derive(Debug,Clone)]
+pub struct RevisionFile {
+    pub path: PathBuf,
+    pub object_id: String,
+    pub language_name: Arc<str>
+}
+
+#[derive(Debug)]
+struct TreeEntry {
+    // Relative to the root of the repository
+    path: PathBuf,
+    object_id: String,
+    bytes: usize
+}
+
+// All the blobs of a revision of a repository
+#[derive(Debug)]
+struct RevisionTree {
+    root: PathBuf,
+    entries: Vec<TreeEntry>,
+    // The object ids of the ignore files, by the dir that contains them, in the order of IGNORE_FILE_NAMES
+    ignore_file_ids: HashMap<PathBuf,Vec<(usize,String)>>,
+    // The object ids of the .gitattributes files, by the dir that contains them
+    gitattributes_ids: HashMap<PathBuf,String>
+}
+
+// A commit whose stats are added to the log, named after its tags and its message
+#[derive(Debug,PartialEq,Clone)]
+pub struct Commit {
+    pub id: String,
+    pub datetime: DateTime<FixedOffset>,
+    pub name: String
+}
+
+// Where the ignore files and the .gitattributes of a traversal are read from
+trait GitignoreSource {
+    fn extended_gitignore_stack(&self, dir: &Path, parent: Option<Arc<GitignoreStack>>) -> Option<Arc<GitignoreStack>>;
+    fn extended_gitattributes_stack(&self, dir: &Path, parent: Option<Arc<GitattributesStack>>) -> Option<Arc<GitattributesStack>>;
+}
+
+// The ignore files and the .gitattributes of the working tree
+struct WorkingTree;
+
+// Decides which files of a target dir the producers would find, either in the working tree
+// or in a revision, as if it was checked out
+struct TreeTraversal<'a, S: GitignoreSource> {
+    source: &'a S,
+    context: &'a TraversalContext,
+    target_dir: PathBuf,
+    // The gitignore stacks of the dirs that are traversed
+    traversed_dirs: HashMap<PathBuf,Option<Arc<GitignoreStack>>>,
+    gitattributes_stacks: HashMap<PathBuf,Option<Arc<GitattributesStack>>>,
+    skipped_dirs: HashSet<PathBuf>
+}
+
+pub fn run_git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = match Command::new("git").arg("-C").arg(dir).args(args).output() {
+        Ok(x) => x,
+        Err(x) => return Err(format!("the git binary could not be executed ({x})"))
+    };
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().trim_start_matches("fatal: ").to_owned());
+    }
+
+    Ok(output.stdout)
+}
+
+// The target files are looked up from the dir that contains them
+fn dir_of(path: &Path) -> &Path {
+    if path.is_dir() {path} else {path.parent().unwrap_or(path)}
+}
+
+pub fn find_repository_root(path: &Path) -> Result<PathBuf, String> {
+    let output = run_git(dir_of(path), &["rev-parse", "--show-toplevel"])?;
+    Ok(PathBuf::from(String::from_utf8_lossy(&output).trim_end()))
+}
+
+pub fn verify_revision(path: &Path, rev: &str) -> Result<(), String> {
+    match run_git(dir_of(path), &["rev-parse", "--verify", "--quiet", "--end-of-options", &format!("{rev}^{{commit}}")]) {
+        Ok(_) => Ok(()),
+        Err(x) if x.is_empty() => Err(format!("it is not a commit of the git repository of '{}'.", path.display())),
+        Err(x) => Err(x)
+    }
+}
+
+// The commits of the repository of the first target dir, the oldest first. The commits of a range are the ones
+// of its first-parent history, so that the merged branches don't interleave with the main one.
+pub fn find_commits(dir: &Path, backfill: &Backfill) -> Result<Vec<Commit>, String> {
+    // <id> US <commit date> US <subject> US <tags>
+    let format = "--format=%H%x1f%ci%x1f%s%x1f%D";
+    let (output, step) = match backfill {
+        Backfill::Tags => (run_git(dir_of(dir), &["log", "--no-walk", "--tags", "--decorate-refs=refs/tags/", format])?, 1),
+        Backfill::Range(range, step) => (run_git(dir_of(dir), &["log", "--first-parent", "--decorate-refs=refs/tags/", format, "--end-of-options",
+                range, "--"])?, *step)
+    };
+
+    let mut commits = Vec::new();
+    for line in String::from_utf8_lossy(&output).lines().step_by(step) {
+        let fields = line.split('\x1f').collect::<Vec<_>>();
+        let [id, date, subject, refs] = fields.as_slice() else { continue };
+        let Ok(datetime) = DateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S %z") else { continue };
+        let tags = refs.split(", ").filter_map(|x| x.strip_prefix("tag: ")).collect::<Vec<_>>();
+        let name = match (tags.is_empty(), subject.is_empty()) {
+            (true, true) => id[..id.len().min(10)].to_owned(),
+            (true, false) => subject.to_string(),
+            (false, true) => tags.join(", "),
+            (false, false) => format!("{}: {subject}", tags.join(", "))
+        };
+        commits.push(Commit{id: id.to_string(), datetime, name});
+    }
+    // The tags are listed by their commit dates, like the commits of a range
+    commits.reverse();
+
+    Ok(commits)
+}
+
+impl RevisionTree {
+    // The symbolic links and the submodules are left out, since they have no contents to parse
+    fn list(root: &Path, rev: &str) -> Result<RevisionTree, String> {
+        let output = run_git(root, &["ls-tree", "-r", "-z", "--long", "--full-tree", rev])?;
+        let mut entries = Vec::new();
+        let mut ignore_file_ids: HashMap<PathBuf,Vec<(usize,String)>> = HashMap::new();
+        let mut gitattributes_ids = HashMap::new();
+        for entry in output.split(|x| *x == 0) {
+            // <mode> SP <type> SP <object id> SP+ <size> TAB <path>
+            let Ok(entry) = std::str::from_utf8(entry) else { continue };
+            let Some((info, path)) = entry.split_once('\t') else { continue };
+            let info = info.split_whitespace().collect::<Vec<_>>();
+            if info.len() != 4 || info[1] != "blob" || info[0] == "120000" {
+                continue;
+            }
+
+            let path = PathBuf::from(path);
+            if let Some(index) = path.file_name().and_then(|x| IGNORE_FILE_NAMES.iter().position(|name| x == *name)) {
+                let ids = ignore_file_ids.entry(path.parent().unwrap().to_path_buf()).or_default();
+                ids.push((index, info[2].to_owned()));
+                ids.sort();
+            } else if path.file_name().is_some_and(|x| x == ".gitattributes") {
+                gitattributes_ids.insert(path.parent().unwrap().to_path_buf(), info[2].to_owned());
+            }
+            entries.push(TreeEntry { path, object_id: info[2].to_owned(), bytes: info[3].parse().unwrap_or(0) });
+        }
+
+        Ok(RevisionTree { root: root.to_path_buf(), entries, ignore_file_ids, gitattributes_ids })
+    }
+
+}
+
+impl GitignoreSource for RevisionTree {
+    fn extended_gitignore_stack(&self, dir: &Path, parent: Option<Arc<GitignoreStack>>) -> Option<Arc<GitignoreStack>> {
+        let Some(object_ids) = dir.strip_prefix(&self.root).ok().and_then(|x| self.ignore_file_ids.get(x)) else { return parent };
+        // The lines of the later files take precedence, like the later lines of a single file
+        let contents = object_ids.iter().filter_map(|(_, id)| run_git(&self.root, &["cat-file", "blob", id]).ok())
+                .map(|x| String::from_utf8_lossy(&x).into_owned()).collect::<Vec<_>>().join("\n");
+        GitignoreStack::extended_with_contents(dir, &contents, parent)
+    }
+
+    fn extended_gitattributes_stack(&self, dir: &Path, parent: Option<Arc<GitattributesStack>>) -> Option<Arc<GitattributesStack>> {
+        let object_id = dir.strip_prefix(&self.root).ok().and_then(|x| self.gitattributes_ids.get(x));
+        match object_id.and_then(|x| run_git(&self.root, &["cat-file", "blob", x]).ok()) {
+            Some(contents) => GitattributesStack::extended_with_contents(dir, &String::from_utf8_lossy(&contents), parent),
+            None => parent
+        }
+    }
+}
+
+impl GitignoreSource for WorkingTree {
+    fn extended_gitignore_stack(&self, dir: &Path, parent: Option<Arc<GitignoreStack>>) -> Option<Arc<GitignoreStack>> {
+        GitignoreStack::extended(dir, parent)
+    }
+
+    fn extended_gitattributes_stack(&self, dir: &Path, parent: Option<Arc<GitattributesStack>>) -> Option<Arc<GitattributesStack>> {
+        GitattributesStack::extended(dir, parent)
+    }
+}
+
+impl<'a, S: GitignoreSource> TreeTraversal<'a, S> {
+    // Like the traversal of the producers, the excludes of the repository and the ignore files of the ancestors
+    // of the target dir apply, unless they ignore the target dir itself
+    fn new(source: &'a S, root: &Path, target_dir: &Path, context: &'a TraversalContext) -> Self {
+        let config = &context.config;
+        let mut gitignore_stack = None;
+        if !config.no_gitignore {
+            gitignore_stack = GitignoreStack::of_repository(root);
+            let mut ancestors = target_dir.ancestors().skip(1).take_while(|x| x.starts_with(root)).collect::<Vec<_>>();
+            ancestors.reverse();
+            for ancestor in ancestors {
+                gitignore_stack = source.extended_gitignore_stack(ancestor, gitignore_stack);
+            }
+            if gitignore_stack.as_ref().is_some_and(|x| x.is_ignored(target_dir, true)) {
+                gitignore_stack = None;
+            }
+            gitignore_stack = source.extended_gitignore_stack(target_dir, gitignore_stack);
+        }
+        let mut gitattributes_stack = None;
+        if !config.no_gitattributes {
+            for ancestor in target_dir.ancestors().take_while(|x| x.starts_with(root)).collect::<Vec<_>>().into_iter().rev() {
+                gitattributes_stack = source.extended_gitattributes_stack(ancestor, gitattributes_stack);
+            }
+        }
+
+        TreeTraversal {
+            source,
+            context,
+            target_dir: target_dir.to_path_buf(),
+            traversed_dirs: HashMap::from([(target_dir.to_path_buf(), gitignore_stack)]),
+            gitattributes_stacks: HashMap::from([(target_dir.to_path_buf(), gitattributes_stack)]),
+            skipped_dirs: HashSet::new()
+        }
+    }
+
+    // Only for the dirs inside the target dir
+    fn is_traversed(&mut self, dir: &Path) -> bool {
+        if self.traversed_dirs.contains_key(dir) {
+            return true;
+        }
+        if self.skipped_dirs.contains(dir) {
+            return false;
+        }
+
+        let parent = dir.parent().unwrap();
+        if !self.is_traversed(parent) || self.is_skipped(dir, parent) {
+            self.skipped_dirs.insert(dir.to_path_buf());
+            return false;
+        }
+
+        let gitignore_stack = if self.context.config.no_gitignore {
+            None
+        } else {
+            self.source.extended_gitignore_stack(dir, self.traversed_dirs[parent].clone())
+        };
+        self.traversed_dirs.insert(dir.to_path_buf(), gitignore_stack);
+        let gitattributes_stack = if self.context.config.no_gitattributes {
+            None
+        } else {
+            self.source.extended_gitattributes_stack(dir, self.gitattributes_stacks[parent].clone())
+        };
+        self.gitattributes_stacks.insert(dir.to_path_buf(), gitattributes_stack);
+        true
+    }
+
+    fn is_skipped(&self, dir: &Path, parent: &Path) -> bool {
+        let Some(dir_name) = dir.file_name().and_then(|x| x.to_str()) else { return true };
+        (!self.context.config.should_search_in_dotted && dir_name.starts_with('.')) ||
+        (!self.context.exclude_matcher.is_empty() && self.context.exclude_matcher.is_match(dir)) || !self.context.include_matcher.may_include_below(dir) ||
+        self.traversed_dirs[parent].as_ref().is_some_and(|x| x.is_ignored(dir, true)) ||
+        self.context.config.max_depth.is_some_and(|x| dir.strip_prefix(&self.target_dir).map_or(0, |y| y.components().count()) >= x)
+    }
+
+    // Like in the producers, the dirs above the file have been checked while traversing them
+    fn exclusion_reason(&self, file: &Path, attributes: &LinguistAttributes, bytes: usize, modified: Option<SystemTime>) -> Option<ExclusionReason> {
+        let dir = file.parent().unwrap();
+        self.context.exclusion_reason(file, dir, self.traversed_dirs[dir].as_deref(), attributes, bytes, modified)
+    }
+
+    fn attributes_of(&self, file: &Path) -> LinguistAttributes {
+        self.gitattributes_stacks[file.parent().unwrap()].as_ref().map(|x| x.attributes_of(file)).unwrap_or_default()
+    }
+}
+
+// Finds the files of the target paths at the revision, with the same rules that the producers use for the working tree,
+// and adds them to the metadata of their languages. They are returned grouped by their repository.
+pub fn find_revision_files(rev: &str, context: &TraversalContext, files_present: &mut FilesPresent)
+-> Result<Vec<(PathBuf, Vec<RevisionFile>)>, String>
+{
+    let TraversalContext {config, extension_lang_map, ..} = context;
+    let mut trees: Vec<RevisionTree> = Vec::new();
+    let mut files_of_repositories: Vec<(PathBuf, Vec<RevisionFile>)> = Vec::new();
+    let mut languages_metadata_map = context.languages_metadata_map.lock().unwrap();
+    for target in &config.dirs {
+        let target = Path::new(target);
+        let root = find_repository_root(target)?;
+        if !trees.iter().any(|x| x.root == root) {
+            trees.push(RevisionTree::list(&root, rev)?);
+            files_of_repositories.push((root.clone(), Vec::new()));
+        }
+        let tree = trees.iter().find(|x| x.root == root).unwrap();
+        let files = &mut files_of_repositories.iter_mut().find(|x| x.0 == root).unwrap().1;
+        let Ok(relative_target) = target.strip_prefix(&root) else { continue };
+
+        let language_of = |path: &Path| path.extension().and_then(|x| x.to_str()).and_then(|x| find_language_of_extension(extension_lang_map, x));
+        let mut add_file = |entry: &TreeEntry, path: PathBuf, lang_name: Arc<str>| {
+            languages_metadata_map.get_mut(lang_name.as_ref()).unwrap().add_file_meta(entry.bytes);
+            files.push(RevisionFile { path, object_id: entry.object_id.clone(), language_name: lang_name });
+        };
+
+        // Files that are given directly as targets are never excluded or ignored
+        if let Some(entry) = tree.entries.iter().find(|x| x.path == relative_target) {
+            if let Some(lang_name) = language_of(target) {
+                files_present.total_files += 1;
+                files_present.relevant_files += 1;
+                add_file(entry, target.to_path_buf(), lang_name);
+            }
+            continue;
+        }
+
+        let mut traversal = TreeTraversal::new(tree, &root, target, context);
+        for entry in tree.entries.iter().filter(|x| x.path.starts_with(relative_target)) {
+            let path = root.join(&entry.path);
+            if !traversal.is_traversed(path.parent().unwrap()) {
+                continue;
+            }
+            files_present.total_files += 1;
+
+            let attributes = traversal.attributes_of(&path);
+            let Some(lang_name) = find_language_of_file(extension_lang_map, &path, &attributes) else { continue };
+            // The modification times of the files of a revision aren't known
+            if let Some(reason) = traversal.exclusion_reason(&path, &attributes, entry.bytes, None) {
+                files_present.exclude(reason);
+                continue;
+            }
+            files_present.relevant_files += 1;
+            add_file(entry, path, lang_name);
+        }
+    }
+
+    Ok(files_of_repositories)
+}
+
+// Finds the changed files of the target paths in the working tree, with the same rules that the producers use,
+// and adds them to the metadata of their languages and to the injector
+pub fn find_changed_files(context: &TraversalContext, files_present: &mut FilesPresent) -> Result<(), String> {
+    let TraversalContext {config, extension_lang_map, ..} = context;
+    let mut changed_paths_of_repositories: HashMap<PathBuf, HashSet<PathBuf>> = HashMap::new();
+    let mut languages_metadata_map = context.languages_metadata_map.lock().unwrap();
+    for target in &config.dirs {
+        let target = Path::new(target);
+        let root = find_repository_root(target)?;
+        if !changed_paths_of_repositories.contains_key(&root) {
+            changed_paths_of_repositories.insert(root.clone(), list_changed_paths(&root, config)?);
+        }
+        let changed_paths = &changed_paths_of_repositories[&root];
+        let Ok(relative_target) = target.strip_prefix(&root) else { continue };
+
+        let language_of = |path: &Path| path.extension().and_then(|x| x.to_str()).and_then(|x| find_language_of_extension(extension_lang_map, x));
+        let mut add_file = |path: PathBuf, lang_name: Arc<str>| {
+            languages_metadata_map.get_mut(lang_name.as_ref()).unwrap().add_file_meta(path.metadata().map_or(0, |m| m.len() as usize));
+            context.files_injector.push(ParsableFile::new(path, lang_name));
+        };
+
+        // Files that are given directly as targets are never excluded or ignored
+        if target.is_file() {
+            if changed_paths.contains(relative_target) && let Some(lang_name) = language_of(target) {
+                files_present.total_files += 1;
+                files_present.relevant_files += 1;
+                add_file(target.to_path_buf(), lang_name);
+            }
+            continue;
+        }
+
+        let mut traversal = TreeTraversal::new(&WorkingTree, &root, target, context);
+        let mut paths = changed_paths.iter().filter(|x| x.starts_with(relative_target)).map(|x| root.join(x)).collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            // A file that was staged and then deleted, or replaced by a dir, and the links that the traversal wouldn't follow
+            if !path.is_file() || (!config.follow_symlinks && path.is_symlink()) || !traversal.is_traversed(path.parent().unwrap()) {
+                continue;
+            }
+            files_present.total_files += 1;
+
+            let attributes = traversal.attributes_of(&path);
+            let Some(lang_name) = find_language_of_file(extension_lang_map, &path, &attributes) else { continue };
+            let metadata = path.metadata().ok();
+            let bytes = metadata.as_ref().map_or(0, |x| x.len() as usize);
+            if let Some(reason) = traversal.exclusion_reason(&path, &attributes, bytes, metadata.and_then(|x| x.modified().ok())) {
+                files_present.exclude(reason);
+                continue;
+            }
+            files_present.relevant_files += 1;
+            add_file(path, lang_name);
+        }
+    }
+
+    Ok(())
+}
+
+// Relative to the root of the repository. The deleted files are left out, since there is nothing to count in them.
+fn list_changed_paths(root: &Path, config: &Configuration) -> Result<HashSet<PathBuf>, String> {
+    let outputs = match &config.changed_since {
+        Some(rev) => vec![run_git(root, &["diff", "--name-only", "-z", "--no-renames", "--diff-filter=d", "--end-of-options", rev, "--"])?,
+                run_git(root, &["ls-files", "--others", "--exclude-standard", "-z"])?],
+        None => vec![run_git(root, &["diff", "--cached", "--name-only", "-z", "--no-renames", "--diff-filter=d"])?]
+    };
+
+    Ok(outputs.iter().flat_map(|x| x.split(|x| *x == 0)).filter(|x| !x.is_empty())
+            .filter_map(|x| std::str::from_utf8(x).ok()).map(PathBuf::from).collect())
+}
+
+// Streams the contents of the files through 'git cat-file --batch' to the consumers, in place of the producers
+pub fn start_revision_reader_thread(files_of_repositories: Vec<(PathBuf, Vec<RevisionFile>)>, files_injector: Arc<Injector<ParsableFile>>)
+-> JoinHandle<()>
+{
+    thread::Builder::new().name("revision-reader".to_owned()).spawn(move || {
+        for (root, files) in files_of_repositories {
+            if let Err(x) = read_blobs(&root, files, &files_injector) {
+                println!("\n{}", format!("Error while reading the files of the git revision: {x}").yellow());
+            }
+        }
+    }).unwrap()
+}
+
+fn read_blobs(root: &Path, files: Vec<RevisionFile>, files_injector: &Injector<ParsableFile>) -> io::Result<()> {
+    let mut child = Command::new("git").arg("-C").arg(root).args(["cat-file", "--batch"])
+            .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    let object_ids = files.iter().map(|x| x.object_id.clone() + "\n").collect::<String>();
+    // Written from another thread, because git stops reading its input while its output is not consumed
+    let writer = thread::spawn(move || stdin.write_all(object_ids.as_bytes()));
+
+    let mut reader = BufReader::new(child.stdout.take().unwrap());
+    let mut header = String::new();
+    for file in files {
+        header.clear();
+        reader.read_line(&mut header)?;
+        // <object id> SP <type> SP <size> LF <contents> LF
+        let Some(bytes) = header.split_whitespace().nth(2).and_then(|x| x.parse::<usize>().ok()) else {
+            return Err(io::Error::other(header.trim().to_owned()));
+        };
+        let mut contents = vec![0; bytes + 1];
+        reader.read_exact(&mut contents)?;
+        contents.pop();
+        files_injector.push(ParsableFile::with_contents(file.path, file.language_name, contents));
+    }
+
+    let _ = writer.join();
+    child.wait()?;
+    Ok(())
+}
+
+// A new repository in a temp dir of its own, so that the tests can run in parallel. The tests that use it fail without
+// a git binary, instead of passing without testing anything.
+#[cfg(test)]
+pub(crate) fn temp_repository(name: &str) -> PathBuf {
+    static REPOSITORIES: AtomicUsize = AtomicUsize::new(0);
+    let root = std::env::temp_dir().join(format!("mezura_{name}_{}_{}", std::process::id(), REPOSITORIES.fetch_add(1, Ordering::Relaxed)));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    run_git(&root, &["init", "-q"]).expect("the git binary could not be executed");
+    // Like the paths that git reports
+    fs::canonicalize(&root).unwrap()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crossbeam_deque::Steal;
+
+    use super::*;
+
+    fn git(root: &Path, args: &[&str]) {
+        run_git(root, &[&["-c", "user.name=test", "-c", "user.email=test@test"], args].concat()).unwrap();
+    }
+
+    #[test]
+    fn test_the_files_of_a_revision_are_found_and_read_with_its_gitignore() {
+        let root = temp_repository("revision_test");
+        fs::create_dir_all(root.join("src").join("generated")).unwrap();
+        fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        fs::write(root.join("src").join("a.py"), "x = 1\n").unwrap();
+        fs::write(root.join("src").join("generated").join("b.py"), "y = 1\n").unwrap();
+        git(&root, &["add", "-f", "-A"]);
+        git(&root, &["commit", "-q", "-m", "first"]);
+        git(&root, &["rm", "-q", ".gitignore"]);
+        fs::write(root.join("src").join("a.py"), "x = 1\nx = 2\n").unwrap();
+        git(&root, &["commit", "-q", "-a", "-m", "second"]);
+
+        let config = Configuration::new(vec![root.to_str().unwrap().to_owned()]);
+        let extension_lang_map = Arc::new(HashMap::from([("py".to_owned(), Arc::from("Python"))]));
+        let languages_metadata_map: MetadataMapMut = Arc::new(Mutex::new(HashMap::from([("Python".to_owned(), LanguageMetadata::new(0, 0))])));
+        let context = TraversalContext::new(Arc::new(config), extension_lang_map, languages_metadata_map.clone(), Arc::new(Injector::new()));
+        let mut files_present = FilesPresent::default();
+        let files_of_repositories = find_revision_files("HEAD~1", &context, &mut files_present).unwrap();
+        // The .gitignore of the first commit ignores the generated dir, although it doesn't exist anymore
+        assert_eq!((1, vec![root.join("src").join("a.py")]), (files_present.relevant_files,
+                files_of_repositories[0].1.iter().map(|x| x.path.clone()).collect::<Vec<_>>()));
+        assert_eq!(6, languages_metadata_map.lock().unwrap()["Python"].bytes);
+
+        let files_injector = Arc::new(Injector::new());
+        start_revision_reader_thread(files_of_repositories, files_injector.clone()).join().unwrap();
+        let Steal::Success(file) = files_injector.steal() else { panic!("no file was read") };
+        assert_eq!(Some(b"x = 1\n".to_vec()), file.contents);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_the_commits_of_a_backfill_are_found_oldest_first() {
+        let root = temp_repository("backfill_test");
+        for i in 1..=5 {
+            fs::write(root.join("a.py"), format!("x = {i}\n")).unwrap();
+            git(&root, &["add", "-A"]);
+            // The commit dates order the commits of the log
+            let status = Command::new("git").arg("-C").arg(&root).args(["-c", "user.name=test", "-c", "user.email=test@test", "commit", "-q", "-m"])
+                    .arg(format!("commit {i}")).env("GIT_COMMITTER_DATE", format!("2024-01-0{i} 10:00:00 +0000")).status().unwrap();
+            assert!(status.success());
+        }
+        git(&root, &["tag", "v1", "HEAD~3"]);
+        git(&root, &["tag", "-a", "v2", "-m", "second release"]);
+
+        assert_eq!(DateTime::parse_from_rfc3339("2024-01-05T10:00:00+00:00").unwrap(), find_commits(&root, &Backfill::Tags).unwrap()[1].datetime);
+        let names = |backfill: &Backfill| find_commits(&root, backfill).unwrap().into_iter().map(|x| x.name).collect::<Vec<_>>();
+        assert_eq!(vec!["commit 1", "commit 3", "v2: commit 5"], names(&Backfill::Range("HEAD".to_owned(), 2)));
+        assert_eq!(vec!["commit 4", "v2: commit 5"], names(&Backfill::Range("HEAD~2..HEAD".to_owned(), 1)));
+        assert_eq!(vec!["v1: commit 2", "v2: commit 5"], names(&Backfill::Tags));
+        assert!(find_commits(&root, &Backfill::Range("nonexistent".to_owned(), 1)).is_err());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_the_changed_files_are_found_with_the_usual_filtering() {
+        let root = temp_repository("changed_files_test");
+        fs::create_dir_all(root.join("vendor")).unwrap();
+        fs::write(root.join(".gitignore"), "*.log.py\n").unwrap();
+        fs::write(root.join("a.py"), "x = 1\n").unwrap();
+        fs::write(root.join("b.py"), "y = 1\n").unwrap();
+        git(&root, &["add", "-A"]);
+        git(&root, &["commit", "-q", "-m", "first"]);
+        fs::write(root.join("a.py"), "x = 2\n").unwrap();
+        fs::write(root.join("c.py"), "z = 1\n").unwrap();
+        fs::write(root.join("d.log.py"), "w = 1\n").unwrap();
+        fs::write(root.join("vendor").join("e.py"), "v = 1\n").unwrap();
+        git(&root, &["add", "c.py"]);
+
+        let find = |config: &Configuration| {
+            let extension_lang_map = Arc::new(HashMap::from([("py".to_owned(), Arc::from("Python"))]));
+            let languages_metadata_map: MetadataMapMut = Arc::new(Mutex::new(HashMap::from([("Python".to_owned(), LanguageMetadata::new(0, 0))])));
+            let context = TraversalContext::new(Arc::new(config.clone()), extension_lang_map, languages_metadata_map, Arc::new(Injector::new()));
+            let mut files_present = FilesPresent::default();
+            find_changed_files(&context, &mut files_present).unwrap();
+            let mut paths = std::iter::from_fn(|| context.files_injector.steal().success()).map(|x| x.path).collect::<Vec<_>>();
+            paths.sort();
+            (files_present.excluded_files, paths)
+        };
+
+        let mut config = Configuration::new(vec![root.to_str().unwrap().to_owned()]);
+        config.set_changed_since(Some("HEAD".to_owned())).set_exclude_dirs(vec!["**/vendor".to_owned()]);
+        assert_eq!((0, vec![root.join("a.py"), root.join("c.py")]), find(&config));
+        config.set_changed_since(None).set_staged(true);
+        assert_eq!((0, vec![root.join("c.py")]), find(&config));
+        config.set_staged(false).set_changed_since(Some("HEAD".to_owned())).set_include_patterns(vec!["a.*".to_owned()]);
+        assert_eq!((1, vec![root.join("a.py")]), find(&config));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
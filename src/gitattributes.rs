@@ -0,0 +1,209 @@
+use crate::*;
+
+// The attributes of GitHub's linguist that a file has, None when no .gitattributes specifies them
+#[derive(Debug,PartialEq,Default,Clone)]
+pub struct LinguistAttributes {
+    pub vendored: Option<bool>,
+    pub generated: Option<bool>,
+    pub documentation: Option<bool>,
+    pub language: Option<String>
+}
+
+#[derive(Debug)]
+struct AttributesRule {
+    matcher: ignore::gitignore::Gitignore,
+    attributes: LinguistAttributes
+}
+
+// The linguist rules of the .gitattributes files of a dir and of its parent dirs
+#[derive(Debug)]
+pub struct GitattributesStack {
+    rules: Vec<AttributesRule>,
+    parent: Option<Arc<GitattributesStack>>
+}
+
+// The names and the common aliases of GitHub's linguist for the supported languages that the program names differently,
+// since the ones that only differ in case are matched anyway
+const LINGUIST_LANGUAGE_ALIASES: &[(&str, &str)] = &[
+    ("JavaScript", "JS"), ("js", "JS"), ("node", "JS"),
+    ("TypeScript", "TS"), ("ts", "TS"),
+    ("golang", "GO"),
+    ("python3", "Python"), ("py", "Python"),
+    ("rb", "Ruby"), ("jruby", "Ruby"), ("rake", "Ruby"),
+    ("rs", "Rust"),
+    ("cpp", "C++"),
+    ("csharp", "C#"),
+    ("sh", "Shell"), ("bash", "Shell"), ("zsh", "Shell"), ("shell-script", "Shell"),
+    ("xhtml", "HTML"),
+    ("Rscript", "R")
+];
+
+// The language that a 'linguist-language' value names, in the naming of the program
+pub fn to_language_name(linguist_name: &str) -> &str {
+    LINGUIST_LANGUAGE_ALIASES.iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(linguist_name))
+        .map_or(linguist_name, |(_, name)| name)
+}
+
+impl LinguistAttributes {
+    // Like the language bar of GitHub, which leaves these files out
+    pub fn is_excluded(&self) -> bool {
+        self.vendored == Some(true) || self.generated == Some(true) || self.documentation == Some(true)
+    }
+
+    // An attribute is set with "attr" or "attr=<value>" and unset with "-attr" or "!attr"
+    fn parse<'a>(tokens: impl Iterator<Item = &'a str>) -> LinguistAttributes {
+        let mut attributes = LinguistAttributes::default();
+        for token in tokens {
+            let (name, value) = match token.strip_prefix(['-', '!']) {
+                Some(name) => (name, None),
+                None => match token.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (token, Some("true"))
+                }
+            };
+            let is_set = value.is_some_and(|x| !x.eq_ignore_ascii_case("false"));
+            match name {
+                "linguist-vendored" => attributes.vendored = Some(is_set),
+                "linguist-generated" => attributes.generated = Some(is_set),
+                "linguist-documentation" => attributes.documentation = Some(is_set),
+                "linguist-language" => attributes.language = value.filter(|x| !x.is_empty()).map(|x| x.to_owned()),
+                _ => ()
+            }
+        }
+        attributes
+    }
+
+    fn is_empty(&self) -> bool {
+        *self == LinguistAttributes::default()
+    }
+
+    // The attributes that are already specified take precedence
+    fn fill_unspecified_from(&mut self, other: &LinguistAttributes) {
+        self.vendored = self.vendored.or(other.vendored);
+        self.generated = self.generated.or(other.generated);
+        self.documentation = self.documentation.or(other.documentation);
+        if self.language.is_none() {
+            self.language.clone_from(&other.language);
+        }
+    }
+}
+
+impl GitattributesStack {
+    pub fn extended(dir: &Path, parent: Option<Arc<GitattributesStack>>) -> Option<Arc<GitattributesStack>> {
+        match fs::read_to_string(dir.join(".gitattributes")) {
+            Ok(contents) => Self::extended_with_contents(dir, &contents, parent),
+            Err(_) => parent
+        }
+    }
+
+    // Only the lines with linguist attributes are kept. The patterns are matched like the ones of a .gitignore,
+    // while the negated patterns and the macros are skipped, like git does.
+    pub fn extended_with_contents(dir: &Path, contents: &str, parent: Option<Arc<GitattributesStack>>) -> Option<Arc<GitattributesStack>> {
+        let mut rules = Vec::new();
+        for line in contents.lines() {
+            let mut tokens = line.split_whitespace();
+            let Some(pattern) = tokens.next() else { continue };
+            if pattern.starts_with(['#', '!', '[']) {
+                continue;
+            }
+            let attributes = LinguistAttributes::parse(tokens);
+            if attributes.is_empty() {
+                continue;
+            }
+
+            let mut builder = ignore::gitignore::GitignoreBuilder::new(dir);
+            if builder.add_line(None, pattern).is_err() {
+                continue;
+            }
+            if let Ok(matcher) = builder.build() {
+                rules.push(AttributesRule { matcher, attributes });
+            }
+        }
+
+        if rules.is_empty() {
+            return parent;
+        }
+        Some(Arc::new(GitattributesStack { rules, parent }))
+    }
+
+    // The .gitattributes files of every dir between the repository root and the given dir, excluding it
+    pub fn for_root_dir(dir: &Path) -> Option<Arc<GitattributesStack>> {
+        if dir.join(".git").exists() {
+            return None;
+        }
+
+        let mut relevant_ancestors: Vec<&Path> = Vec::new();
+        for ancestor in dir.ancestors().skip(1) {
+            relevant_ancestors.push(ancestor);
+            if ancestor.join(".git").exists() {
+                break;
+            }
+        }
+
+        let mut stack = None;
+        for ancestor in relevant_ancestors.iter().rev() {
+            stack = Self::extended(ancestor, stack);
+        }
+        stack
+    }
+
+    // The later lines of a file and the files of the deeper dirs take precedence
+    pub fn attributes_of(&self, path: &Path) -> LinguistAttributes {
+        let mut attributes = LinguistAttributes::default();
+        let mut node = Some(self);
+        while let Some(stack) = node {
+            for rule in stack.rules.iter().rev() {
+                if rule.matcher.matched(path, false).is_ignore() {
+                    attributes.fill_unspecified_from(&rule.attributes);
+                }
+            }
+            node = stack.parent.as_deref();
+        }
+
+        attributes
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_the_later_and_deeper_rules_take_precedence() {
+        let root = Path::new("/repo");
+        let root_stack = GitattributesStack::extended_with_contents(root, "# comment\n*.min.js linguist-generated\nvendor/** linguist-vendored\n\
+                vendor/ours/** -linguist-vendored\n*.h linguist-language=C++ text\n!*.c linguist-vendored\n[attr]binary -diff\n", None).unwrap();
+        // Only the paths inside the dir of a .gitattributes are matched against it
+        let stack = GitattributesStack::extended_with_contents(&root.join("docs"), "*.h linguist-language=C linguist-documentation=true\n\
+                *.md linguist-documentation=false\n", Some(root_stack.clone())).unwrap();
+
+        assert_eq!(LinguistAttributes{generated: Some(true), ..Default::default()}, root_stack.attributes_of(&root.join("lib/a.min.js")));
+        assert!(root_stack.attributes_of(&root.join("vendor/lib/a.c")).is_excluded());
+        assert!(!root_stack.attributes_of(&root.join("vendor/ours/a.c")).is_excluded());
+        assert!(!root_stack.attributes_of(&root.join("src/a.c")).is_excluded());
+        assert_eq!(Some("C++".to_owned()), root_stack.attributes_of(&root.join("src/a.h")).language);
+        assert_eq!(LinguistAttributes{documentation: Some(true), language: Some("C".to_owned()), ..Default::default()},
+                stack.attributes_of(&root.join("docs/examples/a.h")));
+        assert_eq!(LinguistAttributes{documentation: Some(false), ..Default::default()}, stack.attributes_of(&root.join("docs/a.md")));
+        assert_eq!(LinguistAttributes{generated: Some(true), ..Default::default()}, stack.attributes_of(&root.join("docs/b.min.js")));
+    }
+
+    #[test]
+    fn test_the_language_names_of_linguist_are_matched_to_the_supported_languages() {
+        let extension_lang_map: HashMap<String, Arc<str>> = [("js", "JS"), ("go", "GO"), ("c", "C"), ("sh", "Shell")].into_iter()
+                .map(|(extension, name)| (extension.to_owned(), Arc::from(name))).collect();
+        let find_language = |path: &str, language: &str| find_language_of_file(&extension_lang_map, Path::new(path),
+                &LinguistAttributes{language: Some(language.to_owned()), ..Default::default()});
+
+        assert_eq!(Some(Arc::from("JS")), find_language("a.es", "JavaScript"));
+        assert_eq!(Some(Arc::from("GO")), find_language("a.tmpl", "Go"));
+        assert_eq!(Some(Arc::from("GO")), find_language("a.tmpl", "golang"));
+        assert_eq!(Some(Arc::from("Shell")), find_language("a.c", "bash"));
+        assert_eq!(Some(Arc::from("C")), find_language("a.c", "c"));
+        // An unsupported language leaves the extension to decide
+        assert_eq!(Some(Arc::from("C")), find_language("a.c", "Objective-C"));
+        assert_eq!(None, find_language("a.m", "Objective-C"));
+    }
+}
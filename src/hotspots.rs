@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use chrono::Duration;
+
+use crate::{*, config_manager::HotspotMetric};
+
+// The changes of a file in the time window
+#[derive(Debug,PartialEq,Default,Clone)]
+pub struct FileChurn {
+    pub commits: usize,
+    pub added_lines: usize,
+    pub removed_lines: usize
+}
+
+#[derive(Debug,PartialEq)]
+pub struct Hotspot {
+    pub path: PathBuf,
+    pub language_name: Arc<str>,
+    pub churn: FileChurn,
+    // The code lines or the complexity of the file, depending on the metric
+    pub metric_value: usize,
+    pub score: usize
+}
+
+// The churn of the files of the repositories of the target dirs, by their paths in the working tree. The window
+// ends at the date of the revision, if one is given, or else now.
+pub fn find_churn(target_dirs: &[String], rev: Option<&str>, window_days: usize) -> Result<HashMap<PathBuf,FileChurn>, String> {
+    let mut churn: HashMap<PathBuf,FileChurn> = HashMap::new();
+    let mut roots = HashSet::new();
+    for target in target_dirs {
+        let root = git::find_repository_root(Path::new(target))?;
+        if !roots.insert(root.clone()) {
+            continue;
+        }
+
+        let window_end = match rev {
+            Some(rev) => {
+                let output = git::run_git(&root, &["log", "-1", "--format=%cI", "--end-of-options", rev, "--"])?;
+                DateTime::parse_from_rfc3339(String::from_utf8_lossy(&output).trim()).map_err(|x| x.to_string())?
+            },
+            None => chrono::Local::now().fixed_offset()
+        };
+        let since = format!("--since={}", (window_end - Duration::days(window_days as i64)).to_rfc3339());
+        // Without a format, every commit lists only "<added> TAB <removed> TAB <path>" for each one of its files.
+        // The merges list nothing, so the changes of a branch are counted once.
+        let output = git::run_git(&root, &["log", "--numstat", "-z", "--no-renames", "--format=", &since, "--end-of-options",
+                rev.unwrap_or("HEAD"), "--"])?;
+        for entry in output.split(|x| *x == 0) {
+            let Ok(entry) = std::str::from_utf8(entry) else { continue };
+            let mut fields = entry.trim_start_matches('\n').splitn(3, '\t');
+            let (Some(added), Some(removed), Some(path)) = (fields.next(), fields.next(), fields.next()) else { continue };
+            let file_churn = churn.entry(root.join(path)).or_default();
+            file_churn.commits += 1;
+            // The binary files have dashes instead of numbers
+            file_churn.added_lines += added.parse::<usize>().unwrap_or(0);
+            file_churn.removed_lines += removed.parse::<usize>().unwrap_or(0);
+        }
+    }
+
+    Ok(churn)
+}
+
+// The files that changed in the window, ranked by their commits multiplied by the metric, the riskiest first.
+// The files with a score of 0 are left out.
+pub fn find_hotspots(files_details: &[FileDetails], churn: &HashMap<PathBuf,FileChurn>, metric: HotspotMetric) -> Vec<Hotspot> {
+    let mut hotspots = files_details.iter().filter_map(|details| {
+        let file_churn = churn.get(&details.path)?;
+        let metric_value = match metric {
+            HotspotMetric::Size => details.code_lines,
+            HotspotMetric::Complexity => details.complexity
+        };
+        let score = file_churn.commits * metric_value;
+        (score > 0).then(|| Hotspot{path: details.path.clone(), language_name: details.language_name.clone(), churn: file_churn.clone(),
+                metric_value, score})
+    }).collect::<Vec<_>>();
+    hotspots.sort_by(|a,b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+
+    hotspots
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn commit_at(root: &Path, date: &DateTime<FixedOffset>) {
+        let status = std::process::Command::new("git").arg("-C").arg(root).args(["-c", "user.name=test", "-c", "user.email=test@test", "commit",
+                "-q", "-a", "-m", "x"]).env("GIT_COMMITTER_DATE", date.to_rfc3339()).env("GIT_AUTHOR_DATE", date.to_rfc3339()).status().unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_the_files_that_change_often_are_ranked_first() {
+        let root = git::temp_repository("hotspots_test");
+        let date = |x: &str| DateTime::parse_from_rfc3339(x).unwrap();
+        fs::write(root.join("a.py"), "x = 1\n").unwrap();
+        git::run_git(&root, &["add", "-A"]).unwrap();
+        commit_at(&root, &date("2023-01-01T10:00:00+00:00"));
+        fs::write(root.join("a.py"), "x = 2\ny = 2\n").unwrap();
+        fs::write(root.join("b.py"), "if a:\n    b = 1\n").unwrap();
+        git::run_git(&root, &["add", "-A"]).unwrap();
+        commit_at(&root, &date("2024-01-05T10:00:00+00:00"));
+        fs::write(root.join("a.py"), "x = 3\ny = 2\n").unwrap();
+        commit_at(&root, &date("2024-01-10T10:00:00+00:00"));
+
+        // The window ends at the date of the revision, so the first commit is left out
+        let churn = find_churn(&[root.to_string_lossy().into_owned()], Some("HEAD"), 30).unwrap();
+        assert_eq!(FileChurn{commits: 2, added_lines: 3, removed_lines: 2}, churn[&root.join("a.py")]);
+        assert_eq!(FileChurn{commits: 1, added_lines: 2, removed_lines: 0}, churn[&root.join("b.py")]);
+
+        let details = |name: &str, code_lines: usize, complexity: usize| FileDetails{path: root.join(name), language_name: Arc::from("Python"),
+                lines: code_lines, code_lines, comment_keywords: 0, complexity, max_depth: 0, average_depth: 0.0, hygiene: None, content_hash: None,
+                authors: None};
+        let files_details = [details("a.py", 2, 0), details("b.py", 3, 1), details("c.py", 100, 10)];
+        let ranked = |metric| find_hotspots(&files_details, &churn, metric).into_iter().map(|x| (x.path, x.score)).collect::<Vec<_>>();
+        assert_eq!(vec![(root.join("a.py"), 4), (root.join("b.py"), 3)], ranked(HotspotMetric::Size));
+        assert_eq!(vec![(root.join("b.py"), 1)], ranked(HotspotMetric::Complexity));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
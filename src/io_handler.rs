@@ -1,10 +1,13 @@
-use std::{borrow::Cow, collections::HashMap, fs::{self, DirEntry, File}, io::{self, BufRead, BufReader, BufWriter, Write}, path::Path};
+use std::{borrow::Cow, collections::HashMap, fs::{self, DirEntry, File}, io::{self, BufRead, BufReader, BufWriter, Read, Write}, path::{Path, PathBuf}};
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, FixedOffset, Local};
 use colored::*;
 
-use crate::{Configuration, DEFAULT_CONFIG_NAME, FinalStats, Formatted, PERSISTENT_APP_PATHS, config_manager::{self, ConfigurationBuilder, LogOption,
-     MAX_COMPARE_LEVEL, MAX_CONSUMERS_VALUE, MAX_PRODUCERS_VALUE, MIN_COMPARE_LEVEL, MIN_CONSUMERS_VALUE, MIN_PRODUCERS_VALUE, Threads}, domain::*, split_line_on_whitespace, utils};
+use crate::{Configuration, DEFAULT_CONFIG_NAME, Estimation, FinalStats, Formatted, PERSISTENT_APP_PATHS,
+     config_manager::{self, CocomoCoefficients, ConfigurationBuilder, HotspotMetric, LogOption, MAX_CHURN_WINDOW, MAX_COLUMN_LIMIT,
+     MAX_COMPARE_LEVEL, MAX_CONSUMERS_VALUE, MAX_DUPLICATE_LINES, MAX_INDENT_WIDTH, MAX_MAX_DEPTH, MAX_PER_FILE_ENTRIES, MAX_PRODUCERS_VALUE,
+     MAX_SALARY, MIN_CHURN_WINDOW, MIN_COMPARE_LEVEL, MIN_CONSUMERS_VALUE, MIN_COLUMN_LIMIT, MIN_DUPLICATE_LINES, MIN_INDENT_WIDTH,
+     MIN_MAX_DEPTH, MIN_PER_FILE_ENTRIES, MIN_PRODUCERS_VALUE, MIN_SALARY, ModifiedSince, Threads}, domain::*, split_line_on_whitespace, utils};
 
 
 const LANGUAGE                 : &str = "Language";     
@@ -13,9 +16,15 @@ const STRING_SYMBOLS           : &str = "String symbols";
 const COMMENT_SYMBOLS          : &str = "Comment symbols";     
 const MULTILINE_COMMENT_START  : &str = "Multi line comment start";     
 const MULTILINE_COMMENT_END    : &str = "Multi line comment end";     
+const FUNCTION_DECLARATIONS    : &str = "Function declarations";     
+const BRANCH_TOKENS            : &str = "Branch tokens";     
+const BLOCK_SYMBOLS            : &str = "Block symbols";     
+const TEST_PATTERNS            : &str = "Test patterns";     
+const TEST_BLOCKS              : &str = "Test blocks";     
 const KEYWORD                  : &str = "Keyword";     
 const KEYWORD_NAME             : &str = "NAME";     
 const KEYWORD_ALIASES          : &str = "ALIASES";     
+const COMMENT_KEYWORD          : &str = "Comment keyword";     
 
 
 #[derive(Debug)]
@@ -116,8 +125,50 @@ fn parse_file_to_language(mut reader :my_reader::BufReader, buffer :&mut String)
         if !reader.read_line_exists(buffer) {return Err(())}
     }
     
+    let mut function_declarations = Vec::new();
+    let mut branch_tokens = Vec::new();
+    let mut block_symbols = Vec::new();
+    let mut test_patterns = Vec::new();
+    let mut test_blocks = Vec::new();
     let mut keywords = Vec::new();
+    let mut comment_keywords = Vec::new();
     while reader.read_line_exists(buffer) {
+        let is_comment_keyword = match buffer.trim_end() {
+            FUNCTION_DECLARATIONS => {
+                let Ok(declarations) = reader.get_line_sliced(buffer) else { return Err(()) };
+                if declarations[0].is_empty() {return Err(());}
+                function_declarations = declarations;
+                continue;
+            },
+            BRANCH_TOKENS => {
+                let Ok(tokens) = reader.get_line_sliced(buffer) else { return Err(()) };
+                if tokens[0].is_empty() {return Err(());}
+                branch_tokens = tokens;
+                continue;
+            },
+            BLOCK_SYMBOLS => {
+                let Ok(symbols) = reader.get_line_sliced(buffer) else { return Err(()) };
+                if symbols.len() != 2 {return Err(());}
+                block_symbols = symbols;
+                continue;
+            },
+            TEST_PATTERNS => {
+                let Ok(patterns) = reader.get_line_sliced(buffer) else { return Err(()) };
+                if patterns[0].is_empty() || utils::build_exclude_matcher(&patterns).is_err() {return Err(());}
+                test_patterns = patterns;
+                continue;
+            },
+            TEST_BLOCKS => {
+                let Ok(symbols) = reader.get_line_sliced(buffer) else { return Err(()) };
+                if symbols[0].is_empty() {return Err(());}
+                test_blocks = symbols;
+                continue;
+            },
+            KEYWORD => false,
+            COMMENT_KEYWORD => true,
+            "" => continue,
+            _ => return Err(())
+        };
         if !reader.read_lines_exist(2, buffer) {return Err(());}
         let name = buffer.trim().to_string().clone();
         if name.is_empty() {return Err(());}
@@ -129,7 +180,11 @@ fn parse_file_to_language(mut reader :my_reader::BufReader, buffer :&mut String)
             descriptive_name : name,
             aliases
         };
-        keywords.push(keyword);
+        if is_comment_keyword {
+            comment_keywords.push(keyword);
+        } else {
+            keywords.push(keyword);
+        }
     }
     
     Ok(Language {
@@ -139,7 +194,13 @@ fn parse_file_to_language(mut reader :my_reader::BufReader, buffer :&mut String)
         comment_symbols,
         multiline_comment_start_symbol : multi_start,
         multiline_comment_end_symbol : multi_end,
+        function_declarations,
+        branch_tokens,
+        block_symbols,
+        test_patterns,
+        test_blocks,
         keywords,
+        comment_keywords,
         finders : std::sync::OnceLock::new()
     })
 }
@@ -167,21 +228,58 @@ pub fn parse_string_to_language(contents: Cow<str>) -> Language {
         lines.next();
     }
 
+    let mut function_declarations = Vec::new();
+    let mut branch_tokens = Vec::new();
+    let mut block_symbols = Vec::new();
+    let mut test_patterns = Vec::new();
+    let mut test_blocks = Vec::new();
     let mut keywords = Vec::new();
+    let mut comment_keywords = Vec::new();
     while let Some(x) = lines.next() {
-        if x != KEYWORD {break;} 
+        let is_comment_keyword = match x.trim_end() {
+            FUNCTION_DECLARATIONS => {
+                function_declarations = split_line_on_whitespace(lines.next().unwrap());
+                continue;
+            },
+            BRANCH_TOKENS => {
+                branch_tokens = split_line_on_whitespace(lines.next().unwrap());
+                continue;
+            },
+            BLOCK_SYMBOLS => {
+                block_symbols = split_line_on_whitespace(lines.next().unwrap());
+                continue;
+            },
+            TEST_PATTERNS => {
+                test_patterns = split_line_on_whitespace(lines.next().unwrap());
+                continue;
+            },
+            TEST_BLOCKS => {
+                test_blocks = split_line_on_whitespace(lines.next().unwrap());
+                continue;
+            },
+            KEYWORD => false,
+            COMMENT_KEYWORD => true,
+            "" => continue,
+            _ => break
+        };
 
         lines.next();
         let k_name = lines.next().unwrap().trim().to_owned();
         lines.next();
         let k_aliases = split_line_on_whitespace(lines.next().unwrap());
-        keywords.push(Keyword{
+        let keyword = Keyword{
             descriptive_name: k_name,
             aliases: k_aliases
-        });
+        };
+        if is_comment_keyword {
+            comment_keywords.push(keyword);
+        } else {
+            keywords.push(keyword);
+        }
     }
 
-    Language::new(lang_name, extensions, string_symbols, comment_symbols, mult_start, mult_end, keywords)
+    Language::new(lang_name, extensions, string_symbols, comment_symbols, mult_start, mult_end, function_declarations, branch_tokens, block_symbols,
+            test_patterns, test_blocks, keywords, comment_keywords)
 }
 
 pub fn serialize_language(lang: &Language, path: &str) -> Result<(), io::Error> {
@@ -214,8 +312,40 @@ pub fn serialize_language(lang: &Language, path: &str) -> Result<(), io::Error>
     }
     writer.write_all(b"\n")?;
 
-    for keyword in lang.keywords.iter() {
-        writer.write_all(format!("{KEYWORD}\n").as_bytes())?;
+    if !lang.function_declarations.is_empty() {
+        writer.write_all(format!("{FUNCTION_DECLARATIONS}\n").as_bytes())?;
+        writer.write_all(lang.function_declarations.join(" ").as_bytes())?;
+        writer.write_all(b"\n\n")?;
+    }
+
+    if !lang.branch_tokens.is_empty() {
+        writer.write_all(format!("{BRANCH_TOKENS}\n").as_bytes())?;
+        writer.write_all(lang.branch_tokens.join(" ").as_bytes())?;
+        writer.write_all(b"\n\n")?;
+    }
+
+    if !lang.block_symbols.is_empty() {
+        writer.write_all(format!("{BLOCK_SYMBOLS}\n").as_bytes())?;
+        writer.write_all(lang.block_symbols.join(" ").as_bytes())?;
+        writer.write_all(b"\n\n")?;
+    }
+
+    if !lang.test_patterns.is_empty() {
+        writer.write_all(format!("{TEST_PATTERNS}\n").as_bytes())?;
+        writer.write_all(lang.test_patterns.join(" ").as_bytes())?;
+        writer.write_all(b"\n\n")?;
+    }
+
+    if !lang.test_blocks.is_empty() {
+        writer.write_all(format!("{TEST_BLOCKS}\n").as_bytes())?;
+        writer.write_all(lang.test_blocks.join(" ").as_bytes())?;
+        writer.write_all(b"\n\n")?;
+    }
+
+    let all_keywords = lang.keywords.iter().map(|x| (KEYWORD, x))
+        .chain(lang.comment_keywords.iter().map(|x| (COMMENT_KEYWORD, x)));
+    for (header, keyword) in all_keywords {
+        writer.write_all(format!("{header}\n").as_bytes())?;
         writer.write_all(format!("{KEYWORD_NAME}\n").as_bytes())?;
         writer.write_all(keyword.descriptive_name.as_bytes())?;
         writer.write_all(b"\n")?;
@@ -290,6 +420,33 @@ pub fn generate_palette_tuner_page() -> io::Result<String> {
 }
 
 
+// ------------------------------ Files list handling ------------------------------
+
+// "-" reads the standard input
+pub fn read_files_list(source: &str) -> io::Result<Vec<PathBuf>> {
+    let mut contents = Vec::new();
+    if source == "-" {
+        io::stdin().lock().read_to_end(&mut contents)?;
+    } else {
+        File::open(source)?.read_to_end(&mut contents)?;
+    }
+
+    Ok(parse_files_list(&contents))
+}
+
+// The paths are separated by NUL characters if there are any, or else by newlines
+pub fn parse_files_list(contents: &[u8]) -> Vec<PathBuf> {
+    let separator = if contents.contains(&0) {0} else {b'\n'};
+    contents.split(|x| *x == separator)
+        .map(|x| String::from_utf8_lossy(x))
+        .filter_map(|x| {
+            let path = if separator == 0 {x.as_ref()} else {x.trim_end_matches('\r')};
+            (!path.trim().is_empty()).then(|| PathBuf::from(path))
+        })
+        .collect()
+}
+
+
 // ------------------------------ Config handling ------------------------------
 
 pub fn parse_config_file(file_name: Option<&str>, config_dir_path: Option<String>) -> Result<(ConfigurationBuilder, Vec<&'static str>),ConfigFileParseError> {
@@ -301,9 +458,7 @@ pub fn parse_config_file(file_name: Option<&str>, config_dir_path: Option<String
         Err(_) => return Err(ConfigFileParseError::FileNotFound(file_name.to_owned()))
     });
 
-    let (mut dirs, mut braces_as_code, mut should_search_in_dotted, mut threads, mut exclude_dirs,
-         mut languages_of_interest, mut excluded_languages, mut should_show_faulty_files, mut no_keywords, mut no_visual,
-         mut no_gitignore, mut colors, mut color_palette, mut log, mut compare_level) = (None,None,None,None,None,None,None,None,None,None,None,None,None,None,None);
+    let mut config_builder = ConfigurationBuilder::default();
     let mut invalid_fields: Vec<&'static str> = Vec::new();
     let mut buf = String::with_capacity(150);
 
@@ -315,68 +470,230 @@ pub fn parse_config_file(file_name: Option<&str>, config_dir_path: Option<String
             if id == config_manager::DIRS {
                 let paths = read_lines_from_file_to_vec(&mut reader, &mut buf, utils::parse_paths_to_vec);
                 if !paths.is_empty() {
-                    dirs = Some(paths);
+                    config_builder.dirs = Some(paths);
                 }
             } else if id == config_manager::EXCLUDE {
                 let paths = read_lines_from_file_to_vec(&mut reader, &mut buf, utils::parse_paths_to_vec);
                 if utils::build_exclude_matcher(&paths).is_err() {
                     invalid_fields.push(config_manager::EXCLUDE);
                 } else if !paths.is_empty() {
-                    exclude_dirs = Some(paths);
+                    config_builder.exclude_dirs = Some(paths);
+                }
+            } else if id == config_manager::INCLUDE {
+                let patterns = read_lines_from_file_to_vec(&mut reader, &mut buf, utils::parse_paths_to_vec);
+                if utils::build_include_matcher(&patterns, &[]).is_err() {
+                    invalid_fields.push(config_manager::INCLUDE);
+                } else if !patterns.is_empty() {
+                    config_builder.include_patterns = Some(patterns);
                 }
             } else if id == config_manager::LANGUAGES {
                 let langs = read_lines_from_file_to_vec(&mut reader, &mut buf, utils::parse_languages_to_vec);
                 if !langs.is_empty() {
-                    languages_of_interest = Some(langs);
+                    config_builder.languages_of_interest = Some(langs);
                 }
             } else if id == config_manager::EXCLUDE_LANGUAGES {
                 let langs = read_lines_from_file_to_vec(&mut reader, &mut buf, utils::parse_languages_to_vec);
                 if !langs.is_empty() {
-                    excluded_languages = Some(langs);
+                    config_builder.excluded_languages = Some(langs);
                 }
             } else if id == config_manager::THREADS {
                 buf.clear();
                 let _ = reader.read_line(&mut buf);
                 match utils::parse_two_usize_values(&buf,MIN_PRODUCERS_VALUE, MAX_PRODUCERS_VALUE,
                         MIN_CONSUMERS_VALUE, MAX_CONSUMERS_VALUE) {
-                    Some(x) => threads = Some(Threads::from(x)),
+                    Some(x) => config_builder.threads = Some(Threads::from(x)),
                     None => invalid_fields.push(config_manager::THREADS)
                 }
             }else if id == config_manager::BRACES_AS_CODE {
                 match read_bool_value_from_file(&mut reader, &mut buf) {
-                    Ok(x) => braces_as_code = x,
+                    Ok(x) => config_builder.braces_as_code = x,
                     Err(()) => invalid_fields.push(config_manager::BRACES_AS_CODE)
                 }
             } else if id == config_manager::SHOW_FAULTY_FILES {
                 match read_bool_value_from_file(&mut reader, &mut buf) {
-                    Ok(x) => should_show_faulty_files = x,
+                    Ok(x) => config_builder.should_show_faulty_files = x,
                     Err(()) => invalid_fields.push(config_manager::SHOW_FAULTY_FILES)
                 }
             } else if id == config_manager::SEARCH_IN_DOTTED {
                 match read_bool_value_from_file(&mut reader, &mut buf) {
-                    Ok(x) => should_search_in_dotted = x,
+                    Ok(x) => config_builder.should_search_in_dotted = x,
                     Err(()) => invalid_fields.push(config_manager::SEARCH_IN_DOTTED)
                 }
             } else if id == config_manager::NO_KEYWORDS {
                 match read_bool_value_from_file(&mut reader, &mut buf) {
-                    Ok(x) => no_keywords = x,
+                    Ok(x) => config_builder.no_keywords = x,
                     Err(()) => invalid_fields.push(config_manager::NO_KEYWORDS)
                 }
             } else if id == config_manager::NO_VISUAL {
                 match read_bool_value_from_file(&mut reader, &mut buf) {
-                    Ok(x) => no_visual = x,
+                    Ok(x) => config_builder.no_visual = x,
                     Err(()) => invalid_fields.push(config_manager::NO_VISUAL)
                 }
             } else if id == config_manager::NO_GITIGNORE {
                 match read_bool_value_from_file(&mut reader, &mut buf) {
-                    Ok(x) => no_gitignore = x,
+                    Ok(x) => config_builder.no_gitignore = x,
                     Err(()) => invalid_fields.push(config_manager::NO_GITIGNORE)
                 }
+            } else if id == config_manager::NO_GITATTRIBUTES {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.no_gitattributes = x,
+                    Err(()) => invalid_fields.push(config_manager::NO_GITATTRIBUTES)
+                }
+            } else if id == config_manager::FOLLOW_SYMLINKS {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.follow_symlinks = x,
+                    Err(()) => invalid_fields.push(config_manager::FOLLOW_SYMLINKS)
+                }
+            } else if id == config_manager::MAX_DEPTH {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_usize_value(&buf, MIN_MAX_DEPTH, MAX_MAX_DEPTH) {
+                    Some(x) => config_builder.max_depth = Some(x),
+                    None => invalid_fields.push(config_manager::MAX_DEPTH)
+                }
+            } else if id == config_manager::MAX_FILE_SIZE {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_size_value(&buf) {
+                    Some(x) => config_builder.max_file_size = Some(x),
+                    None => invalid_fields.push(config_manager::MAX_FILE_SIZE)
+                }
+            } else if id == config_manager::MIN_FILE_SIZE {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_size_value(&buf) {
+                    Some(x) => config_builder.min_file_size = Some(x),
+                    None => invalid_fields.push(config_manager::MIN_FILE_SIZE)
+                }
+            } else if id == config_manager::MODIFIED_SINCE {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match ModifiedSince::parse(&buf) {
+                    Some(x) => config_builder.modified_since = Some(x),
+                    None => invalid_fields.push(config_manager::MODIFIED_SINCE)
+                }
+            } else if id == config_manager::EXCLUSION_REASONS {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.exclusion_reasons = x,
+                    Err(()) => invalid_fields.push(config_manager::EXCLUSION_REASONS)
+                }
+            } else if id == config_manager::FAIL_ON_TRAVERSAL_ERRORS {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.fail_on_traversal_errors = x,
+                    Err(()) => invalid_fields.push(config_manager::FAIL_ON_TRAVERSAL_ERRORS)
+                }
+            } else if id == config_manager::PER_FILE {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_usize_value(&buf, MIN_PER_FILE_ENTRIES, MAX_PER_FILE_ENTRIES) {
+                    Some(x) => config_builder.per_file = Some(x),
+                    None => invalid_fields.push(config_manager::PER_FILE)
+                }
+            } else if id == config_manager::INDENT_WIDTH {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_usize_value(&buf, MIN_INDENT_WIDTH, MAX_INDENT_WIDTH) {
+                    Some(x) => config_builder.indent_width = Some(x),
+                    None => invalid_fields.push(config_manager::INDENT_WIDTH)
+                }
+            } else if id == config_manager::HYGIENE {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.hygiene = x,
+                    Err(()) => invalid_fields.push(config_manager::HYGIENE)
+                }
+            } else if id == config_manager::DISTRIBUTION {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.distribution = x,
+                    Err(()) => invalid_fields.push(config_manager::DISTRIBUTION)
+                }
+            } else if id == config_manager::COLUMN_LIMIT {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_usize_value(&buf, MIN_COLUMN_LIMIT, MAX_COLUMN_LIMIT) {
+                    Some(x) => config_builder.column_limit = Some(x),
+                    None => invalid_fields.push(config_manager::COLUMN_LIMIT)
+                }
+            } else if id == config_manager::DUPLICATE_FILES {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.duplicate_files = x,
+                    Err(()) => invalid_fields.push(config_manager::DUPLICATE_FILES)
+                }
+            } else if id == config_manager::DEDUPE_FILES {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.dedupe_files = x,
+                    Err(()) => invalid_fields.push(config_manager::DEDUPE_FILES)
+                }
+            } else if id == config_manager::DUPLICATES {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_usize_value(&buf, MIN_DUPLICATE_LINES, MAX_DUPLICATE_LINES) {
+                    Some(x) => config_builder.duplicates = Some(x),
+                    None => invalid_fields.push(config_manager::DUPLICATES)
+                }
+            } else if id == config_manager::ESTIMATION {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.estimation = x,
+                    Err(()) => invalid_fields.push(config_manager::ESTIMATION)
+                }
+            } else if id == config_manager::COCOMO {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_positive_f64_values(&buf, 4) {
+                    Some(x) => config_builder.cocomo = Some(CocomoCoefficients::from(&x)),
+                    None => invalid_fields.push(config_manager::COCOMO)
+                }
+            } else if id == config_manager::SALARY {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_usize_value(&buf, MIN_SALARY, MAX_SALARY) {
+                    Some(x) => config_builder.salary = Some(x),
+                    None => invalid_fields.push(config_manager::SALARY)
+                }
+            } else if id == config_manager::TEST_PATTERNS {
+                let patterns = read_lines_from_file_to_vec(&mut reader, &mut buf, utils::parse_paths_to_vec);
+                if utils::build_exclude_matcher(&patterns).is_err() {
+                    invalid_fields.push(config_manager::TEST_PATTERNS);
+                } else if !patterns.is_empty() {
+                    config_builder.test_patterns = Some(patterns);
+                }
+            } else if id == config_manager::OWNERS {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.owners = x,
+                    Err(()) => invalid_fields.push(config_manager::OWNERS)
+                }
+            } else if id == config_manager::AUTHORS {
+                match read_bool_value_from_file(&mut reader, &mut buf) {
+                    Ok(x) => config_builder.authors = x,
+                    Err(()) => invalid_fields.push(config_manager::AUTHORS)
+                }
+            } else if id == config_manager::MAILMAP {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                let path = buf.trim();
+                if path.is_empty() || !Path::new(path).is_file() {
+                    invalid_fields.push(config_manager::MAILMAP);
+                } else {
+                    config_builder.mailmap = Some(path.to_owned());
+                }
+            } else if id == config_manager::HOTSPOTS {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match HotspotMetric::parse(buf.trim()) {
+                    Some(x) => config_builder.hotspots = Some(x),
+                    None => invalid_fields.push(config_manager::HOTSPOTS)
+                }
+            } else if id == config_manager::CHURN_WINDOW {
+                buf.clear();
+                let _ = reader.read_line(&mut buf);
+                match utils::parse_usize_value(&buf, MIN_CHURN_WINDOW, MAX_CHURN_WINDOW) {
+                    Some(x) => config_builder.churn_window = Some(x),
+                    None => invalid_fields.push(config_manager::CHURN_WINDOW)
+                }
             } else if id == config_manager::COLORS {
                 buf.clear();
                 let _ = reader.read_line(&mut buf);
                 match utils::parse_colors_to_vec(&buf) {
-                    Some(x) => colors = Some(x),
+                    Some(x) => config_builder.colors = Some(x),
                     None => invalid_fields.push(config_manager::COLORS)
                 }
             } else if id == config_manager::COLOR_PALETTE {
@@ -386,22 +703,22 @@ pub fn parse_config_file(file_name: Option<&str>, config_dir_path: Option<String
                 if name.is_empty() || load_palette(name, &PERSISTENT_APP_PATHS.palettes_dir).is_none() {
                     invalid_fields.push(config_manager::COLOR_PALETTE);
                 } else {
-                    color_palette = Some(name.to_owned());
+                    config_builder.color_palette = Some(name.to_owned());
                 }
             } else if id == config_manager::LOG {
                 buf.clear();
                 let _ = reader.read_line(&mut buf);
                 let name = &buf.trim().to_lowercase();
                 if name == "yes" || name == "true" {
-                    log = Some(LogOption::new(None));
+                    config_builder.log = Some(LogOption::new(None));
                 } else if name != "no" && name != "false"{
-                    log = Some(LogOption::new(Some(name.to_owned())));
+                    config_builder.log = Some(LogOption::new(Some(name.to_owned())));
                 }
             } else if id == config_manager::COMPRARE_LEVEL {
                 buf.clear();
                 let _ = reader.read_line(&mut buf);
                 match utils::parse_usize_value(&buf,MIN_COMPARE_LEVEL, MAX_COMPARE_LEVEL) {
-                    Some(x) => compare_level = Some(x),
+                    Some(x) => config_builder.compare_level = Some(x),
                     None => invalid_fields.push(config_manager::COMPRARE_LEVEL)
                 }
             }
@@ -409,8 +726,7 @@ pub fn parse_config_file(file_name: Option<&str>, config_dir_path: Option<String
         buf.clear();
     }
 
-    Ok((ConfigurationBuilder::new(dirs,exclude_dirs, languages_of_interest, excluded_languages, threads, braces_as_code,should_search_in_dotted,
-             should_show_faulty_files, no_keywords, no_visual, no_gitignore, colors, color_palette, log, compare_level, None, None), invalid_fields))
+    Ok((config_builder, invalid_fields))
 }
 
 // Dirs must be specified (is checked before calling this function)
@@ -431,6 +747,10 @@ pub fn save_existing_commands_from_config_builder_to_file(config_path: Option<St
         writer.write_all(&[b"\n\n===> ",config_manager::EXCLUDE.as_bytes(),b"\n"].concat())?;
         writer.write_all(exclude_dirs.join(",").as_bytes())?;
     }
+    if let Some(include_patterns) = &config_builder.include_patterns {
+        writer.write_all(&[b"\n\n===> ",config_manager::INCLUDE.as_bytes(),b"\n"].concat())?;
+        writer.write_all(include_patterns.join(",").as_bytes())?;
+    }
     if let Some(languages_of_interest) = &config_builder.languages_of_interest {
         writer.write_all(&[b"\n\n===> ",config_manager::LANGUAGES.as_bytes(),b"\n"].concat())?;
         writer.write_all(languages_of_interest.join(",").as_bytes())?;
@@ -467,6 +787,106 @@ pub fn save_existing_commands_from_config_builder_to_file(config_path: Option<St
         writer.write_all(&[b"\n\n===> ",config_manager::NO_GITIGNORE.as_bytes(),b"\n"].concat())?;
         writer.write_all(if *no_gitignore {b"yes"} else {b"no"})?;
     }
+    if let Some(no_gitattributes) = &config_builder.no_gitattributes {
+        writer.write_all(&[b"\n\n===> ",config_manager::NO_GITATTRIBUTES.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *no_gitattributes {b"yes"} else {b"no"})?;
+    }
+    if let Some(follow_symlinks) = &config_builder.follow_symlinks {
+        writer.write_all(&[b"\n\n===> ",config_manager::FOLLOW_SYMLINKS.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *follow_symlinks {b"yes"} else {b"no"})?;
+    }
+    if let Some(max_depth) = &config_builder.max_depth {
+        writer.write_all(&[b"\n\n===> ",config_manager::MAX_DEPTH.as_bytes(),b"\n"].concat())?;
+        writer.write_all(max_depth.to_string().as_bytes())?;
+    }
+    if let Some(max_file_size) = &config_builder.max_file_size {
+        writer.write_all(&[b"\n\n===> ",config_manager::MAX_FILE_SIZE.as_bytes(),b"\n"].concat())?;
+        writer.write_all(max_file_size.to_string().as_bytes())?;
+    }
+    if let Some(min_file_size) = &config_builder.min_file_size {
+        writer.write_all(&[b"\n\n===> ",config_manager::MIN_FILE_SIZE.as_bytes(),b"\n"].concat())?;
+        writer.write_all(min_file_size.to_string().as_bytes())?;
+    }
+    if let Some(modified_since) = &config_builder.modified_since {
+        writer.write_all(&[b"\n\n===> ",config_manager::MODIFIED_SINCE.as_bytes(),b"\n"].concat())?;
+        writer.write_all(modified_since.to_config_string().as_bytes())?;
+    }
+    if let Some(exclusion_reasons) = &config_builder.exclusion_reasons {
+        writer.write_all(&[b"\n\n===> ",config_manager::EXCLUSION_REASONS.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *exclusion_reasons {b"yes"} else {b"no"})?;
+    }
+    if let Some(fail_on_traversal_errors) = &config_builder.fail_on_traversal_errors {
+        writer.write_all(&[b"\n\n===> ",config_manager::FAIL_ON_TRAVERSAL_ERRORS.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *fail_on_traversal_errors {b"yes"} else {b"no"})?;
+    }
+    if let Some(per_file) = &config_builder.per_file {
+        writer.write_all(&[b"\n\n===> ",config_manager::PER_FILE.as_bytes(),b"\n"].concat())?;
+        writer.write_all(per_file.to_string().as_bytes())?;
+    }
+    if let Some(indent_width) = &config_builder.indent_width {
+        writer.write_all(&[b"\n\n===> ",config_manager::INDENT_WIDTH.as_bytes(),b"\n"].concat())?;
+        writer.write_all(indent_width.to_string().as_bytes())?;
+    }
+    if let Some(hygiene) = &config_builder.hygiene {
+        writer.write_all(&[b"\n\n===> ",config_manager::HYGIENE.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *hygiene {b"yes"} else {b"no"})?;
+    }
+    if let Some(distribution) = &config_builder.distribution {
+        writer.write_all(&[b"\n\n===> ",config_manager::DISTRIBUTION.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *distribution {b"yes"} else {b"no"})?;
+    }
+    if let Some(column_limit) = &config_builder.column_limit {
+        writer.write_all(&[b"\n\n===> ",config_manager::COLUMN_LIMIT.as_bytes(),b"\n"].concat())?;
+        writer.write_all(column_limit.to_string().as_bytes())?;
+    }
+    if let Some(duplicate_files) = &config_builder.duplicate_files {
+        writer.write_all(&[b"\n\n===> ",config_manager::DUPLICATE_FILES.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *duplicate_files {b"yes"} else {b"no"})?;
+    }
+    if let Some(dedupe_files) = &config_builder.dedupe_files {
+        writer.write_all(&[b"\n\n===> ",config_manager::DEDUPE_FILES.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *dedupe_files {b"yes"} else {b"no"})?;
+    }
+    if let Some(duplicates) = &config_builder.duplicates {
+        writer.write_all(&[b"\n\n===> ",config_manager::DUPLICATES.as_bytes(),b"\n"].concat())?;
+        writer.write_all(duplicates.to_string().as_bytes())?;
+    }
+    if let Some(estimation) = &config_builder.estimation {
+        writer.write_all(&[b"\n\n===> ",config_manager::ESTIMATION.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *estimation {b"yes"} else {b"no"})?;
+    }
+    if let Some(cocomo) = &config_builder.cocomo {
+        writer.write_all(&[b"\n\n===> ",config_manager::COCOMO.as_bytes(),b"\n"].concat())?;
+        writer.write_all(format!("{} {} {} {}", cocomo.a, cocomo.b, cocomo.c, cocomo.d).as_bytes())?;
+    }
+    if let Some(salary) = &config_builder.salary {
+        writer.write_all(&[b"\n\n===> ",config_manager::SALARY.as_bytes(),b"\n"].concat())?;
+        writer.write_all(salary.to_string().as_bytes())?;
+    }
+    if let Some(test_patterns) = &config_builder.test_patterns {
+        writer.write_all(&[b"\n\n===> ",config_manager::TEST_PATTERNS.as_bytes(),b"\n"].concat())?;
+        writer.write_all(test_patterns.join(",").as_bytes())?;
+    }
+    if let Some(owners) = &config_builder.owners {
+        writer.write_all(&[b"\n\n===> ",config_manager::OWNERS.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *owners {b"yes"} else {b"no"})?;
+    }
+    if let Some(authors) = &config_builder.authors {
+        writer.write_all(&[b"\n\n===> ",config_manager::AUTHORS.as_bytes(),b"\n"].concat())?;
+        writer.write_all(if *authors {b"yes"} else {b"no"})?;
+    }
+    if let Some(mailmap) = &config_builder.mailmap {
+        writer.write_all(&[b"\n\n===> ",config_manager::MAILMAP.as_bytes(),b"\n"].concat())?;
+        writer.write_all(mailmap.as_bytes())?;
+    }
+    if let Some(hotspots) = &config_builder.hotspots {
+        writer.write_all(&[b"\n\n===> ",config_manager::HOTSPOTS.as_bytes(),b"\n"].concat())?;
+        writer.write_all(hotspots.as_str().as_bytes())?;
+    }
+    if let Some(churn_window) = &config_builder.churn_window {
+        writer.write_all(&[b"\n\n===> ",config_manager::CHURN_WINDOW.as_bytes(),b"\n"].concat())?;
+        writer.write_all(churn_window.to_string().as_bytes())?;
+    }
     if let Some(colors) = &config_builder.colors {
         writer.write_all(&[b"\n\n===> ",config_manager::COLORS.as_bytes(),b"\n"].concat())?;
         writer.write_all(colors.iter().map(utils::color_to_config_string)
@@ -499,10 +919,14 @@ pub fn write_default_config(contents: String) -> Result<(), io::Error> {
 
 // ----------------------------------- Log handling ------------------------------------------
 
-pub fn log_stats(path: &str, contents: &Option<String>, final_stats: &FinalStats, datetime_now: &DateTime<Local>, config: &Configuration) -> io::Result<()> {
+const LOG_DATETIME_FORMAT : &str = "%Y-%m-%d %H:%M:%S %z";
+
+pub fn log_stats(path: &str, contents: &Option<String>, content_info_map: &HashMap<String,LanguageContentInfo>, final_stats: &FinalStats,
+        datetime_now: &DateTime<Local>, config: &Configuration) -> io::Result<()>
+{
     let mut writer = std::io::BufWriter::new(std::fs::OpenOptions::new().write(true).create(true).truncate(true).open(path)?);
 
-    write_current_log(&mut writer, config, datetime_now, final_stats)?;
+    write_current_log(&mut writer, config, &datetime_now.fixed_offset(), content_info_map, final_stats)?;
 
     if let Some(contents) = contents {
         writer.write_all(contents.as_bytes())?;
@@ -512,9 +936,65 @@ pub fn log_stats(path: &str, contents: &Option<String>, final_stats: &FinalStats
     Ok(())
 }
 
-fn write_current_log(writer: &mut BufWriter<File>, config: &Configuration, datetime_now: &DateTime<Local>, final_stats: &FinalStats) -> io::Result<()> {
+pub fn format_log_entry(config: &Configuration, datetime: &DateTime<FixedOffset>, content_info_map: &HashMap<String,LanguageContentInfo>,
+        final_stats: &FinalStats) -> String
+{
+    let mut entry = Vec::new();
+    // Writing to a vec can't fail
+    let _ = write_current_log(&mut entry, config, datetime, content_info_map, final_stats);
+    String::from_utf8_lossy(&entry).into_owned()
+}
+
+// The entries are placed among the existing ones by their dates, the newest first. An entry with the same name
+// and date as an existing one is skipped, so that adding the same entries again doesn't duplicate them.
+pub fn add_entries_to_log(path: &str, contents: &Option<String>, entries: Vec<(DateTime<FixedOffset>, String)>) -> io::Result<usize> {
+    let mut all_entries = split_log_entries(contents.as_deref().unwrap_or_default());
+    let mut added = 0;
+    for (datetime, entry) in entries {
+        let header = entry.lines().take(2).collect::<Vec<_>>();
+        if all_entries.iter().any(|(_, x)| x.lines().take(2).eq(header.iter().copied())) {
+            continue;
+        }
+        all_entries.push((datetime, Cow::Owned(entry)));
+        added += 1;
+    }
+    // Stable, so that the entries with the same date keep their order
+    all_entries.sort_by_key(|x| std::cmp::Reverse(x.0));
+
+    let mut writer = BufWriter::new(std::fs::OpenOptions::new().write(true).create(true).truncate(true).open(path)?);
+    for (_, entry) in all_entries {
+        writer.write_all(entry.as_bytes())?;
+    }
+    writer.flush()?;
+
+    Ok(added)
+}
+
+// Every entry starts with its name line, followed by its date
+fn split_log_entries(contents: &str) -> Vec<(DateTime<FixedOffset>, Cow<'_, str>)> {
+    let mut starts = contents.match_indices("===>").map(|x| x.0).filter(|x| *x == 0 || contents.as_bytes()[x - 1] == b'\n').collect::<Vec<_>>();
+    if starts.first().is_some_and(|x| *x != 0) {
+        starts.insert(0, 0);
+    }
+
+    let mut entries: Vec<(DateTime<FixedOffset>, Cow<str>)> = Vec::with_capacity(starts.len());
+    for (i, start) in starts.iter().enumerate() {
+        let entry = &contents[*start..starts.get(i + 1).copied().unwrap_or(contents.len())];
+        // Whatever can't be dated stays right after the entry that precedes it
+        let datetime = entry.lines().nth(1).and_then(|x| DateTime::parse_from_str(x.trim(), LOG_DATETIME_FORMAT).ok())
+                .or_else(|| entries.last().map(|x| x.0))
+                .unwrap_or_else(|| Local::now().fixed_offset());
+        entries.push((datetime, Cow::Borrowed(entry)));
+    }
+
+    entries
+}
+
+fn write_current_log(writer: &mut impl Write, config: &Configuration, datetime: &DateTime<FixedOffset>,
+        content_info_map: &HashMap<String,LanguageContentInfo>, final_stats: &FinalStats) -> io::Result<()>
+{
     writer.write_all(format!("===>{}\n",config.log.name.clone().unwrap_or_default()).as_bytes())?;
-    writer.write_all(datetime_now.format("%Y-%m-%d %H:%M:%S %z").to_string().as_bytes())?;
+    writer.write_all(datetime.format(LOG_DATETIME_FORMAT).to_string().as_bytes())?;
     writer.write_all(b"\n")?;
     writer.write_all(b"Configuration:\n")?;
     writer.write_all(format!("    dirs: {}\n",config.dirs.join(",")).as_bytes())?;
@@ -529,12 +1009,80 @@ fn write_current_log(writer: &mut BufWriter<File>, config: &Configuration, datet
     writer.write_all(format!("        Code: {}\n",final_stats.code_lines).as_bytes())?;
     writer.write_all(format!("        Extra: {}\n",final_stats.extra_lines).as_bytes())?;
     writer.write_all(format!("    Total Size: {}\n",final_stats.bytes_size).as_bytes())?;
-    writer.write_all(format!("        Average Size: {}\n\n\n",final_stats.bytes_average_size).as_bytes())?;
+    writer.write_all(format!("        Average Size: {}\n",final_stats.bytes_average_size).as_bytes())?;
+    if !config.no_keywords {
+        write_comment_keywords_log(writer, content_info_map)?;
+    }
+    write_nesting_log(writer, content_info_map)?;
+    write_tests_log(writer, content_info_map, final_stats)?;
+    if config.estimation {
+        write_estimation_log(writer, final_stats, config)?;
+    }
+    writer.write_all(b"\n\n")?;
     writer.write_all(b"--------------------------------------------------------------------------------------------\n\n\n")?;
 
     Ok(())
 }
 
+fn write_comment_keywords_log(writer: &mut impl Write, content_info_map: &HashMap<String,LanguageContentInfo>) -> io::Result<()> {
+    let mut comment_keywords_sum: HashMap<&str,usize> = HashMap::new();
+    for content_info in content_info_map.values() {
+        for (name, occurences) in content_info.comment_keyword_occurences.iter() {
+            *comment_keywords_sum.entry(name).or_insert(0) += *occurences;
+        }
+    }
+    let mut sorted_comment_keywords = comment_keywords_sum.into_iter().collect::<Vec<_>>();
+    sorted_comment_keywords.sort_unstable();
+
+    writer.write_all(format!("    Comment markers: {}\n", sorted_comment_keywords.iter().map(|x| x.1).sum::<usize>()).as_bytes())?;
+    for (name, occurences) in sorted_comment_keywords {
+        writer.write_all(format!("        {name}: {occurences}\n").as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_nesting_log(writer: &mut impl Write, content_info_map: &HashMap<String,LanguageContentInfo>) -> io::Result<()> {
+    let mut depth_histogram = Vec::new();
+    content_info_map.values().for_each(|x| merge_depth_histograms(&mut depth_histogram, &x.depth_histogram));
+    let (Some(max_depth), Some(average_depth)) = (max_depth_of(&depth_histogram), average_depth_of(&depth_histogram)) else {
+        return Ok(())
+    };
+
+    writer.write_all(format!("    Max nesting: {max_depth}\n").as_bytes())?;
+    writer.write_all(format!("        Average nesting: {average_depth:.2}\n").as_bytes())?;
+    for (depth, lines) in depth_histogram_entries(&depth_histogram) {
+        writer.write_all(format!("        Depth {depth}: {lines}\n").as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_tests_log(writer: &mut impl Write, content_info_map: &HashMap<String,LanguageContentInfo>, final_stats: &FinalStats)
+-> io::Result<()>
+{
+    let total_test_code_lines = content_info_map.values().map(|x| x.test_code_lines).sum::<usize>();
+    writer.write_all(format!("    Test code lines: {total_test_code_lines}\n").as_bytes())?;
+    writer.write_all(format!("        Non-test code lines: {}\n", final_stats.code_lines - total_test_code_lines).as_bytes())?;
+    let mut sorted_languages = content_info_map.iter().filter(|(_,x)| x.code_lines > 0).collect::<Vec<_>>();
+    sorted_languages.sort_unstable_by_key(|(name,_)| name.as_str());
+    for (name, content_info) in sorted_languages {
+        writer.write_all(format!("        {name}: {} test , {} non-test\n", content_info.test_code_lines,
+                content_info.code_lines - content_info.test_code_lines).as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write_estimation_log(writer: &mut impl Write, final_stats: &FinalStats, config: &Configuration) -> io::Result<()> {
+    let estimation = Estimation::calculate(final_stats.code_lines, &config.cocomo, config.salary);
+    writer.write_all(format!("    Estimated effort: {:.2}\n", estimation.effort).as_bytes())?;
+    writer.write_all(format!("        Schedule: {:.2}\n", estimation.schedule).as_bytes())?;
+    writer.write_all(format!("        People: {:.2}\n", estimation.people).as_bytes())?;
+    writer.write_all(format!("        Cost: {:.0}\n", estimation.cost).as_bytes())?;
+
+    Ok(())
+}
 
 fn read_bool_value_from_file(reader: &mut BufReader<File>, buf: &mut String) -> Result<Option<bool>, ()> {
     buf.clear();
@@ -670,7 +1218,11 @@ mod tests {
 
     #[test]
     fn test_save_config_file_and_then_parse_it() -> std::io::Result<()> {
-        let command = "./ --exclude a,b,c.txt,d.txt, --braces-as-code --threads 1 1".to_string();
+        let command = "./ --exclude a,b,c.txt,d.txt, --include src/**,*.rs --follow-symlinks --max-depth 3 --max-file-size 2MB \
+                --min-file-size 10 --modified-since 30d --exclusion-reasons --fail-on-traversal-errors --braces-as-code --threads 1 1 \
+                --per-file 15 --indent-width 2 --hygiene --distribution --column-limit 80 --dedupe-files --duplicates 6 \
+                --cocomo 3 1.12 2.5 0.35 --salary 80000 --test-patterns *_spec.lua,**/testdata/** --owners --authors \
+                --hotspots complexity --churn-window 90".to_string();
         let config_builder = config_manager::create_config_builder_from_args(&command).unwrap();
 
         let test_config_dir = Some(LOCAL_APP_PATHS.test_config_dir.clone());
@@ -680,11 +1232,34 @@ mod tests {
         assert!(invalid_fields.is_empty());
         assert_eq!(config_builder.dirs, options.dirs);
         assert_eq!(config_builder.exclude_dirs, options.exclude_dirs);
+        assert_eq!(config_builder.include_patterns, options.include_patterns);
+        assert_eq!(Some(true), options.follow_symlinks);
+        assert_eq!(Some(3), options.max_depth);
+        assert_eq!(Some(2_000_000), options.max_file_size);
+        assert_eq!(Some(10), options.min_file_size);
+        assert_eq!(Some(config_manager::ModifiedSince::Days(30)), options.modified_since);
+        assert_eq!(Some(true), options.exclusion_reasons);
+        assert_eq!(Some(true), options.fail_on_traversal_errors);
         assert_eq!(config_builder.threads, options.threads);
         assert_eq!(config_builder.braces_as_code, options.braces_as_code);
         assert_eq!(config_builder.should_show_faulty_files, options.should_show_faulty_files);
         assert_eq!(config_builder.should_search_in_dotted, options.should_search_in_dotted);
         assert_eq!(config_builder.no_visual, options.no_visual);
+        assert_eq!(Some(15), options.per_file);
+        assert_eq!(Some(2), options.indent_width);
+        assert_eq!(Some(true), options.hygiene);
+        assert_eq!(Some(true), options.distribution);
+        assert_eq!(Some(80), options.column_limit);
+        assert_eq!(None, options.duplicate_files);
+        assert_eq!(Some(true), options.dedupe_files);
+        assert_eq!(Some(6), options.duplicates);
+        assert_eq!(Some(config_manager::CocomoCoefficients::new(3.0, 1.12, 2.5, 0.35)), options.cocomo);
+        assert_eq!(Some(80000), options.salary);
+        assert_eq!(Some(vec!["*_spec.lua".to_owned(), "**/testdata/**".to_owned()]), options.test_patterns);
+        assert_eq!(Some(true), options.owners);
+        assert_eq!(Some(true), options.authors);
+        assert_eq!(Some(config_manager::HotspotMetric::Complexity), options.hotspots);
+        assert_eq!(Some(90), options.churn_window);
 
         Ok(())
     }
@@ -757,18 +1332,52 @@ mod tests {
         let dir_str = dir.to_str().unwrap().to_owned() + "/";
 
         std::fs::write(dir.join("badcfg.txt"),
-                "===> threads\n3343 45534\n\n===> braces-as-code\nmitsos\n\n===> compare\n99\n\n===> no-visual\nyes\n").unwrap();
+                "===> threads\n3343 45534\n\n===> braces-as-code\nmitsos\n\n===> compare\n99\n\n===> no-visual\nyes\n\n\
+                ===> per-file\n500\n\n===> indent-width\n0\n").unwrap();
 
         let (options, invalid_fields) = io_handler::parse_config_file(Some("badcfg"), Some(dir_str)).unwrap();
-        assert_eq!(invalid_fields, vec![config_manager::THREADS, config_manager::BRACES_AS_CODE, config_manager::COMPRARE_LEVEL]);
+        assert_eq!(invalid_fields, vec![config_manager::THREADS, config_manager::BRACES_AS_CODE, config_manager::COMPRARE_LEVEL,
+                config_manager::PER_FILE, config_manager::INDENT_WIDTH]);
         assert_eq!(options.threads, None);
         assert_eq!(options.braces_as_code, None);
         assert_eq!(options.compare_level, None);
         assert_eq!(options.no_visual, Some(true));
+        assert_eq!(options.per_file, None);
 
         std::fs::remove_dir_all(&dir).unwrap();
     }
 
+    #[test]
+    fn test_files_lists_are_split_on_nul_or_on_newlines() {
+        assert_eq!(vec![PathBuf::from("a.rs"), PathBuf::from("src/b c.rs")], io_handler::parse_files_list(b"a.rs\r\n\nsrc/b c.rs\n"));
+        assert_eq!(vec![PathBuf::from("a\nb.rs"), PathBuf::from("c.rs")], io_handler::parse_files_list(b"a\nb.rs\0c.rs\0"));
+        assert!(io_handler::parse_files_list(b"").is_empty());
+    }
+
+    #[test]
+    fn test_log_entries_are_added_by_their_dates() -> std::io::Result<()> {
+        let dir = std::env::temp_dir().join("mezura_backfill_log_test");
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("log").to_str().unwrap().to_owned();
+
+        let config = Configuration::new(vec!["/a".to_owned()]);
+        let entry = |name: &str, date: &str| {
+            let datetime = DateTime::parse_from_str(date, super::LOG_DATETIME_FORMAT).unwrap();
+            let mut config = config.clone();
+            config.log.name = Some(name.to_owned());
+            (datetime, io_handler::format_log_entry(&config, &datetime, &HashMap::new(), &FinalStats::new(1, 10, 8, 100)))
+        };
+        let existing = Some(entry("new", "2024-03-01 10:00:00 +0000").1 + &entry("old", "2024-01-01 10:00:00 +0000").1);
+
+        let added = io_handler::add_entries_to_log(&path, &existing, vec![entry("middle", "2024-02-01 12:00:00 +0200"),
+                entry("old", "2024-01-01 10:00:00 +0000"), entry("oldest", "2023-12-31 23:00:00 -0500")])?;
+        assert_eq!(2, added);
+        let names = std::fs::read_to_string(&path)?.lines().filter_map(|x| x.strip_prefix("===>").map(|x| x.to_owned())).collect::<Vec<_>>();
+        assert_eq!(vec!["new", "middle", "old", "oldest"], names);
+
+        std::fs::remove_dir_all(&dir)
+    }
+
     #[test]
     fn test_serialize_language_overwrites_longer_existing_file() {
         let dir = std::env::temp_dir().join("mezura_serialize_truncate_test");
@@ -782,11 +1391,16 @@ mod tests {
 
         let long_lang = Language::new("Truncatetest".to_owned(), vec!["trnc".to_owned()], vec!["\"".to_owned()],
                 vec!["//".to_owned()], Some("/*".to_owned()), Some("*/".to_owned()),
-                (0..20).map(|i| keyword(&format!("keyword{i}"))).collect());
+                vec!["fn".to_owned(), "proc".to_owned()], vec!["if".to_owned(), "&&".to_owned()],
+                vec!["{".to_owned(), "}".to_owned()], vec!["*_test.trnc".to_owned(), "**/tests/**".to_owned()], vec!["#[test]".to_owned()],
+                (0..20).map(|i| keyword(&format!("keyword{i}"))).collect(),
+                vec![keyword("TODO"), keyword("FIXME")]);
         io_handler::serialize_language(&long_lang, &dir_str).unwrap();
 
         let short_lang = Language::new("Truncatetest".to_owned(), vec!["trnc".to_owned()], vec!["\"".to_owned()],
-                vec!["//".to_owned()], Some("/*".to_owned()), Some("*/".to_owned()), vec![keyword("keyword0")]);
+                vec!["//".to_owned()], Some("/*".to_owned()), Some("*/".to_owned()), vec!["fn".to_owned()], vec!["?".to_owned()],
+                vec![], vec![], vec![], vec![keyword("keyword0")],
+                vec![keyword("HACK")]);
         io_handler::serialize_language(&short_lang, &dir_str).unwrap();
 
         let (lang_map, faulty_files) = io_handler::parse_supported_languages_to_map(&dir_str).unwrap();
@@ -10,23 +10,37 @@ pub mod consumer;
 pub mod producer;
 pub mod message_printer;
 pub mod file_parser;
+pub mod code_owners;
+pub mod authors;
+pub mod hotspots;
+pub mod git;
+pub mod gitattributes;
+pub mod diff;
+pub mod duplicated_code;
+pub mod archives;
 
 mod result_printer;
 
 pub use colored::{Color,Colorize,ColoredString};
 pub use config_manager::Configuration;
 pub use utils::*;
-pub use domain::{Language, LanguageContentInfo, LanguageMetadata, FileStats, Keyword};
+pub use domain::{Language, LanguageContentInfo, LanguageMetadata, FileStats, Keyword, FileHygiene, LanguageHygiene, LineEnding, Indentation, ContentHash, LineFingerprint};
 
 pub type FaultyFilesListMut = Arc<Mutex<Vec<FaultyFileDetails>>>;
+pub type TraversalErrorsListMut = Arc<Mutex<Vec<TraversalErrorDetails>>>;
+pub type FileDetailsListMut = Arc<Mutex<Vec<FileDetails>>>;
 pub type ExtensionLangMap = Arc<HashMap<String, Arc<str>>>;
 pub type ContentInfoMapMut  = Arc<Mutex<HashMap<String,LanguageContentInfo>>>;
 pub type MetadataMapMut     = Arc<Mutex<HashMap<String,LanguageMetadata>>>;
+pub type FileHashesMapMut   = Arc<Mutex<HashMap<(Arc<str>,ContentHash),Vec<PathBuf>>>>;
+pub type FileFingerprintsListMut = Arc<Mutex<Vec<FileFingerprints>>>;
+pub type FileIdsSetMut      = Arc<Mutex<HashSet<FileId>>>;
 
 use directories::{BaseDirs,ProjectDirs};
+use gitattributes::{GitattributesStack, LinguistAttributes};
 use crossbeam_deque::{Worker,Injector};
 use chrono::{DateTime, Local};
-use std::{collections::HashMap, fs::{self, File}, io::Read, path::{Path, PathBuf}, sync::atomic::{AtomicBool, AtomicUsize, Ordering}, time::{Duration, Instant}};
+use std::{collections::{HashMap, HashSet}, fs::{self, File}, io::Read, path::{Path, PathBuf}, sync::atomic::{AtomicBool, AtomicUsize, Ordering}, time::{Duration, Instant, SystemTime}};
 use std::{sync::{Arc, LazyLock, Mutex, OnceLock}, thread::JoinHandle};
 
 
@@ -37,6 +51,10 @@ pub const CONFIG_DIR_NAME : &str = "config";
 pub const LOGS_DIR_NAME : &str = "logs";
 pub const TEST_DIR_NAME : &str = "test_dir";
 pub const DEFAULT_CONFIG_NAME : &str = "default.txt";
+// The salary is multiplied by it in the estimated cost, to include the costs of the equipment, the offices etc. like scc does
+pub const ESTIMATION_OVERHEAD : f64 = 2.4;
+// The ignore files that are read in every traversed dir, with the later ones taking precedence
+pub const IGNORE_FILE_NAMES : [&str; 3] = [".gitignore", ".ignore", ".mezuraignore"];
 
 pub static PERSISTENT_APP_PATHS : LazyLock<PersistentAppPaths> = LazyLock::new(PersistentAppPaths::get);
 pub static LOCAL_APP_PATHS : LazyLock<LocalAppPaths> = LazyLock::new(LocalAppPaths::get);
@@ -45,38 +63,289 @@ pub static CHANGELOG_BYTES : &[u8] = include_bytes!("../Changelog");
 
 pub fn run(config: Configuration, language_map: HashMap<String, Language>) -> Result<Option<Metrics>, ParseFilesError> {
     let config = Arc::new(config);
-    let faulty_files_ref : FaultyFilesListMut  = Arc::new(Mutex::new(Vec::with_capacity(10)));
-    let finish_condition_ref = Arc::new(AtomicBool::new(false));
+    match (&config.rev, &config.changed_since) {
+        (Some(rev), _) => println!("\n{} {}...","Analyzing directories at revision".underline().bold(), rev.underline().bold()),
+        (None, Some(rev)) => println!("\n{} {}...","Analyzing files changed since".underline().bold(), rev.underline().bold()),
+        (None, None) if config.staged => println!("\n{}...","Analyzing staged files".underline().bold()),
+        (None, None) if config.files_from.is_some() => println!("\n{}...","Analyzing listed files".underline().bold()),
+        (None, None) => println!("\n{}...","Analyzing directories".underline().bold())
+    }
+
+    let parsed_files = search_and_parse_files(&config, &Arc::new(language_map))?;
+    let (total_files_num, relevant_files_num, excluded_files_num, duplicate_files_num) = (parsed_files.files_present.total_files,
+            parsed_files.files_present.relevant_files, parsed_files.files_present.excluded_files, parsed_files.files_present.duplicate_files);
+    let duplicates_msg = if duplicate_files_num > 0 {format!(" {} duplicates skipped.", with_seperators(duplicate_files_num))} else {String::new()};
+    println!("{} files found. {} of interest. {} excluded.{}",with_seperators(total_files_num), with_seperators(relevant_files_num),
+            with_seperators(excluded_files_num), duplicates_msg);
+    if config.exclusion_reasons && excluded_files_num > 0 {
+        println!("Excluded by {}.", format_exclusion_reasons(&parsed_files.files_present));
+    }
+    print_traversal_errors(&parsed_files.traversal_errors, &config);
+    if config.fail_on_traversal_errors && !parsed_files.traversal_errors.is_empty() {
+        return Err(ParseFilesError::TraversalErrors(parsed_files.traversal_errors.len()));
+    }
+    println!();
+
+    println!("{}...","Parsing files".underline().bold());
+
+    let faulty_files_ref = &parsed_files.faulty_files;
+    print_faulty_files_or_ok(faulty_files_ref, &config);
+    if faulty_files_ref.lock().unwrap().len() == relevant_files_num {
+        return Err(ParseFilesError::AllAreFaultyFiles);
+    }
+
+    let mut global_languages_metadata_map_guard = parsed_files.languages_metadata.lock();
+    let languages_metadata_map = global_languages_metadata_map_guard.as_deref_mut().unwrap();
+
+    remove_faulty_files_stats(faulty_files_ref, languages_metadata_map);
+
+    let duplicate_file_groups = make_duplicate_file_groups(&parsed_files.file_hashes.lock().unwrap());
+    if config.dedupe_files {
+        remove_duplicate_files_stats(&duplicate_file_groups, languages_metadata_map);
+    }
+    let duplicated_code = (config.duplicates > 0).then(||
+            duplicated_code::find_duplicated_code(&parsed_files.files_fingerprints.lock().unwrap(), config.duplicates));
+
+    let mut content_info_map_guard = parsed_files.languages_content_info.lock();
+    let content_info_map = content_info_map_guard.as_deref_mut().unwrap();
+
+    let metrics = generate_metrics_if_parsing_took_more_than_one_sec(parsed_files.parsing_duration_millis, relevant_files_num, content_info_map);
+
+    let final_stats = FinalStats::calculate(content_info_map, languages_metadata_map);
+    let log_file_path = get_specified_config_file_path(&config);
+    let existing_log_contents = {
+        if let Some(path) = &log_file_path {
+            extract_file_contents(path)
+        } else {
+            None
+        }
+    };
+    let datetime_now = chrono::Local::now();
+
+    remove_languages_with_0_files(content_info_map, languages_metadata_map);
+    // Logged before printing, because printing the overview merges the least significant languages together
+    let log_result = match log_file_path {
+        Some(path) if config.log.should_log => io_handler::log_stats(&path, &existing_log_contents, content_info_map,
+                &final_stats, &datetime_now, &config),
+        _ => Ok(())
+    };
+
+    let mut file_details = parsed_files.file_details.lock().unwrap();
+    let ownership = config.owners.then(|| code_owners::find_ownership(&file_details, &config.dirs));
+    let authorship = config.authors.then(|| authors::find_authorship(&file_details));
+    let hotspots = config.hotspots.map(|metric| hotspots::find_churn(&config.dirs, config.rev.as_deref(), config.churn_window)
+            .map(|churn| hotspots::find_hotspots(&file_details, &churn, metric)));
+    let extra_sections = result_printer::ExtraSections {
+        files_details: &mut file_details,
+        duplicate_file_groups: &duplicate_file_groups,
+        duplicated_code: duplicated_code.as_ref(),
+        ownership: ownership.as_ref(),
+        authorship: authorship.as_ref(),
+        hotspots: hotspots.as_ref()
+    };
+    result_printer::format_and_print_results(content_info_map, languages_metadata_map, extra_sections, &final_stats, &existing_log_contents,
+        &datetime_now, &config);
+
+    if log_result.is_err() {
+        println!("\n{}","Error while trying to save the log.".yellow());
+    }
+
+    Ok(metrics)
+}
+
+// Computes the stats of every commit of the backfill, as if it was checked out, and adds them to the log
+// of the specified configuration as entries dated by the commits.
+pub fn backfill_log(config: Configuration, language_map: HashMap<String, Language>) -> Result<(), ParseFilesError> {
+    let (Some(backfill), Some(log_file_path)) = (&config.backfill, get_specified_config_file_path(&config)) else {
+        return Ok(())
+    };
+    let Some(dir) = config.dirs.first() else { return Ok(()) };
+    let commits = git::find_commits(Path::new(dir), backfill).map_err(ParseFilesError::GitFailure)?;
+    println!("\n{} {} commits...","Backfilling the log with".underline().bold(), with_seperators(commits.len()));
+
+    let language_map_ref = Arc::new(language_map);
+    let mut entries = Vec::with_capacity(commits.len());
+    for (i, commit) in commits.iter().enumerate() {
+        let progress = format!("  [{}/{}] {} {}", i + 1, commits.len(), &commit.id[..commit.id.len().min(10)], commit.name);
+
+        let mut commit_config = config.clone();
+        commit_config.set_rev(Some(commit.id.clone())).set_log_option(config_manager::LogOption{should_log: true, name: Some(commit.name.clone())});
+        let commit_config = Arc::new(commit_config);
+
+        let parsed_files = match search_and_parse_files(&commit_config, &language_map_ref) {
+            Ok(x) => x,
+            Err(ParseFilesError::NoRelevantFiles(_)) => {
+                println!("{progress} {}", "-> skipped, no files of interest".yellow());
+                continue;
+            },
+            Err(x) => return Err(x)
+        };
+        if parsed_files.faulty_files.lock().unwrap().len() == parsed_files.files_present.relevant_files {
+            println!("{progress} {}", "-> skipped, every file is faulty".yellow());
+            continue;
+        }
+
+        let mut languages_metadata_map = parsed_files.languages_metadata.lock().unwrap();
+        remove_faulty_files_stats(&parsed_files.faulty_files, &mut languages_metadata_map);
+        if config.dedupe_files {
+            remove_duplicate_files_stats(&make_duplicate_file_groups(&parsed_files.file_hashes.lock().unwrap()), &mut languages_metadata_map);
+        }
+        let mut content_info_map = parsed_files.languages_content_info.lock().unwrap();
+        let final_stats = FinalStats::calculate(&content_info_map, &languages_metadata_map);
+        remove_languages_with_0_files(&mut content_info_map, &mut languages_metadata_map);
+
+        println!("{progress} -> {} files, {} lines", with_seperators(final_stats.files), with_seperators(final_stats.lines));
+        entries.push((commit.datetime, io_handler::format_log_entry(&commit_config, &commit.datetime, &content_info_map, &final_stats)));
+    }
+
+    match io_handler::add_entries_to_log(&log_file_path, &extract_file_contents(&log_file_path), entries) {
+        Ok(added) => println!("\n{} entries were added to the log of '{}'.", added,
+                config.config_name_to_save.as_ref().or(config.config_name_to_load.as_ref()).cloned().unwrap_or_default()),
+        Err(_) => println!("\n{}","Error while trying to save the log.".yellow())
+    }
+
+    Ok(())
+}
+
+// Compares the stats of the two targets of the diff per language, instead of counting the target dirs
+pub fn run_diff(config: Configuration, language_map: HashMap<String, Language>) -> Result<(), ParseFilesError> {
+    let Some((old, new)) = &config.diff else { return Ok(()) };
+    println!("\n{}...","Comparing".underline().bold());
+
     let language_map_ref = Arc::new(language_map);
-    let extension_lang_map: ExtensionLangMap = Arc::new(make_extension_language_map(&language_map_ref));
-    let languages_content_info_ref : ContentInfoMapMut = Arc::new(Mutex::new(make_language_stats(language_map_ref.clone())));
-    let global_languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(&language_map_ref)));
+    let old_stats = count_diff_target(&config, old, &language_map_ref)?;
+    let new_stats = count_diff_target(&config, new, &language_map_ref)?;
+    if old_stats.files.is_empty() && new_stats.files.is_empty() {
+        return Err(ParseFilesError::NoRelevantFiles(get_activated_languages_as_str(&config)));
+    }
+
+    result_printer::print_diff(&diff::find_language_diffs(old_stats, new_stats), old, new);
+
+    Ok(())
+}
+
+// A target without any relevant files is valid, e.g. a revision before the project had any code
+fn count_diff_target(config: &Configuration, target: &config_manager::DiffTarget, language_map_ref: &Arc<HashMap<String, Language>>)
+        -> Result<diff::DiffTargetStats, ParseFilesError>
+{
+    let mut target_config = config.clone();
+    let label = match target {
+        config_manager::DiffTarget::Dir(dir) => {
+            target_config.dirs = vec![dir.clone()];
+            target_config.set_rev(None);
+            dir
+        },
+        config_manager::DiffTarget::Rev(rev) => {
+            target_config.set_rev(Some(rev.clone()));
+            rev
+        }
+    };
+
+    let parsed_files = match search_and_parse_files(&Arc::new(target_config.clone()), language_map_ref) {
+        Ok(x) => x,
+        Err(ParseFilesError::NoRelevantFiles(_)) => {
+            println!("{label}: no files of interest.");
+            return Ok(diff::DiffTargetStats {
+                content_info_map: make_language_stats(language_map_ref.clone()),
+                languages_metadata_map: make_language_metadata(language_map_ref),
+                files: HashMap::new()
+            });
+        },
+        Err(x) => return Err(x)
+    };
+    let faulty_files_num = parsed_files.faulty_files.lock().unwrap().len();
+    println!("{label}: {} files of interest.{}", with_seperators(parsed_files.files_present.relevant_files),
+            if faulty_files_num > 0 {format!(" {} faulty, skipped.", with_seperators(faulty_files_num))} else {String::new()});
+
+    let mut languages_metadata_map = std::mem::take(&mut *parsed_files.languages_metadata.lock().unwrap());
+    remove_faulty_files_stats(&parsed_files.faulty_files, &mut languages_metadata_map);
+    if config.dedupe_files {
+        remove_duplicate_files_stats(&make_duplicate_file_groups(&parsed_files.file_hashes.lock().unwrap()), &mut languages_metadata_map);
+    }
+    let files = parsed_files.file_details.lock().unwrap().iter().map(|x| {
+        let relative_path = get_path_inside_target_dir(&x.path, &target_config.dirs);
+        (relative_path.to_path_buf(), (x.language_name.clone(), x.content_hash))
+    }).collect();
+
+    Ok(diff::DiffTargetStats {
+        content_info_map: std::mem::take(&mut *parsed_files.languages_content_info.lock().unwrap()),
+        languages_metadata_map,
+        files
+    })
+}
+
+// Everything that the producers and the consumers collected about the files of the target dirs
+struct ParsedFiles {
+    faulty_files: FaultyFilesListMut,
+    file_details: FileDetailsListMut,
+    languages_content_info: ContentInfoMapMut,
+    languages_metadata: MetadataMapMut,
+    file_hashes: FileHashesMapMut,
+    files_fingerprints: FileFingerprintsListMut,
+    files_present: FilesPresent,
+    traversal_errors: Vec<TraversalErrorDetails>,
+    parsing_duration_millis: u128
+}
+
+fn search_and_parse_files(config: &Arc<Configuration>, language_map_ref: &Arc<HashMap<String, Language>>) -> Result<ParsedFiles, ParseFilesError> {
+    let finish_condition_ref = Arc::new(AtomicBool::new(false));
+    let extension_lang_map: ExtensionLangMap = Arc::new(make_extension_language_map(language_map_ref));
+    let global_languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(language_map_ref)));
 
     let mut files_present = FilesPresent::default();
     let idle_producers = Arc::new(AtomicUsize::new(0));
     let files_injector = Arc::new(Injector::<ParsableFile>::new());
     let dirs_injector = Arc::new(Injector::<TraversedDir>::new());
-    let exclude_matcher = Arc::new(build_exclude_matcher(&config.exclude_dirs)
-            .expect("exclude patterns are validated during argument parsing"));
-    calculate_single_file_stats_or_add_to_injector(&config, &dirs_injector, &files_injector, &mut files_present,
-            &extension_lang_map, &global_languages_metadata_map);
+    let traversal_context = Arc::new(TraversalContext::new(config.clone(), extension_lang_map.clone(), global_languages_metadata_map.clone(),
+            files_injector.clone()));
+    let parsing_context = Arc::new(ParsingContext::new(config.clone(), language_map_ref.clone()));
+    // The files of a git revision are read by git, and the changed files are listed by git or by the user,
+    // so none of them are searched for by the producers
+    let are_files_searched = config.rev.is_none() && config.changed_since.is_none() && !config.staged && config.files_from.is_none();
+    let revision_files = match &config.rev {
+        Some(rev) => match git::find_revision_files(rev, &traversal_context, &mut files_present) {
+            Ok(x) => Some(x),
+            Err(x) => return Err(ParseFilesError::GitFailure(x))
+        },
+        None if let Some(source) = &config.files_from => {
+            match io_handler::read_files_list(source) {
+                Ok(paths) => producer::add_listed_files(paths, &traversal_context, &mut files_present),
+                Err(x) => return Err(ParseFilesError::UnreadableFilesList(x.to_string()))
+            }
+            None
+        },
+        None if !are_files_searched => {
+            if let Err(x) = git::find_changed_files(&traversal_context, &mut files_present) {
+                return Err(ParseFilesError::GitFailure(x));
+            }
+            None
+        },
+        None => {
+            calculate_single_file_stats_or_add_to_injector(config, &dirs_injector, &files_injector, &mut files_present,
+                    &extension_lang_map, &global_languages_metadata_map);
+            if let Err(x) = archives::find_archive_files(&traversal_context, &mut files_present) {
+                return Err(ParseFilesError::UnreadableArchive(x));
+            }
+            None
+        }
+    };
 
     let files_stats = Arc::new(Mutex::new(files_present));
 
     let mut producer_handles = Vec::with_capacity(config.threads.producers);
     let mut consumer_handles = Vec::with_capacity(config.threads.consumers);
 
-    println!("\n{}...","Analyzing directories".underline().bold());
-
     let parsing_started_instant = Instant::now();
-    for i in 0..config.threads.producers {
-        producer_handles.push(producer::start_producer_thread(i, files_injector.clone(), dirs_injector.clone(), Worker::new_fifo(),
-            global_languages_metadata_map.clone(), idle_producers.clone(), extension_lang_map.clone(), exclude_matcher.clone(),
-            config.clone(), files_stats.clone()));
+    if let Some(revision_files) = revision_files {
+        producer_handles.push(git::start_revision_reader_thread(revision_files, files_injector.clone()));
+    } else if are_files_searched {
+        for i in 0..config.threads.producers {
+            producer_handles.push(producer::start_producer_thread(i, dirs_injector.clone(), Worker::new_fifo(), idle_producers.clone(),
+                traversal_context.clone(), files_stats.clone()));
+        }
     }
     for i in 0..config.threads.consumers {
-        consumer_handles.push(consumer::start_parser_thread(i, files_injector.clone(), faulty_files_ref.clone(), finish_condition_ref.clone(),
-        languages_content_info_ref.clone(), language_map_ref.clone(), config.clone()));
+        consumer_handles.push(consumer::start_parser_thread(i, files_injector.clone(), finish_condition_ref.clone(), parsing_context.clone()));
     }
 
     for handle in producer_handles {
@@ -87,8 +356,8 @@ pub fn run(config: Configuration, language_map: HashMap<String, Language>) -> Re
     //If there are a lot of files remaining after producers finish, it makes sense to start another consumer.
     let len = files_injector.len();
     if len > 1200 {
-        consumer_handles.push(consumer::start_parser_thread(config.threads.consumers, files_injector, faulty_files_ref.clone(), finish_condition_ref.clone(),
-        languages_content_info_ref.clone(), language_map_ref.clone(), config.clone()));
+        consumer_handles.push(consumer::start_parser_thread(config.threads.consumers, files_injector, finish_condition_ref.clone(),
+                parsing_context.clone()));
     }
 
     finish_condition_ref.store(true,Ordering::Relaxed);
@@ -102,52 +371,31 @@ pub fn run(config: Configuration, language_map: HashMap<String, Language>) -> Re
             producers_done_millis, parsing_duration_millis - producers_done_millis, len);
     }
 
-    let file_stats_guard = files_stats.lock().unwrap();
-    let (total_files_num, relevant_files_num, excluded_files_num) =
-            (file_stats_guard.total_files, file_stats_guard.relevant_files, file_stats_guard.excluded_files);
-    if relevant_files_num == 0 {
-        return Err(ParseFilesError::NoRelevantFiles(get_activated_languages_as_str(&config)));
-    }
-    println!("{} files found. {} of interest. {} excluded.\n",with_seperators(total_files_num), with_seperators(relevant_files_num), with_seperators(excluded_files_num));
-
-    println!("{}...","Parsing files".underline().bold());
-
-    print_faulty_files_or_ok(&faulty_files_ref, &config);
-    if faulty_files_ref.lock().unwrap().len() == relevant_files_num {
-        return Err(ParseFilesError::AllAreFaultyFiles);
+    let files_present = files_stats.lock().unwrap().clone();
+    let mut traversal_errors = std::mem::take(&mut *traversal_context.traversal_errors.lock().unwrap());
+    traversal_errors.sort_by(|a, b| a.path.cmp(&b.path));
+    if files_present.relevant_files == 0 {
+        return Err(ParseFilesError::NoRelevantFiles(get_activated_languages_as_str(config)));
     }
 
-    let mut global_languages_metadata_map_guard = global_languages_metadata_map.lock();
-    let languages_metadata_map = global_languages_metadata_map_guard.as_deref_mut().unwrap();
-
-    remove_faulty_files_stats(&faulty_files_ref, languages_metadata_map, &extension_lang_map);
-
-    let mut content_info_map_guard = languages_content_info_ref.lock();
-    let content_info_map = content_info_map_guard.as_deref_mut().unwrap();
-
-    let metrics = generate_metrics_if_parsing_took_more_than_one_sec(parsing_duration_millis, relevant_files_num, content_info_map);
-
-    let final_stats = FinalStats::calculate(content_info_map, languages_metadata_map);
-    let log_file_path = get_specified_config_file_path(&config);
-    let existing_log_contents = {
-        if let Some(path) = &log_file_path {
-            extract_file_contents(path)
-        } else {
-            None
-        }
-    };
-    let datetime_now = chrono::Local::now();
-
-    remove_languages_with_0_files(content_info_map, languages_metadata_map);
-    result_printer::format_and_print_results(content_info_map, languages_metadata_map, &final_stats,
-        &existing_log_contents, &datetime_now, &config);
-
-    if config.log.should_log && let Some(path) = log_file_path
-        && io_handler::log_stats(&path, &existing_log_contents, &final_stats, &datetime_now, &config).is_err() {
-        println!("\n{}","Error while trying to save the log.".yellow());
-    }
+    Ok(ParsedFiles {
+        faulty_files: parsing_context.faulty_files.clone(),
+        file_details: parsing_context.files_details.clone(),
+        languages_content_info: parsing_context.languages_content_info.clone(),
+        languages_metadata: global_languages_metadata_map,
+        file_hashes: parsing_context.file_hashes.clone(),
+        files_fingerprints: parsing_context.files_fingerprints.clone(),
+        files_present,
+        traversal_errors,
+        parsing_duration_millis
+    })
+}
 
-    Ok(metrics)
+// Only the reasons that excluded any files, like "ignore files: 3, file size: 1"
+fn format_exclusion_reasons(files_present: &FilesPresent) -> String {
+    ExclusionReason::ALL.iter().zip(files_present.excluded_by).filter(|x| x.1 > 0)
+            .map(|(reason, files)| format!("{}: {}", reason.description(), with_seperators(files)))
+            .collect::<Vec<_>>().join(", ")
 }
 
 //pub for integration tests
@@ -168,7 +416,8 @@ pub fn calculate_single_file_stats_or_add_to_injector(config: &Configuration, di
             }
         } else if dir_path.is_dir() {
             let gitignore_stack = if config.no_gitignore { None } else { GitignoreStack::for_root_dir(dir_path) };
-            dirs_injector.push(TraversedDir::new(dir_path.to_path_buf(), gitignore_stack));
+            let gitattributes_stack = if config.no_gitattributes { None } else { GitattributesStack::for_root_dir(dir_path) };
+            dirs_injector.push(TraversedDir::new(dir_path.to_path_buf(), gitignore_stack, gitattributes_stack, 0));
         }
     })
 }
@@ -207,6 +456,13 @@ pub fn find_language_of_extension(extension_lang_map: &HashMap<String, Arc<str>>
     extension_lang_map.get(extension).cloned()
 }
 
+// The language that the .gitattributes assign to a file takes precedence over the one of its extension, if it is supported
+pub fn find_language_of_file(extension_lang_map: &HashMap<String, Arc<str>>, path: &Path, attributes: &LinguistAttributes) -> Option<Arc<str>> {
+    attributes.language.as_deref().map(gitattributes::to_language_name)
+        .and_then(|name| extension_lang_map.values().find(|x| x.eq_ignore_ascii_case(name)).cloned())
+        .or_else(|| path.extension().and_then(|x| x.to_str()).and_then(|x| find_language_of_extension(extension_lang_map, x)))
+}
+
 
 fn generate_metrics_if_parsing_took_more_than_one_sec(parsing_duration_millis: u128, relevant_files: usize,
         content_info_map: &HashMap<String, LanguageContentInfo>) -> Option<Metrics>
@@ -247,20 +503,56 @@ fn print_faulty_files_or_ok(faulty_files_ref: &FaultyFilesListMut, config: &Conf
     }
 }
 
-fn remove_faulty_files_stats(faulty_files_ref: &FaultyFilesListMut, languages_metadata_map: &mut HashMap<String,LanguageMetadata>,
-        extension_lang_map: &HashMap<String, Arc<str>>) {
+fn print_traversal_errors(traversal_errors: &[TraversalErrorDetails], config: &Configuration) {
+    if traversal_errors.is_empty() {
+        return;
+    }
+    let color = if config.fail_on_traversal_errors {Color::Red} else {Color::Yellow};
+    println!("{} {}", with_seperators(traversal_errors.len()).color(color),
+            "paths couldn't be read while searching. Their files were not counted.".color(color));
+    if config.should_show_faulty_files {
+        for x in traversal_errors {
+            println!("-- Error: {} ({:?}) \n   for path: {}", x.error_msg, x.error_kind, x.path.display());
+        }
+    } else {
+        println!("Run with command '--{}' to get detailed info.",config_manager::SHOW_FAULTY_FILES)
+    }
+}
+
+fn remove_faulty_files_stats(faulty_files_ref: &FaultyFilesListMut, languages_metadata_map: &mut HashMap<String,LanguageMetadata>) {
     let faulty_files = &*faulty_files_ref.as_ref().lock().unwrap();
     for file in faulty_files {
-        let extension = utils::get_file_extension(Path::new(&file.path));
-        if let Some(x) = extension {
-            let lang_name = find_language_of_extension(extension_lang_map, x).unwrap();
-            let language_metadata = languages_metadata_map.get_mut(lang_name.as_ref()).unwrap();
+        if let Some(language_metadata) = languages_metadata_map.get_mut(file.language_name.as_ref()) {
             language_metadata.files -= 1;
             language_metadata.bytes -= file.size as usize;
         }
     }
 }
 
+//pub for integration tests
+pub fn make_duplicate_file_groups(file_hashes: &HashMap<(Arc<str>,ContentHash),Vec<PathBuf>>) -> Vec<DuplicateFileGroup> {
+    let mut groups = file_hashes.iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|((language_name, content_hash), paths)| {
+                let mut paths = paths.clone();
+                paths.sort();
+                DuplicateFileGroup { language_name: language_name.clone(), bytes: content_hash.bytes, paths }
+            })
+            .collect::<Vec<_>>();
+    groups.sort_by(|a,b| b.wasted_bytes().cmp(&a.wasted_bytes()).then_with(|| a.paths.cmp(&b.paths)));
+    groups
+}
+
+// The consumers skip the stats of the extra copies, but they were already added to the metadata by the producers
+//pub for integration tests
+pub fn remove_duplicate_files_stats(duplicate_file_groups: &[DuplicateFileGroup], languages_metadata_map: &mut HashMap<String,LanguageMetadata>) {
+    for group in duplicate_file_groups {
+        let language_metadata = languages_metadata_map.get_mut(group.language_name.as_ref()).unwrap();
+        language_metadata.files -= group.paths.len() - 1;
+        language_metadata.bytes -= group.wasted_bytes();
+    }
+}
+
 fn get_activated_languages_as_str(config: &Configuration) -> String {
     let mut msg = if config.languages_of_interest.is_empty() {
         String::new()
@@ -346,36 +638,156 @@ pub struct FinalStats {
     average_size_measurement: String
 }
 
+// Basic COCOMO estimation, based on the code lines
+#[derive(Debug,PartialEq)]
+pub struct Estimation {
+    // person-months
+    pub effort: f64,
+    // months
+    pub schedule: f64,
+    pub people: f64,
+    pub cost: f64
+}
+
 #[derive(Debug)]
 pub struct FaultyFileDetails {
     path: String,
     error_msg: String,
-    size: u64
+    size: u64,
+    // The language that the producer assigned to the file, that may come from its linguist attributes
+    language_name: Arc<str>
+}
+
+// A dir that couldn't be read, or an entry of it that couldn't be inspected, while traversing, or a listed file that
+// couldn't be found
+#[derive(Debug)]
+pub struct TraversalErrorDetails {
+    pub path: PathBuf,
+    pub error_kind: std::io::ErrorKind,
+    pub error_msg: String
+}
+
+// Per-file stats, only collected when a section or a command that lists or matches files is requested
+#[derive(Debug)]
+pub struct FileDetails {
+    pub path: PathBuf,
+    pub language_name: Arc<str>,
+    pub lines: usize,
+    pub code_lines: usize,
+    pub comment_keywords: usize,
+    pub complexity: usize,
+    pub max_depth: usize,
+    pub average_depth: f64,
+    pub hygiene: Option<FileHygiene>,
+    pub content_hash: Option<ContentHash>,
+    // Only blamed when the authors are requested, None if the file couldn't be blamed
+    pub authors: Option<HashMap<String,authors::AuthorStats>>
+}
+
+// Files of the same language with identical contents, only collected when duplicate files are detected
+#[derive(Debug,PartialEq)]
+pub struct DuplicateFileGroup {
+    pub language_name: Arc<str>,
+    pub bytes: usize,
+    pub paths: Vec<PathBuf>
+}
+
+// The fingerprints of the code lines of a file, only collected when duplicated code is detected
+#[derive(Debug)]
+pub struct FileFingerprints {
+    pub path: PathBuf,
+    pub language_name: Arc<str>,
+    pub lines: Vec<LineFingerprint>
 }
 
 #[derive(Debug)]
 pub enum ParseFilesError {
     NoRelevantFiles(String),
-    AllAreFaultyFiles
+    AllAreFaultyFiles,
+    GitFailure(String),
+    UnreadableFilesList(String),
+    UnreadableArchive(String),
+    // The number of the paths that couldn't be read while searching, when they are fatal
+    TraversalErrors(usize)
 }
 
 #[derive(Debug,Default,Clone)]
 pub struct FilesPresent {
     pub total_files: usize,
     pub relevant_files: usize,
-    pub excluded_files: usize
+    pub excluded_files: usize,
+    // The excluded files by the reason of their exclusion, in the order of ExclusionReason::ALL
+    pub excluded_by: [usize; ExclusionReason::ALL.len()],
+    // The files that were reached a second time, through links
+    pub duplicate_files: usize
+}
+
+// Why a file of a language of interest isn't counted, in the order that they are checked
+#[derive(Debug,PartialEq,Clone,Copy)]
+pub enum ExclusionReason {
+    ExcludePatterns,
+    IncludePatterns,
+    IgnoreFiles,
+    LinguistAttributes,
+    FileSize,
+    ModificationTime
+}
+
+// The filters that need the metadata of a file
+#[derive(Debug,Default,Clone)]
+pub struct MetadataFilters {
+    pub min_size: Option<usize>,
+    pub max_size: Option<usize>,
+    pub modified_cutoff: Option<SystemTime>
+}
+
+// What every way of finding the files shares, to decide which of them are counted and where they go
+pub struct TraversalContext {
+    pub config: Arc<Configuration>,
+    pub extension_lang_map: ExtensionLangMap,
+    pub exclude_matcher: globset::GlobSet,
+    pub include_matcher: IncludeMatcher,
+    pub metadata_filters: MetadataFilters,
+    pub languages_metadata_map: MetadataMapMut,
+    pub files_injector: Arc<Injector<ParsableFile>>,
+    pub seen_file_ids: FileIdsSetMut,
+    pub traversal_errors: TraversalErrorsListMut
+}
+
+// What the consumers share, to parse the files and collect their stats
+pub struct ParsingContext {
+    pub config: Arc<Configuration>,
+    pub language_map: Arc<HashMap<String,Language>>,
+    pub faulty_files: FaultyFilesListMut,
+    pub languages_content_info: ContentInfoMapMut,
+    pub files_details: FileDetailsListMut,
+    pub file_hashes: FileHashesMapMut,
+    pub files_fingerprints: FileFingerprintsListMut
 }
 
 #[derive(Debug,Clone)]
 pub struct ParsableFile {
     pub path: PathBuf,
-    pub language_name: Arc<str>
+    pub language_name: Arc<str>,
+    // Set when the file is not read from the filesystem, like the blobs of a git revision
+    pub contents: Option<Vec<u8>>
 }
 
 #[derive(Debug,Clone)]
 pub struct TraversedDir {
     pub path: PathBuf,
-    pub gitignore_stack: Option<Arc<GitignoreStack>>
+    pub gitignore_stack: Option<Arc<GitignoreStack>>,
+    pub gitattributes_stack: Option<Arc<GitattributesStack>>,
+    // 0 for the target dirs
+    pub depth: usize
+}
+
+// What identifies a file or a dir regardless of the links that lead to it: its device and inode where they
+// are available, or else its canonical path, which can't tell the hard links of a file apart
+#[derive(Debug,PartialEq,Eq,Hash,Clone)]
+pub enum FileId {
+    Inode(u64,u64),
+    Path(PathBuf)
 }
 
 #[derive(Debug)]
@@ -446,7 +858,12 @@ impl Formatted for ParseFilesError {
     fn formatted(&self) -> ColoredString {
         match self {
             Self::NoRelevantFiles(x) => format!("{} {}","No relevant files found in the given directory.", x).yellow(),
-            Self::AllAreFaultyFiles => "None of the files were able to be parsed".yellow()
+            Self::AllAreFaultyFiles => "None of the files were able to be parsed".yellow(),
+            Self::GitFailure(x) => format!("Error while reading the files of the git revision: {x}").red(),
+            Self::UnreadableFilesList(x) => format!("Error while reading the list of files: {x}").red(),
+            Self::UnreadableArchive(x) => format!("Error while reading the archive {x}").red(),
+            Self::TraversalErrors(x) => format!("Stopped, because {} paths couldn't be read while searching.",
+                    with_seperators(*x)).red()
         }
     }
 }
@@ -526,22 +943,216 @@ impl FinalStats {
     }
 }
 
+impl Estimation {
+    pub fn calculate(code_lines: usize, coefficients: &config_manager::CocomoCoefficients, salary: usize) -> Self {
+        let effort = coefficients.a * (code_lines as f64 / 1000.0).powf(coefficients.b);
+        let schedule = coefficients.c * effort.powf(coefficients.d);
+        let people = if schedule > 0.0 {effort / schedule} else {0.0};
+        let cost = effort * (salary as f64 / 12.0) * ESTIMATION_OVERHEAD;
+        Estimation {
+            effort,
+            schedule,
+            people,
+            cost
+        }
+    }
+}
+
 impl FaultyFileDetails {
-    pub fn new(path: String, error_msg: String, size: u64) -> Self {
+    pub fn new(path: String, error_msg: String, size: u64, language_name: Arc<str>) -> Self {
         FaultyFileDetails {
             path,
             error_msg,
-            size
+            size,
+            language_name
+        }
+    }
+}
+
+impl TraversalErrorDetails {
+    pub fn new(path: PathBuf, error: &std::io::Error) -> Self {
+        TraversalErrorDetails {
+            path,
+            error_kind: error.kind(),
+            error_msg: error.to_string()
         }
     }
 }
 
+impl FileDetails {
+    pub fn new(path: PathBuf, language_name: Arc<str>, stats: &FileStats) -> Self {
+        FileDetails {
+            path,
+            language_name,
+            lines: stats.lines,
+            code_lines: stats.code_lines,
+            comment_keywords: stats.total_comment_keywords(),
+            complexity: stats.complexity,
+            max_depth: domain::max_depth_of(&stats.depth_histogram).unwrap_or(0),
+            average_depth: domain::average_depth_of(&stats.depth_histogram).unwrap_or(0.0),
+            hygiene: stats.hygiene.clone(),
+            content_hash: stats.content_hash,
+            authors: None
+        }
+    }
+}
+
+impl FileFingerprints {
+    pub fn new(path: PathBuf, language_name: Arc<str>, lines: Vec<LineFingerprint>) -> Self {
+        FileFingerprints {
+            path,
+            language_name,
+            lines
+        }
+    }
+}
+
+impl DuplicateFileGroup {
+    // The bytes of all the copies except the first one
+    pub fn wasted_bytes(&self) -> usize {
+        self.bytes * (self.paths.len() - 1)
+    }
+}
+
 impl FilesPresent {
-    pub fn new(total_files: usize, relevant_files: usize, excluded_files: usize) -> Self {
+    pub fn new(total_files: usize, relevant_files: usize, excluded_files: usize, duplicate_files: usize) -> Self {
         FilesPresent {
             total_files,
             relevant_files,
-            excluded_files
+            excluded_files,
+            excluded_by: [0; ExclusionReason::ALL.len()],
+            duplicate_files
+        }
+    }
+
+    pub fn exclude(&mut self, reason: ExclusionReason) {
+        self.excluded_files += 1;
+        self.excluded_by[reason as usize] += 1;
+    }
+
+    pub fn add(&mut self, other: &FilesPresent) {
+        self.total_files += other.total_files;
+        self.relevant_files += other.relevant_files;
+        self.excluded_files += other.excluded_files;
+        for (x, y) in self.excluded_by.iter_mut().zip(other.excluded_by) {
+            *x += y;
+        }
+        self.duplicate_files += other.duplicate_files;
+    }
+}
+
+impl ExclusionReason {
+    pub const ALL : [ExclusionReason; 6] = [ExclusionReason::ExcludePatterns, ExclusionReason::IncludePatterns, ExclusionReason::IgnoreFiles,
+            ExclusionReason::LinguistAttributes, ExclusionReason::FileSize, ExclusionReason::ModificationTime];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ExclusionReason::ExcludePatterns => "exclude patterns",
+            ExclusionReason::IncludePatterns => "include patterns",
+            ExclusionReason::IgnoreFiles => "ignore files",
+            ExclusionReason::LinguistAttributes => "linguist attributes",
+            ExclusionReason::FileSize => "file size",
+            ExclusionReason::ModificationTime => "modification time"
+        }
+    }
+}
+
+impl TraversalContext {
+    pub fn new(config: Arc<Configuration>, extension_lang_map: ExtensionLangMap, languages_metadata_map: MetadataMapMut,
+            files_injector: Arc<Injector<ParsableFile>>) -> Self
+    {
+        TraversalContext {
+            exclude_matcher: build_exclude_matcher(&config.exclude_dirs).expect("exclude patterns are validated during argument parsing"),
+            include_matcher: build_include_matcher(&config.include_patterns, &config.dirs)
+                    .expect("include patterns are validated during argument parsing"),
+            metadata_filters: MetadataFilters::of(&config),
+            seen_file_ids: Arc::new(Mutex::new(HashSet::new())),
+            traversal_errors: Arc::new(Mutex::new(Vec::new())),
+            config,
+            extension_lang_map,
+            languages_metadata_map,
+            files_injector
+        }
+    }
+
+    // Why a file of a language of interest isn't counted, for every way of finding the files. The exclude patterns and
+    // the ignore files apply to the dirs of the file below the given dir too, since only the traversals have skipped them.
+    pub fn exclusion_reason(&self, path: &Path, checked_below: &Path, gitignore_stack: Option<&GitignoreStack>, attributes: &LinguistAttributes,
+            bytes: usize, modified: Option<SystemTime>) -> Option<ExclusionReason>
+    {
+        let mut dirs_and_file = path.ancestors().take_while(|x| *x != checked_below);
+        if !self.exclude_matcher.is_empty() && dirs_and_file.clone().any(|x| self.exclude_matcher.is_match(x)) {
+            Some(ExclusionReason::ExcludePatterns)
+        } else if !self.include_matcher.includes(path) {
+            Some(ExclusionReason::IncludePatterns)
+        } else if let Some(stack) = gitignore_stack && dirs_and_file.any(|x| stack.is_ignored(x, x != path)) {
+            Some(ExclusionReason::IgnoreFiles)
+        } else if attributes.is_excluded() {
+            Some(ExclusionReason::LinguistAttributes)
+        } else {
+            self.metadata_filters.exclusion_reason(bytes, modified)
+        }
+    }
+}
+
+impl ParsingContext {
+    pub fn new(config: Arc<Configuration>, language_map: Arc<HashMap<String,Language>>) -> Self {
+        ParsingContext {
+            faulty_files: Arc::new(Mutex::new(Vec::with_capacity(10))),
+            languages_content_info: Arc::new(Mutex::new(make_language_stats(language_map.clone()))),
+            files_details: Arc::new(Mutex::new(Vec::new())),
+            file_hashes: Arc::new(Mutex::new(HashMap::new())),
+            files_fingerprints: Arc::new(Mutex::new(Vec::new())),
+            config,
+            language_map
+        }
+    }
+}
+
+impl MetadataFilters {
+    pub fn of(config: &Configuration) -> Self {
+        MetadataFilters {
+            min_size: config.min_file_size,
+            max_size: config.max_file_size,
+            modified_cutoff: config.modified_since.as_ref().map(|x| x.cutoff())
+        }
+    }
+
+    // The files whose modification time is unknown aren't excluded by it
+    pub fn exclusion_reason(&self, bytes: usize, modified: Option<SystemTime>) -> Option<ExclusionReason> {
+        if self.min_size.is_some_and(|x| bytes < x) || self.max_size.is_some_and(|x| bytes > x) {
+            return Some(ExclusionReason::FileSize);
+        }
+        if let (Some(cutoff), Some(modified)) = (self.modified_cutoff, modified) && modified < cutoff {
+            return Some(ExclusionReason::ModificationTime);
+        }
+        None
+    }
+}
+
+impl FileId {
+    #[cfg(unix)]
+    pub fn of(_path: &Path, metadata: &fs::Metadata) -> Option<FileId> {
+        use std::os::unix::fs::MetadataExt;
+        Some(FileId::Inode(metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(not(unix))]
+    pub fn of(path: &Path, _metadata: &fs::Metadata) -> Option<FileId> {
+        fs::canonicalize(path).ok().map(FileId::Path)
+    }
+
+    // Only known where the files have inodes
+    pub fn has_hard_links(metadata: &fs::Metadata) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            metadata.nlink() > 1
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = metadata;
+            false
         }
     }
 }
@@ -550,50 +1161,89 @@ impl ParsableFile {
     pub fn new(path: PathBuf, language_name: Arc<str>) -> Self {
         ParsableFile {
             path,
-            language_name
+            language_name,
+            contents: None
+        }
+    }
+
+    pub fn with_contents(path: PathBuf, language_name: Arc<str>, contents: Vec<u8>) -> Self {
+        ParsableFile {
+            path,
+            language_name,
+            contents: Some(contents)
         }
     }
 }
 
 impl TraversedDir {
-    pub fn new(path: PathBuf, gitignore_stack: Option<Arc<GitignoreStack>>) -> Self {
+    pub fn new(path: PathBuf, gitignore_stack: Option<Arc<GitignoreStack>>, gitattributes_stack: Option<Arc<GitattributesStack>>, depth: usize) -> Self {
         TraversedDir {
             path,
-            gitignore_stack
+            gitignore_stack,
+            gitattributes_stack,
+            depth
         }
     }
 }
 
 impl GitignoreStack {
     pub fn extended(dir: &Path, parent: Option<Arc<GitignoreStack>>) -> Option<Arc<GitignoreStack>> {
-        let gitignore_path = dir.join(".gitignore");
-        if !gitignore_path.is_file() {
-            return parent;
-        }
+        Self::extended_with_files(dir, &IGNORE_FILE_NAMES.map(|x| dir.join(x)), parent)
+    }
+
+    // The excludes of the repository itself, which apply under all of its ignore files: the global excludes file
+    // of git (core.excludesFile) and the .git/info/exclude of the repository, which takes precedence over it
+    pub fn of_repository(root: &Path) -> Option<Arc<GitignoreStack>> {
+        let (global_matcher, _) = ignore::gitignore::GitignoreBuilder::new(root).build_global();
+        let stack = (!global_matcher.is_empty()).then(|| Arc::new(GitignoreStack { matcher: global_matcher, parent: None }));
+        Self::extended_with_files(root, &[root.join(".git").join("info").join("exclude")], stack)
+    }
 
-        let (matcher, _) = ignore::gitignore::Gitignore::new(&gitignore_path);
-        if matcher.is_empty() {
+    fn extended_with_files(dir: &Path, paths: &[PathBuf], parent: Option<Arc<GitignoreStack>>) -> Option<Arc<GitignoreStack>> {
+        let mut builder = ignore::gitignore::GitignoreBuilder::new(dir);
+        let mut any_file = false;
+        for path in paths.iter().filter(|x| x.is_file()) {
+            let _ = builder.add(path);
+            any_file = true;
+        }
+        if !any_file {
             return parent;
         }
 
-        Some(Arc::new(GitignoreStack { matcher, parent }))
+        match builder.build() {
+            Ok(matcher) if !matcher.is_empty() => Some(Arc::new(GitignoreStack { matcher, parent })),
+            _ => parent
+        }
     }
 
-    // The .gitignore files of every dir between the repository root and the given dir, excluding it
-    fn of_ancestors(dir: &Path) -> Option<Arc<GitignoreStack>> {
-        if dir.join(".git").exists() {
-            return None;
+    // For the .gitignore files that don't exist in the working tree, like the ones of a git revision
+    pub fn extended_with_contents(dir: &Path, contents: &str, parent: Option<Arc<GitignoreStack>>) -> Option<Arc<GitignoreStack>> {
+        let mut builder = ignore::gitignore::GitignoreBuilder::new(dir);
+        for line in contents.lines() {
+            let _ = builder.add_line(None, line);
         }
 
+        match builder.build() {
+            Ok(matcher) if !matcher.is_empty() => Some(Arc::new(GitignoreStack { matcher, parent })),
+            _ => parent
+        }
+    }
+
+    // The ignore files of every dir between the repository root and the given dir, excluding it,
+    // on top of the excludes of the repository
+    fn of_ancestors(dir: &Path) -> Option<Arc<GitignoreStack>> {
         let mut relevant_ancestors: Vec<&Path> = Vec::new();
-        for ancestor in dir.ancestors().skip(1) {
-            relevant_ancestors.push(ancestor);
+        let mut stack = None;
+        for ancestor in dir.ancestors() {
+            if ancestor != dir {
+                relevant_ancestors.push(ancestor);
+            }
             if ancestor.join(".git").exists() {
+                stack = Self::of_repository(ancestor);
                 break;
             }
         }
 
-        let mut stack = None;
         for ancestor in relevant_ancestors.iter().rev() {
             stack = Self::extended(ancestor, stack);
         }
@@ -609,12 +1259,17 @@ impl GitignoreStack {
         stack
     }
 
+    // The ignore files of a dir and of every dir above it, up to the repository root
+    pub fn of_dir(dir: &Path) -> Option<Arc<GitignoreStack>> {
+        Self::extended(dir, Self::of_ancestors(dir))
+    }
+
     // Used for paths that the program discovered on its own, like the matches of a glob pattern
     pub fn is_path_ignored(path: &Path) -> bool {
         let is_dir = path.is_dir();
         let Some(parent) = path.parent() else { return false };
 
-        let stack = Self::extended(parent, Self::of_ancestors(parent));
+        let stack = Self::of_dir(parent);
         match stack {
             Some(x) => x.is_ignored_with_ancestor_dirs(path, is_dir),
             None => false
@@ -623,7 +1278,7 @@ impl GitignoreStack {
 
     // Unlike the traversal, which prunes ignored dirs as it descends and therefore only has to
     // check the entry itself, a standalone path has to be checked against its parent dirs too
-    fn is_ignored_with_ancestor_dirs(&self, path: &Path, is_dir: bool) -> bool {
+    pub fn is_ignored_with_ancestor_dirs(&self, path: &Path, is_dir: bool) -> bool {
         let mut node = Some(self);
         while let Some(stack) = node {
             match stack.matcher.matched_path_or_any_parents(path, is_dir) {
@@ -655,6 +1310,9 @@ impl GitignoreStack {
 
 pub mod domain {
     use super::*;
+    use std::hash::{Hash, Hasher};
+
+    pub const MAX_SEPARATE_DEPTH: usize = 8;
 
     #[derive(Debug, Clone)]
     pub struct Language {
@@ -664,7 +1322,16 @@ pub mod domain {
         pub comment_symbols : Vec<String>,
         pub multiline_comment_start_symbol : Option<String>,
         pub multiline_comment_end_symbol : Option<String>,
+        pub function_declarations : Vec<String>,
+        pub branch_tokens : Vec<String>,
+        // Either empty or the start and end symbols of a block, like { and }
+        pub block_symbols : Vec<String>,
+        // Globs of the file names that contain only test code, like *_test.go
+        pub test_patterns : Vec<String>,
+        // The symbols that start a block of test code inside a file, like #[cfg(test)]
+        pub test_blocks : Vec<String>,
         pub keywords : Vec<Keyword>,
+        pub comment_keywords : Vec<Keyword>,
         pub finders : OnceLock<crate::file_parser::LanguageFinders>
     }
 
@@ -676,7 +1343,13 @@ pub mod domain {
                 && self.comment_symbols == other.comment_symbols
                 && self.multiline_comment_start_symbol == other.multiline_comment_start_symbol
                 && self.multiline_comment_end_symbol == other.multiline_comment_end_symbol
+                && self.function_declarations == other.function_declarations
+                && self.branch_tokens == other.branch_tokens
+                && self.block_symbols == other.block_symbols
+                && self.test_patterns == other.test_patterns
+                && self.test_blocks == other.test_blocks
                 && self.keywords == other.keywords
+                && self.comment_keywords == other.comment_keywords
         }
     }
 
@@ -690,7 +1363,19 @@ pub mod domain {
     pub struct LanguageContentInfo {
         pub lines : usize,
         pub code_lines : usize,
-        pub keyword_occurences : HashMap<String,usize>
+        // The code lines of the test files and of the test blocks inside the rest of the files
+        pub test_code_lines : usize,
+        pub functions : usize,
+        pub complexity : usize,
+        // The number of code lines in each nesting depth, the index is the depth
+        pub depth_histogram : Vec<usize>,
+        pub hygiene : LanguageHygiene,
+        // The lines of every file, for the distribution of the file lengths
+        pub file_lines : Vec<usize>,
+        pub long_lines : usize,
+        pub keyword_occurences : HashMap<String,usize>,
+        // Only the comment keywords that were found at least once are present
+        pub comment_keyword_occurences : HashMap<String,usize>
     }
 
     #[derive(Debug,PartialEq,Default,Clone)]
@@ -703,7 +1388,75 @@ pub mod domain {
     pub struct FileStats {
         pub lines : usize,
         pub code_lines : usize,
-        pub keyword_occurences : Vec<usize>
+        pub test_code_lines : usize,
+        pub functions : usize,
+        pub complexity : usize,
+        pub depth_histogram : Vec<usize>,
+        // Only measured when the hygiene section is requested
+        pub hygiene : Option<FileHygiene>,
+        // Only calculated when duplicate files are detected
+        pub content_hash : Option<ContentHash>,
+        // Only calculated when duplicated code is detected
+        pub code_line_fingerprints : Vec<LineFingerprint>,
+        // The 1-based numbers of the code lines, only collected when the authors are requested
+        pub code_line_numbers : Vec<usize>,
+        pub long_lines : usize,
+        pub keyword_occurences : Vec<usize>,
+        pub comment_keyword_occurences : Vec<usize>
+    }
+
+    #[derive(Debug,PartialEq,Default,Clone)]
+    pub struct FileHygiene {
+        pub lf_lines : usize,
+        pub crlf_lines : usize,
+        pub space_indented_lines : usize,
+        pub tab_indented_lines : usize,
+        pub trailing_whitespace_lines : usize,
+        pub has_final_newline : bool
+    }
+
+    // The number of files of a language that have each property
+    #[derive(Debug,PartialEq,Default,Clone)]
+    pub struct LanguageHygiene {
+        pub lf_files : usize,
+        pub crlf_files : usize,
+        pub mixed_line_ending_files : usize,
+        pub space_indented_files : usize,
+        pub tab_indented_files : usize,
+        pub mixed_indentation_files : usize,
+        pub trailing_whitespace_files : usize,
+        pub no_final_newline_files : usize
+    }
+
+    #[derive(Debug,PartialEq,Clone,Copy)]
+    pub enum LineEnding {
+        LF,
+        CRLF,
+        Mixed,
+        // A file without any line breaks
+        Unknown
+    }
+
+    #[derive(Debug,PartialEq,Clone,Copy)]
+    pub enum Indentation {
+        Spaces,
+        Tabs,
+        Mixed,
+        // A file without any indented lines
+        Unknown
+    }
+
+    #[derive(Debug,PartialEq,Eq,Hash,Clone,Copy)]
+    pub struct ContentHash {
+        pub hash : u64,
+        pub bytes : usize
+    }
+
+    // The hash of a code line, without its comments, string literals and whitespace
+    #[derive(Debug,PartialEq,Clone,Copy)]
+    pub struct LineFingerprint {
+        pub hash : u64,
+        pub line_number : usize
     }
 
     impl Clone for Keyword {
@@ -716,9 +1469,11 @@ pub mod domain {
     }
 
     impl Language {
+        #[allow(clippy::too_many_arguments)]
         pub fn new(name: String, extensions: Vec<String>, string_symbols: Vec<String>, comment_symbols: Vec<String>,
             multiline_comment_start_symbol: Option<String>, multiline_comment_end_symbol: Option<String>,
-            keywords: Vec<Keyword>) -> Self
+            function_declarations: Vec<String>, branch_tokens: Vec<String>, block_symbols: Vec<String>,
+            test_patterns: Vec<String>, test_blocks: Vec<String>, keywords: Vec<Keyword>, comment_keywords: Vec<Keyword>) -> Self
         {
             Language {
                 name,
@@ -727,7 +1482,13 @@ pub mod domain {
                 comment_symbols,
                 multiline_comment_start_symbol,
                 multiline_comment_end_symbol,
+                function_declarations,
+                branch_tokens,
+                block_symbols,
+                test_patterns,
+                test_blocks,
                 keywords,
+                comment_keywords,
                 finders : OnceLock::new()
             }
         }
@@ -758,7 +1519,15 @@ pub mod domain {
             LanguageContentInfo {
                 lines,
                 code_lines,
-                keyword_occurences
+                test_code_lines: 0,
+                functions: 0,
+                complexity: 0,
+                depth_histogram: Vec::new(),
+                hygiene: LanguageHygiene::default(),
+                file_lines: Vec::new(),
+                long_lines: 0,
+                keyword_occurences,
+                comment_keyword_occurences: HashMap::new()
             }
         }
 
@@ -766,38 +1535,100 @@ pub mod domain {
             LanguageContentInfo {
                 lines,
                 code_lines: 0,
-                keyword_occurences: HashMap::new()
+                test_code_lines: 0,
+                functions: 0,
+                complexity: 0,
+                depth_histogram: Vec::new(),
+                hygiene: LanguageHygiene::default(),
+                file_lines: Vec::new(),
+                long_lines: 0,
+                keyword_occurences: HashMap::new(),
+                comment_keyword_occurences: HashMap::new()
             }
         }
 
-        pub fn add_file_stats(&mut self, other: FileStats, keywords: &[Keyword]) {
+        pub fn add_file_stats(&mut self, other: FileStats, language: &Language) {
             self.lines += other.lines;
             self.code_lines += other.code_lines;
+            self.test_code_lines += other.test_code_lines;
+            self.functions += other.functions;
+            self.complexity += other.complexity;
+            merge_depth_histograms(&mut self.depth_histogram, &other.depth_histogram);
+            if let Some(hygiene) = &other.hygiene {
+                self.hygiene.add_file_hygiene(hygiene);
+            }
+            self.file_lines.push(other.lines);
+            self.long_lines += other.long_lines;
             for (keyword_index, occurrences) in other.keyword_occurences.iter().enumerate() {
                 if *occurrences > 0 {
-                    *self.keyword_occurences.get_mut(&keywords[keyword_index].descriptive_name).unwrap() += *occurrences;
+                    *self.keyword_occurences.get_mut(&language.keywords[keyword_index].descriptive_name).unwrap() += *occurrences;
                 }
             }
+            add_comment_keyword_occurences(&mut self.comment_keyword_occurences, &other.comment_keyword_occurences, language);
         }
 
-        pub fn from_file_stats(stats: FileStats, keywords: &[Keyword]) -> LanguageContentInfo {
+        pub fn from_file_stats(stats: FileStats, language: &Language) -> LanguageContentInfo {
             let mut keyword_occurences = HashMap::<String,usize>::new();
             for (keyword_index, occurrences) in stats.keyword_occurences.iter().enumerate() {
-                keyword_occurences.insert(keywords[keyword_index].descriptive_name.clone(), *occurrences);
+                keyword_occurences.insert(language.keywords[keyword_index].descriptive_name.clone(), *occurrences);
+            }
+            let mut comment_keyword_occurences = HashMap::<String,usize>::new();
+            add_comment_keyword_occurences(&mut comment_keyword_occurences, &stats.comment_keyword_occurences, language);
+            let mut hygiene = LanguageHygiene::default();
+            if let Some(file_hygiene) = &stats.hygiene {
+                hygiene.add_file_hygiene(file_hygiene);
             }
             LanguageContentInfo {
                 lines : stats.lines,
                 code_lines : stats.code_lines,
-                keyword_occurences
+                test_code_lines : stats.test_code_lines,
+                functions : stats.functions,
+                complexity : stats.complexity,
+                depth_histogram : stats.depth_histogram,
+                hygiene,
+                file_lines : vec![stats.lines],
+                long_lines : stats.long_lines,
+                keyword_occurences,
+                comment_keyword_occurences
             }
         }
 
         pub fn add_content_info(&mut self, other: &LanguageContentInfo) {
             self.lines += other.lines;
             self.code_lines += other.code_lines;
+            self.test_code_lines += other.test_code_lines;
+            self.functions += other.functions;
+            self.complexity += other.complexity;
+            merge_depth_histograms(&mut self.depth_histogram, &other.depth_histogram);
+            self.hygiene.add_language_hygiene(&other.hygiene);
+            self.file_lines.extend_from_slice(&other.file_lines);
+            self.long_lines += other.long_lines;
             for (k,v) in other.keyword_occurences.iter() {
                 *self.keyword_occurences.get_mut(k).unwrap() += *v;
             }
+            for (k,v) in other.comment_keyword_occurences.iter() {
+                *self.comment_keyword_occurences.entry(k.to_owned()).or_insert(0) += *v;
+            }
+        }
+
+        pub fn total_comment_keywords(&self) -> usize {
+            self.comment_keyword_occurences.values().sum()
+        }
+
+        pub fn average_code_lines_per_function(&self) -> Option<f64> {
+            if self.functions == 0 {
+                None
+            } else {
+                Some(self.code_lines as f64 / self.functions as f64)
+            }
+        }
+
+        pub fn max_depth(&self) -> Option<usize> {
+            max_depth_of(&self.depth_histogram)
+        }
+
+        pub fn average_depth(&self) -> Option<f64> {
+            average_depth_of(&self.depth_histogram)
         }
     }
 
@@ -806,7 +1637,15 @@ pub mod domain {
             LanguageContentInfo {
                 lines : 0,
                 code_lines : 0,
-                keyword_occurences : get_keyword_stats_map(ext)
+                test_code_lines : 0,
+                functions : 0,
+                complexity : 0,
+                depth_histogram : Vec::new(),
+                hygiene : LanguageHygiene::default(),
+                file_lines : Vec::new(),
+                long_lines : 0,
+                keyword_occurences : get_keyword_stats_map(ext),
+                comment_keyword_occurences : HashMap::new()
             }
         }
     }
@@ -835,7 +1674,35 @@ pub mod domain {
             FileStats {
                 lines : 0,
                 code_lines : 0,
-                keyword_occurences : vec![0; keywords.len()]
+                test_code_lines : 0,
+                functions : 0,
+                complexity : 0,
+                depth_histogram : Vec::new(),
+                hygiene : None,
+                content_hash : None,
+                code_line_fingerprints : Vec::new(),
+                code_line_numbers : Vec::new(),
+                long_lines : 0,
+                keyword_occurences : vec![0; keywords.len()],
+                comment_keyword_occurences : Vec::new()
+            }
+        }
+
+        pub fn with_all_keywords_of(language: &Language) -> Self {
+            FileStats {
+                lines : 0,
+                code_lines : 0,
+                test_code_lines : 0,
+                functions : 0,
+                complexity : 0,
+                depth_histogram : Vec::new(),
+                hygiene : None,
+                content_hash : None,
+                code_line_fingerprints : Vec::new(),
+                code_line_numbers : Vec::new(),
+                long_lines : 0,
+                keyword_occurences : vec![0; language.keywords.len()],
+                comment_keyword_occurences : vec![0; language.comment_keywords.len()]
             }
         }
 
@@ -847,9 +1714,191 @@ pub mod domain {
             self.code_lines += 1;
         }
 
+        pub fn incr_test_code_lines(&mut self) {
+            self.test_code_lines += 1;
+        }
+
         pub fn incr_keyword(&mut self, keyword_index: usize) {
             self.keyword_occurences[keyword_index] += 1;
         }
+
+        pub fn add_functions(&mut self, functions: usize) {
+            self.functions += functions;
+        }
+
+        pub fn add_complexity(&mut self, complexity: usize) {
+            self.complexity += complexity;
+        }
+
+        pub fn incr_long_lines(&mut self) {
+            self.long_lines += 1;
+        }
+
+        pub fn add_code_line_depth(&mut self, depth: usize) {
+            if self.depth_histogram.len() <= depth {
+                self.depth_histogram.resize(depth + 1, 0);
+            }
+            self.depth_histogram[depth] += 1;
+        }
+
+        pub fn add_comment_keyword(&mut self, keyword_index: usize, occurrences: usize) {
+            self.comment_keyword_occurences[keyword_index] += occurrences;
+        }
+
+        pub fn total_comment_keywords(&self) -> usize {
+            self.comment_keyword_occurences.iter().sum()
+        }
+    }
+
+    impl ContentHash {
+        pub fn of(contents: &str) -> Self {
+            let mut hasher = std::hash::DefaultHasher::new();
+            contents.hash(&mut hasher);
+            ContentHash {
+                hash : hasher.finish(),
+                bytes : contents.len()
+            }
+        }
+    }
+
+    impl LineFingerprint {
+        // Runs of whitespace are treated as a single space, so that reformatted copies still match
+        pub fn of(cleansed: &str, line_number: usize) -> Self {
+            let mut hasher = std::hash::DefaultHasher::new();
+            for word in cleansed.split_whitespace() {
+                word.hash(&mut hasher);
+            }
+            LineFingerprint {
+                hash : hasher.finish(),
+                line_number
+            }
+        }
+    }
+
+    impl FileHygiene {
+        // The line is given both with and without its line ending
+        pub fn add_line(&mut self, line_with_ending: &str, line: &str) {
+            if line_with_ending.ends_with("\r\n") {
+                self.crlf_lines += 1;
+            } else if line_with_ending.ends_with('\n') {
+                self.lf_lines += 1;
+            }
+
+            if line.ends_with([' ', '\t']) {
+                self.trailing_whitespace_lines += 1;
+            }
+            // Lines with only whitespace are not indented, they are just trailing whitespace
+            if line.trim_start().is_empty() {
+                return;
+            }
+            if line.starts_with(' ') {
+                self.space_indented_lines += 1;
+            } else if line.starts_with('\t') {
+                self.tab_indented_lines += 1;
+            }
+        }
+
+        pub fn line_ending(&self) -> LineEnding {
+            match (self.lf_lines > 0, self.crlf_lines > 0) {
+                (true, true) => LineEnding::Mixed,
+                (true, false) => LineEnding::LF,
+                (false, true) => LineEnding::CRLF,
+                (false, false) => LineEnding::Unknown
+            }
+        }
+
+        pub fn indentation(&self) -> Indentation {
+            match (self.space_indented_lines > 0, self.tab_indented_lines > 0) {
+                (true, true) => Indentation::Mixed,
+                (true, false) => Indentation::Spaces,
+                (false, true) => Indentation::Tabs,
+                (false, false) => Indentation::Unknown
+            }
+        }
+    }
+
+    impl LanguageHygiene {
+        pub fn add_file_hygiene(&mut self, hygiene: &FileHygiene) {
+            match hygiene.line_ending() {
+                LineEnding::LF => self.lf_files += 1,
+                LineEnding::CRLF => self.crlf_files += 1,
+                LineEnding::Mixed => self.mixed_line_ending_files += 1,
+                LineEnding::Unknown => ()
+            }
+            match hygiene.indentation() {
+                Indentation::Spaces => self.space_indented_files += 1,
+                Indentation::Tabs => self.tab_indented_files += 1,
+                Indentation::Mixed => self.mixed_indentation_files += 1,
+                Indentation::Unknown => ()
+            }
+            if hygiene.trailing_whitespace_lines > 0 {
+                self.trailing_whitespace_files += 1;
+            }
+            if !hygiene.has_final_newline {
+                self.no_final_newline_files += 1;
+            }
+        }
+
+        pub fn add_language_hygiene(&mut self, other: &LanguageHygiene) {
+            self.lf_files += other.lf_files;
+            self.crlf_files += other.crlf_files;
+            self.mixed_line_ending_files += other.mixed_line_ending_files;
+            self.space_indented_files += other.space_indented_files;
+            self.tab_indented_files += other.tab_indented_files;
+            self.mixed_indentation_files += other.mixed_indentation_files;
+            self.trailing_whitespace_files += other.trailing_whitespace_files;
+            self.no_final_newline_files += other.no_final_newline_files;
+        }
+
+        // Ties go to LF and spaces
+        pub fn usual_line_ending(&self) -> LineEnding {
+            if self.crlf_files > self.lf_files {LineEnding::CRLF} else {LineEnding::LF}
+        }
+
+        pub fn usual_indentation(&self) -> Indentation {
+            if self.tab_indented_files > self.space_indented_files {Indentation::Tabs} else {Indentation::Spaces}
+        }
+    }
+
+    // The histogram never ends with an empty depth, so the last index is the max depth
+    pub fn max_depth_of(depth_histogram: &[usize]) -> Option<usize> {
+        depth_histogram.len().checked_sub(1)
+    }
+
+    pub fn average_depth_of(depth_histogram: &[usize]) -> Option<f64> {
+        let lines = depth_histogram.iter().sum::<usize>();
+        if lines == 0 {
+            return None;
+        }
+        let depth_sum = depth_histogram.iter().enumerate().map(|(depth, lines)| depth * lines).sum::<usize>();
+        Some(depth_sum as f64 / lines as f64)
+    }
+
+    // Depths from MAX_SEPARATE_DEPTH and deeper are grouped together, so that the histogram stays short
+    pub fn depth_histogram_entries(depth_histogram: &[usize]) -> Vec<(String,usize)> {
+        let mut entries = depth_histogram.iter().take(MAX_SEPARATE_DEPTH).enumerate()
+                .map(|(depth, lines)| (depth.to_string(), *lines)).collect::<Vec<_>>();
+        if depth_histogram.len() > MAX_SEPARATE_DEPTH {
+            entries.push((format!("{MAX_SEPARATE_DEPTH}+"), depth_histogram[MAX_SEPARATE_DEPTH..].iter().sum()));
+        }
+        entries
+    }
+
+    pub fn merge_depth_histograms(into: &mut Vec<usize>, other: &[usize]) {
+        if into.len() < other.len() {
+            into.resize(other.len(), 0);
+        }
+        for (depth, lines) in other.iter().enumerate() {
+            into[depth] += lines;
+        }
+    }
+
+    fn add_comment_keyword_occurences(map: &mut HashMap<String,usize>, occurences: &[usize], language: &Language) {
+        for (keyword_index, occurrences) in occurences.iter().enumerate() {
+            if *occurrences > 0 {
+                *map.entry(language.comment_keywords[keyword_index].descriptive_name.clone()).or_insert(0) += *occurrences;
+            }
+        }
     }
 
     fn get_keyword_stats_map(extension: &Language) -> HashMap<String,usize> {
@@ -927,4 +1976,31 @@ mod tests {
         assert_eq!(customf, ef);
         assert_eq!(customf, cf);
     }
+
+    #[test]
+    fn test_Estimation_calculation() {
+        let coefficients = config_manager::CocomoCoefficients::default();
+        let estimation = Estimation::calculate(1000, &coefficients, 56286);
+        assert_eq!((2.4, 3.49, 0.69, 27017.28), (round_2(estimation.effort), round_2(estimation.schedule),
+                round_2(estimation.people), round_2(estimation.cost)));
+
+        let estimation = Estimation::calculate(50000, &coefficients, 56286);
+        assert_eq!((145.93, 16.61, 8.79), (round_2(estimation.effort), round_2(estimation.schedule), round_2(estimation.people)));
+
+        assert_eq!(Estimation {effort: 0.0, schedule: 0.0, people: 0.0, cost: 0.0}, Estimation::calculate(0, &coefficients, 56286));
+    }
+
+    #[test]
+    fn test_the_faulty_files_are_removed_from_the_language_they_were_counted_in() {
+        let mut languages_metadata_map = hashmap!["C".to_owned() => LanguageMetadata::new(1, 10),
+                "C++".to_owned() => LanguageMetadata::new(2, 50)];
+        // A .h file that the linguist attributes assigned to C++, and a file without an extension
+        let faulty_files: FaultyFilesListMut = Arc::new(Mutex::new(vec![
+                FaultyFileDetails::new("a.h".to_owned(), "invalid UTF-8".to_owned(), 20, Arc::from("C++")),
+                FaultyFileDetails::new("b".to_owned(), "invalid UTF-8".to_owned(), 5, Arc::from("C++"))]));
+        remove_faulty_files_stats(&faulty_files, &mut languages_metadata_map);
+
+        assert_eq!(&LanguageMetadata::new(1, 10), languages_metadata_map.get("C").unwrap());
+        assert_eq!(&LanguageMetadata::new(0, 25), languages_metadata_map.get("C++").unwrap());
+    }
 }
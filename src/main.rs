@@ -97,6 +97,20 @@ fn main() {
         });
     }
 
+    if config.diff.is_some() {
+        if let Err(x) = mezura::run_diff(config, language_map) {
+            println!("{}",x.formatted());
+        }
+        return;
+    }
+
+    if config.backfill.is_some() {
+        if let Err(x) = mezura::backfill_log(config, language_map) {
+            println!("{}",x.formatted());
+        }
+        return;
+    }
+
     let instant = Instant::now();
     match mezura::run(config, language_map) {
         Ok(x) => {
@@ -107,7 +121,13 @@ fn main() {
             };
             println!("{}",perf + &metrics);
         },
-        Err(x) => println!("{}",x.formatted())
+        Err(x) => {
+            println!("{}",x.formatted());
+            // So that a CI job fails instead of counting only a part of the tree
+            if matches!(x, ParseFilesError::TraversalErrors(_)) {
+                std::process::exit(1);
+            }
+        }
     }
 }
 
@@ -249,8 +269,8 @@ mod tests {
     fn test_retain_only_languages_of_interest() {
         let languages_of_interest = vec!["java".to_owned()];
         let mut language_map = hashmap![
-                "Java".to_owned() => Language::new("Java".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![]),
-                "C#".to_owned() => Language::new("C#".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![])];
+                "Java".to_owned() => Language::new("Java".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![],vec![],vec![],vec![],vec![],vec![],vec![]),
+                "C#".to_owned() => Language::new("C#".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![],vec![],vec![],vec![],vec![],vec![],vec![])];
 
         let result = retain_only_languages_of_interest(&mut language_map, &languages_of_interest);
         assert!(result.unwrap().is_none());
@@ -258,8 +278,8 @@ mod tests {
         
         let languages_of_interest = vec!["java".to_owned(),"c++".to_owned(),"Rust".to_owned()];
         let mut language_map = hashmap![
-                "Java".to_owned() => Language::new("Java".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![]),
-                "C#".to_owned() => Language::new("C#".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![])];
+                "Java".to_owned() => Language::new("Java".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![],vec![],vec![],vec![],vec![],vec![],vec![]),
+                "C#".to_owned() => Language::new("C#".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![],vec![],vec![],vec![],vec![],vec![],vec![])];
 
         let result = retain_only_languages_of_interest(&mut language_map, &languages_of_interest);
         assert!(result.unwrap().is_some());
@@ -267,8 +287,8 @@ mod tests {
         
         let languages_of_interest = vec!["c++".to_owned(),"Rust".to_owned()];
         let mut language_map = hashmap![
-                "Java".to_owned() => Language::new("Java".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![]),
-                "C#".to_owned() => Language::new("C#".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![])];
+                "Java".to_owned() => Language::new("Java".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![],vec![],vec![],vec![],vec![],vec![],vec![]),
+                "C#".to_owned() => Language::new("C#".to_owned(),vec![],vec![],vec!["\"".to_owned()],None,None,vec![],vec![],vec![],vec![],vec![],vec![],vec![])];
 
         let result = retain_only_languages_of_interest(&mut language_map, &languages_of_interest);
         assert!(result.is_err());
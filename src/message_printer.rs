@@ -16,6 +16,11 @@ pub const DIRS_HELP  :  &str =
     are dotted, are skipped (see the '--no-gitignore' and '--search-in-dotted' commands).
     A path that you write out explicitly is always used, even if it is ignored or dotted.
     Targets that are contained in other targets are dropped, so that no file is counted twice.
+    A file can also be a zip, tar, tar.gz or tgz archive, whose files are read without extracting
+    it and counted like the files of a directory, by their paths inside the archive. The '--exclude'
+    and '--include' patterns, the languages, the file filters and the ignore files and .gitattributes
+    files of the directories above the archive apply to them, but the ones inside the archive don't.
+    Nested archives are not opened.
     If you are using Windows Powershell, you will need to escape the commas with a backtick: `
     or surround all the arguments with quotation marks:
     <path1>`, <path2>`, <path3>   or   \"<path1>, <path2>, <path3>\"
@@ -40,6 +45,25 @@ pub const EXCLUDE_HELP  :  &str =
     or surround all the arguments with quotation marks:
     <arg1>`, <arg2>`, <arg3>   or   \"<arg1>, <arg2>, <arg3>\"
 
+";
+pub const INCLUDE_HELP  :  &str =
+"--include
+    1..n glob patterns separated by commas.
+
+    When given, only the files that match at least one of the patterns are counted, while the rest
+    are included in the reported count of excluded files. The '--exclude' patterns still apply.
+
+    A pattern without a slash matches a file or folder name at any depth ('*.rs', 'src').
+    Unlike '--exclude', a pattern with slashes is relative to each one of the target directories,
+    like in a .gitignore ('src/**' matches '<target>/src/main.rs' but not '<target>/lib/src/a.rs').
+    Full absolute paths work too. Glob syntax is supported in both forms: * ? [..] {..}
+    A pattern that matches a folder includes every file inside it ('docs/api' is like 'docs/api/**'),
+    and the folders that no pattern could match inside of are not traversed at all.
+
+    If you are using Windows Powershell, you will need to escape the commas with a backtick: `
+    or surround all the arguments with quotation marks:
+    <arg1>`, <arg2>`, <arg3>   or   \"<arg1>, <arg2>, <arg3>\"
+
 ";
 pub const NO_GITIGNORE_HELP  :  &str =
 "--no-gitignore
@@ -53,8 +77,109 @@ pub const NO_GITIGNORE_HELP  :  &str =
     even if a .gitignore of their parent directories would ignore them. The matches of a glob
     pattern do not count as written out explicitly, since the program is the one that found them.
 
+    The .ignore files (the convention of ripgrep and fd) and the .mezuraignore files, for the
+    exclusions that only concern this program, are read the same way. In a directory, a
+    .mezuraignore takes precedence over an .ignore, which takes precedence over a .gitignore.
+    The .git/info/exclude of the repository and the global excludes file of git (core.excludesFile)
+    apply too, below all of them.
+
     This flag disables that behavior, so that every relevant file is counted
-    regardless of .gitignore rules.
+    regardless of the rules of the ignore files.
+
+";
+pub const NO_GITATTRIBUTES_HELP  :  &str =
+"--no-gitattributes
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    By default, the program reads the .gitattributes files of the traversed directories (and of
+    their parent directories, up to the repository root) like GitHub's linguist does, so that the
+    stats line up with the language bar of GitHub. The files that are marked as
+    'linguist-vendored', 'linguist-generated' or 'linguist-documentation' are skipped and included
+    in the excluded files count, while 'linguist-language=<name>' counts the matching files as the
+    given language, regardless of their extension, as long as it is one of the supported languages.
+    The names are case-insensitive, and the names and aliases of linguist are accepted too (like
+    'JavaScript', 'TypeScript' or 'golang'), while the unsupported languages are ignored, so that
+    the extension decides. Like in git, the later lines and the deeper files take precedence.
+
+    This flag disables that behavior, so that the attributes are not taken into account.
+
+";
+pub const FOLLOW_SYMLINKS_HELP  :  &str =
+"--follow-symlinks
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    By default, the symbolic links (and the Windows junctions) that are found while traversing the
+    target directories are skipped. With this flag they are followed, so that the linked directories
+    are traversed and the linked files are counted. Every directory and every file is visited once,
+    however many links lead to it, so the links that point back to their parent directories cause
+    no loops, and the files that are reached a second time are skipped.
+
+    Regardless of this flag, the hard links of a file are counted only once. The skipped files are
+    reported as duplicates next to the number of files found.
+
+";
+pub const MAX_DEPTH_HELP  :  &str =
+"--max-depth
+    Number, from 1 to 10000. Default: unlimited
+
+    Only the files up to this depth inside every target directory are counted, with the files
+    directly inside a target directory being at depth 1. The deeper folders are not traversed at
+    all, so their files are not included in the reported count of excluded files.
+
+";
+pub const MAX_FILE_SIZE_HELP  :  &str =
+"--max-file-size
+    A size in bytes, optionally followed by a unit: B, KB, MB or GB (case-insensitive), where
+    1 KB is 1000 bytes, like in the results. Default: unlimited
+
+    The files that are bigger than this size are skipped, and they are included in the excluded
+    files count.
+
+";
+pub const MIN_FILE_SIZE_HELP  :  &str =
+"--min-file-size
+    A size in bytes, optionally followed by a unit: B, KB, MB or GB (case-insensitive), where
+    1 KB is 1000 bytes, like in the results. Default: 0
+
+    The files that are smaller than this size are skipped, and they are included in the excluded
+    files count. It can't be bigger than '--max-file-size'.
+
+";
+pub const MODIFIED_SINCE_HELP  :  &str =
+"--modified-since
+    Either a date, as YYYY-MM-DD or in the RFC 3339 format (2024-01-31T10:00:00+02:00), or a
+    number of hours, days or weeks that end at the time of the run (12h, 30d, 2w).
+
+    Only the files that were last modified after that date, or in that period, are counted,
+    according to their modification time, while the rest are included in the excluded files count.
+    A date without a time is the start of that day in the local timezone. With '--rev' this filter
+    is ignored, since the files of a revision have no modification time.
+
+";
+pub const EXCLUSION_REASONS_HELP  :  &str =
+"--exclusion-reasons
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Shows, under the number of the files found, how many of the excluded files were excluded by
+    every reason: the '--exclude' and the '--include' patterns, the ignore files, the linguist
+    attributes, the file size limits and the modification time.
+
+";
+pub const FAIL_ON_TRAVERSAL_ERRORS_HELP  :  &str =
+"--fail-on-traversal-errors
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    By default, the directories that can't be read while traversing the target directories, like
+    the ones without the permissions to list them, and the entries that can't be inspected, are
+    skipped, and their number is reported next to the number of the files found (with their paths
+    and errors if '--show-faulty-files' is given). The same goes for the files of '--files-from'
+    that don't exist or can't be read. With this flag, any one of them stops the program before
+    any stats are shown or logged, with a non-zero exit code, so that a CI job can't silently
+    count only a part of a tree.
 
 ";
 pub const LANGUAGES_HELP  :  &str =
@@ -120,6 +245,8 @@ pub const SHOW_FAULTY_FILES_HELP  :  &str =
 
     This flag specifies that their path, along with information about the exact error is displayed too.
     The most common reason for this error is if a file contains non UTF-8 characters.
+    The same goes for the directories, the entries and the listed files that couldn't be read
+    while searching.
 
 ";
 pub const NO_VISUAL_HELP  :  &str =
@@ -130,6 +257,273 @@ pub const NO_VISUAL_HELP  :  &str =
     Disables the colors in the \"overview\" section of the results, and disables the visualization with
     the vertical lines that represent the percentages.
 
+";
+pub const PER_FILE_HELP  :  &str =
+"--per-file
+    Optional number, from 0 to 100. Default: 10 if given without a number, otherwise disabled (0)
+
+    Adds a \"Per file\" section to the results, that lists the files with the most comment markers
+    (like TODO, FIXME or HACK), the most complex files and the most deeply nested files. The paths
+    are shown relative to the target directory they were found in. Providing 0 disables the section,
+    which is useful to override a value that is saved in a configuration.
+
+";
+pub const INDENT_WIDTH_HELP  :  &str =
+"--indent-width
+    Number, from 1 to 16. Default: 4
+
+    The number of spaces that make up one nesting level, for the languages that don't declare block
+    symbols (like python). A tab is always one level.
+
+";
+pub const HYGIENE_HELP  :  &str =
+"--hygiene
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Adds a \"Hygiene\" section to the results, that counts per language the files that use LF or CRLF
+    line endings, space or tab indentation, that have trailing whitespace and that lack a final
+    newline. It also lists the offending files: the ones that mix line endings or indentation, that
+    don't follow the convention of most files of their language, that have trailing whitespace or
+    that lack a final newline.
+
+";
+pub const DISTRIBUTION_HELP  :  &str =
+"--distribution
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Adds a \"Distribution\" section to the results, with the median, the 90th and the 99th percentile
+    and the maximum of the lines per file of every language, along with a histogram of them, since
+    the averages hide the outliers. It also shows the number of the lines that are longer than the
+    '--column-limit'.
+
+";
+pub const COLUMN_LIMIT_HELP  :  &str =
+"--column-limit
+    Number, from 1 to 1000. Default: 120
+
+    Lines longer than this number of columns are counted as long lines in the \"Distribution\" section
+    of the results. A tab counts as many columns as the '--indent-width'.
+
+";
+pub const DUPLICATE_FILES_HELP  :  &str =
+"--duplicate-files
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Adds a \"Duplicate files\" section to the results, that lists the groups of files of the same
+    language with identical contents, like vendored copies or copy-pasted modules, along with the
+    bytes that the extra copies waste. The contents are compared through a hash that is calculated
+    while parsing.
+
+";
+pub const DEDUPE_FILES_HELP  :  &str =
+"--dedupe-files
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Counts every group of files with identical contents only once in the stats, as if the extra
+    copies didn't exist. The groups are also listed in the \"Duplicate files\" section.
+
+";
+pub const DUPLICATES_HELP  :  &str =
+"--duplicates
+    Number, from 0 to 100. Default: 0
+
+    Adds a \"Duplicated code\" section to the results, that finds the blocks of at least this number
+    of consecutive code lines that appear more than once, in the same or in different files. The
+    lines are compared without their comments, their string literals and their whitespace. It shows
+    the percentage of duplicated code lines per language and the biggest duplicated blocks, along
+    with their locations. Providing 0 disables the section, which is useful to override a value
+    that is saved in a configuration.
+
+";
+pub const ESTIMATION_HELP  :  &str =
+"--estimation
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Adds an \"Estimation\" section to the results, with the effort in person-months, the schedule in
+    months, the people and the cost that the basic COCOMO model estimates for the total code lines.
+    The cost is the effort multiplied by the monthly salary and an overhead of 2.4. The estimation
+    is also saved in the logs.
+
+";
+pub const COCOMO_HELP  :  &str =
+"--cocomo
+    4 positive numbers separated by spaces: a b c d. Default: 2.4 1.05 2.5 0.38
+
+    The coefficients of the basic COCOMO model that the \"Estimation\" section uses, where the effort
+    is a * KLOC^b person-months and the schedule is c * effort^d months. The default values are the
+    ones for \"organic\" projects, while the \"semi-detached\" ones are 3.0 1.12 2.5 0.35 and the
+    \"embedded\" ones are 3.6 1.20 2.5 0.32.
+
+";
+pub const SALARY_HELP  :  &str =
+"--salary
+    Number, from 1 to 100000000. Default: 56286
+
+    The annual salary of a developer, that the estimated cost of the \"Estimation\" section is
+    based on.
+
+";
+pub const TEST_PATTERNS_HELP  :  &str =
+"--test-patterns
+    The glob patterns of the files that contain only test code, separated by commas if more than 1,
+    in this form: '--test-patterns <pattern1>, <pattern2>'. Default: **/tests/**
+    As with '--exclude', a pattern without a slash matches a file name at any depth (*_spec.lua),
+    while a pattern with slashes matches the end of the path inside the target directory
+    (src/testdata/**), so that the directories above it are never matched.
+
+    The code lines of the test files are counted as test code, and the \"Details\" section shows the
+    test and the non-test code lines of each language, along with their ratio. The patterns are
+    used on top of the ones that each language defines in its file (e.g. *_test.go for Go), and
+    the blocks of test code inside a file, like the #[cfg(test)] modules of Rust, are also counted.
+
+";
+pub const OWNERS_HELP  :  &str =
+"--owners
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Adds an \"Ownership\" section to the results, that aggregates the files, the lines and the code
+    lines of every owner or team of the CODEOWNERS file of the target dirs, which is searched in
+    the .github, the root and the docs dir, in this order. The patterns are matched like the ones
+    of a .gitignore, with the last matching pattern deciding the owners of a file. The files that
+    have no owners are counted and the biggest ones of them are listed.
+
+";
+pub const AUTHORS_HELP  :  &str =
+"--authors
+    No arguments in the cmd, but if specified in a configuration file use 'true' or 'yes' to enable,
+    or 'no' to disable. Default: no
+
+    Adds an \"Authors\" section, with the code and the extra lines of every author, overall and per
+    language. Every line of the parsed files is attributed to the author of its last change, through
+    'git blame', while its classification as code or extra comes from the parsing itself. The lines
+    that aren't committed yet are attributed to \"Not Committed Yet\", and the files that aren't
+    tracked by git are only counted. The authors are mapped through the .mailmap of the repository,
+    along with the one of '--mailmap'. Blaming every file takes time on big repositories.
+";
+pub const MAILMAP_HELP  :  &str =
+"--mailmap
+    The path of a mailmap file (e.g. ../authors.mailmap).
+
+    Maps the names and the emails of the authors of '--authors' to their canonical ones, in the
+    format of git's .mailmap files, so that an author who committed with many emails is counted
+    once. It is read after the .mailmap of the repository, if one exists, augmenting it.
+
+";
+pub const HOTSPOTS_HELP  :  &str =
+"--hotspots
+    Optional metric, either 'size' or 'complexity'. Default: size if given without a metric
+
+    Adds a \"Hotspots\" section, that ranks the files by how often they changed multiplied by their
+    code lines, or by their complexity, since the big or complex files that change a lot are the
+    riskiest ones. The changes are the commits that touched every file in the window of
+    '--churn-window', according to 'git log --numstat', along with the lines that they added and
+    removed. With '--rev' the window ends at the date of the revision. The files that aren't
+    tracked by git, or that didn't change in the window, are left out, like the files of the
+    languages without branch keywords when ranked by complexity.
+
+";
+pub const CHURN_WINDOW_HELP  :  &str =
+"--churn-window
+    Number of days, from 1 to 36500. Default: 365
+
+    The time window of the changes that '--hotspots' counts, ending today, or at the date of the
+    revision of '--rev'.
+
+";
+pub const REV_HELP  :  &str =
+"--rev
+    A git revision, like a branch, a tag or a commit hash (e.g. v1.0, main~10, 4f2a9c1).
+
+    Counts the files of the target dirs as they were at this revision, without checking it out.
+    The files are read through the local git binary ('git ls-tree' and 'git cat-file'), so every
+    target dir must be inside a git repository, while no network access is needed. The excluded
+    paths, the languages and the .gitignore files of the revision apply as usual. It is not saved
+    in configurations.
+
+";
+pub const BACKFILL_HELP  :  &str =
+"--backfill
+    Either 'tags', or a git revision range optionally followed by a number N, in this form:
+    '--backfill <range> [N]' (e.g. v1.0..main, HEAD 10). Default N: 1
+
+    Instead of counting the target dirs once, counts them at every selected commit of the git
+    repository of the first target dir, like '--rev' does, and adds an entry to the log of the
+    loaded configuration for each one, dated by the commit and named after its tags and message.
+    The commits are either the ones that are tagged, or every Nth commit of the range, starting
+    from its newest one and following only the first parent of the merges. A range without '..'
+    selects the whole history up to that revision. Entries that are already in the log are not
+    added again, and the log stays sorted by date, so that the \"Progress\" section is meaningful
+    for old projects right away. It needs a configuration to be loaded or saved, like '--log', and
+    it is not saved in configurations.
+
+";
+pub const DIFF_HELP  :  &str =
+"--diff
+    Two targets, each one either a dir or a git revision, in this form: '--diff <a> <b>'
+    (e.g. v1.0 main, ../old-copy ./, HEAD~1 HEAD)
+
+    Instead of counting the target dirs, compares a with b and reports the added, the removed and
+    the changed files of every language, along with the differences of their files and lines, like
+    the \"Progress\" section does for the totals. A dir is counted as is, while for a revision the
+    target dirs are counted at that revision, like '--rev' does. The files of the two sides are
+    matched by their paths relative to the counted dir, so that two copies of a project can be
+    compared too. It is useful for summarising the size of a change. It is not saved in
+    configurations.
+
+";
+pub const CHANGED_SINCE_HELP  :  &str =
+"--changed-since
+    A git revision, like a branch, a tag or a commit hash (e.g. main, HEAD~1, v1.0).
+
+    Counts only the files of the target dirs that changed since this revision, according to
+    'git diff --name-only', along with the untracked files that are not ignored. The files are
+    read from the working tree, and the deleted ones are left out. The excluded paths, the
+    languages and the .gitignore files apply as usual. Useful for pre-commit hooks and pull
+    request checks. It can't be combined with '--rev', '--diff', '--backfill', '--staged' or
+    '--files-from', and it is not saved in configurations.
+
+";
+pub const STAGED_HELP  :  &str =
+"--staged
+    No arguments.
+
+    Counts only the files of the target dirs that are staged for the next commit, according to
+    'git diff --cached --name-only'. The files are read from the working tree, and the deleted
+    ones are left out. The excluded paths, the languages and the .gitignore files apply as usual.
+    Useful for pre-commit hooks. Like '--changed-since', it can't be combined with the commands
+    that select other files, and it is not saved in configurations.
+
+";
+pub const FILES_FROM_HELP  :  &str =
+"--files-from
+    The path of a file, or '-' for the standard input.
+
+    Counts only the files that are listed in it, one per line or separated by NUL characters, like
+    the output of 'git ls-files -z', 'fd -0' or 'find -print0'. The relative paths are relative to
+    the working directory. The listed files are counted directly, without traversing any directory,
+    so by default the ignore files and the linguist attributes don't apply, since the list already
+    decides what is counted (see '--respect-ignores'). The '--exclude' and '--include' patterns, the
+    languages and the file filters still apply when they are given. A file that is listed more than
+    once is counted once, and the listed dirs are skipped, while the paths that don't exist or can't
+    be read are reported like the unreadable directories of a traversal. Like '--rev', it can't be
+    combined with the commands that select other files, and it is not saved in configurations.
+
+";
+pub const RESPECT_IGNORES_HELP  :  &str =
+"--respect-ignores
+    No arguments.
+
+    Only with '--files-from'. The listed files are checked against the ignore files and the linguist
+    attributes of their directories too, like the files of a traversal, unless '--no-gitignore' or
+    '--no-gitattributes' is given: the ignored files and the vendored, generated and documentation
+    files are skipped, and the language overrides apply. It is not saved in configurations.
+
 ";
 pub const COLORS_HELP  :  &str =
 "--colors
@@ -275,6 +669,7 @@ pub fn print_whole_help_message() {
     msg += TUNE_PALETTES_HELP;
     msg += DIRS_HELP;
     msg += EXCLUDE_HELP;
+    msg += INCLUDE_HELP;
     msg += LANGUAGES_HELP;
     msg += EXCLUDE_LANGUAGES_HELP;
     msg += THREADS_HELP;
@@ -283,6 +678,38 @@ pub fn print_whole_help_message() {
     msg += SHOW_FAULTY_FILES_HELP;
     msg += NO_VISUAL_HELP;
     msg += NO_GITIGNORE_HELP;
+    msg += NO_GITATTRIBUTES_HELP;
+    msg += FOLLOW_SYMLINKS_HELP;
+    msg += MAX_DEPTH_HELP;
+    msg += MAX_FILE_SIZE_HELP;
+    msg += MIN_FILE_SIZE_HELP;
+    msg += MODIFIED_SINCE_HELP;
+    msg += EXCLUSION_REASONS_HELP;
+    msg += FAIL_ON_TRAVERSAL_ERRORS_HELP;
+    msg += PER_FILE_HELP;
+    msg += INDENT_WIDTH_HELP;
+    msg += HYGIENE_HELP;
+    msg += DISTRIBUTION_HELP;
+    msg += COLUMN_LIMIT_HELP;
+    msg += DUPLICATE_FILES_HELP;
+    msg += DEDUPE_FILES_HELP;
+    msg += DUPLICATES_HELP;
+    msg += ESTIMATION_HELP;
+    msg += COCOMO_HELP;
+    msg += SALARY_HELP;
+    msg += TEST_PATTERNS_HELP;
+    msg += OWNERS_HELP;
+    msg += AUTHORS_HELP;
+    msg += MAILMAP_HELP;
+    msg += HOTSPOTS_HELP;
+    msg += CHURN_WINDOW_HELP;
+    msg += REV_HELP;
+    msg += BACKFILL_HELP;
+    msg += DIFF_HELP;
+    msg += CHANGED_SINCE_HELP;
+    msg += STAGED_HELP;
+    msg += FILES_FROM_HELP;
+    msg += RESPECT_IGNORES_HELP;
     msg += COLORS_HELP;
     msg += COLOR_PALETTE_HELP;
     msg += LOG_HELP;
@@ -425,6 +852,8 @@ fn get_help_msg_of_command(command: &str) -> Option<&str> {
         Some(DIRS_HELP)
     } else if command == EXCLUDE {
         Some(EXCLUDE_HELP)
+    } else if command == INCLUDE {
+        Some(INCLUDE_HELP)
     } else if command == LANGUAGES {
         Some(LANGUAGES_HELP)
     } else if command == EXCLUDE_LANGUAGES {
@@ -441,6 +870,70 @@ fn get_help_msg_of_command(command: &str) -> Option<&str> {
         Some(NO_VISUAL_HELP)
     } else if command == NO_GITIGNORE {
         Some(NO_GITIGNORE_HELP)
+    } else if command == NO_GITATTRIBUTES {
+        Some(NO_GITATTRIBUTES_HELP)
+    } else if command == FOLLOW_SYMLINKS {
+        Some(FOLLOW_SYMLINKS_HELP)
+    } else if command == MAX_DEPTH {
+        Some(MAX_DEPTH_HELP)
+    } else if command == MAX_FILE_SIZE {
+        Some(MAX_FILE_SIZE_HELP)
+    } else if command == MIN_FILE_SIZE {
+        Some(MIN_FILE_SIZE_HELP)
+    } else if command == MODIFIED_SINCE {
+        Some(MODIFIED_SINCE_HELP)
+    } else if command == EXCLUSION_REASONS {
+        Some(EXCLUSION_REASONS_HELP)
+    } else if command == FAIL_ON_TRAVERSAL_ERRORS {
+        Some(FAIL_ON_TRAVERSAL_ERRORS_HELP)
+    } else if command == PER_FILE {
+        Some(PER_FILE_HELP)
+    } else if command == INDENT_WIDTH {
+        Some(INDENT_WIDTH_HELP)
+    } else if command == HYGIENE {
+        Some(HYGIENE_HELP)
+    } else if command == DISTRIBUTION {
+        Some(DISTRIBUTION_HELP)
+    } else if command == COLUMN_LIMIT {
+        Some(COLUMN_LIMIT_HELP)
+    } else if command == DUPLICATE_FILES {
+        Some(DUPLICATE_FILES_HELP)
+    } else if command == DEDUPE_FILES {
+        Some(DEDUPE_FILES_HELP)
+    } else if command == DUPLICATES {
+        Some(DUPLICATES_HELP)
+    } else if command == ESTIMATION {
+        Some(ESTIMATION_HELP)
+    } else if command == COCOMO {
+        Some(COCOMO_HELP)
+    } else if command == SALARY {
+        Some(SALARY_HELP)
+    } else if command == TEST_PATTERNS {
+        Some(TEST_PATTERNS_HELP)
+    } else if command == OWNERS {
+        Some(OWNERS_HELP)
+    } else if command == AUTHORS {
+        Some(AUTHORS_HELP)
+    } else if command == MAILMAP {
+        Some(MAILMAP_HELP)
+    } else if command == HOTSPOTS {
+        Some(HOTSPOTS_HELP)
+    } else if command == CHURN_WINDOW {
+        Some(CHURN_WINDOW_HELP)
+    } else if command == REV {
+        Some(REV_HELP)
+    } else if command == BACKFILL {
+        Some(BACKFILL_HELP)
+    } else if command == DIFF {
+        Some(DIFF_HELP)
+    } else if command == CHANGED_SINCE {
+        Some(CHANGED_SINCE_HELP)
+    } else if command == STAGED {
+        Some(STAGED_HELP)
+    } else if command == FILES_FROM {
+        Some(FILES_FROM_HELP)
+    } else if command == RESPECT_IGNORES {
+        Some(RESPECT_IGNORES_HELP)
     } else if command == COLORS {
         Some(COLORS_HELP)
     } else if command == COLOR_PALETTE {
@@ -4,32 +4,29 @@ use crossbeam_deque::Steal;
 
 use crate::*;
 
+type DirStacks = (Option<Arc<GitignoreStack>>, Option<Arc<GitattributesStack>>);
 
-pub fn start_producer_thread(id: usize, files_injector: Arc<Injector<ParsableFile>>, dirs_injector: Arc<Injector<TraversedDir>>, worker: Worker<TraversedDir>,
-        languages_metadata_map: MetadataMapMut, idle_producers: Arc<AtomicUsize>, extension_lang_map: ExtensionLangMap, exclude_matcher: Arc<globset::GlobSet>,
-        config: Arc<Configuration>, files_stats: Arc<Mutex<FilesPresent>>)
+
+pub fn start_producer_thread(id: usize, dirs_injector: Arc<Injector<TraversedDir>>, worker: Worker<TraversedDir>, idle_producers: Arc<AtomicUsize>,
+        context: Arc<TraversalContext>, files_stats: Arc<Mutex<FilesPresent>>)
 -> JoinHandle<()>
 {
     thread::Builder::new().name(id.to_string()).spawn(move || {
-        let (total_files, relevant_files, excluded_files) =
-                search_for_files(id, files_injector, dirs_injector, worker, idle_producers, extension_lang_map, exclude_matcher, languages_metadata_map, config);
-        let mut file_stats_guard = files_stats.lock().unwrap(); 
-        file_stats_guard.total_files += total_files;
-        file_stats_guard.relevant_files += relevant_files;
-        file_stats_guard.excluded_files += excluded_files;
+        let files_present = search_for_files(id, dirs_injector, worker, idle_producers, &context);
+        files_stats.lock().unwrap().add(&files_present);
 
     }).unwrap()
 }
 
-pub fn search_for_files(_id: usize, files_injector: Arc<Injector<ParsableFile>>, dirs_injector: Arc<Injector<TraversedDir>>, worker: Worker<TraversedDir>, idle_producers: Arc<AtomicUsize>,
-        extension_lang_map: ExtensionLangMap, exclude_matcher: Arc<globset::GlobSet>, languages_metadata_map: MetadataMapMut, config: Arc<Configuration>)
--> (usize,usize,usize)
+pub fn search_for_files(_id: usize, dirs_injector: Arc<Injector<TraversedDir>>, worker: Worker<TraversedDir>, idle_producers: Arc<AtomicUsize>,
+        context: &TraversalContext)
+-> FilesPresent
 {
-    let mut total_files = 0;
-    let mut relevant_files = 0;
-    let mut excluded_files = 0;
+    let config = &context.config;
+    let mut files_present = FilesPresent::default();
     let mut should_terminate = false;
     let mut local_metadata: HashMap<String, LanguageMetadata> = HashMap::new();
+    let mut local_traversal_errors = Vec::new();
     // let mut times_slept = 0;
 
     loop {
@@ -48,20 +45,29 @@ pub fn search_for_files(_id: usize, files_injector: Arc<Injector<ParsableFile>>,
             }
         };
 
-        if let Some(dir) = &next_dir {
+        if let Some(mut dir) = next_dir {
            if should_terminate {
                 should_terminate = false;
                 idle_producers.fetch_sub(1, Ordering::SeqCst);
             }
 
-            if let Ok(entries) = fs::read_dir(&dir.path) {
-                let gitignore_stack = if config.no_gitignore {
-                    None
-                } else {
-                    GitignoreStack::extended(&dir.path, dir.gitignore_stack.clone())
-                };
-                traverse_dir(&files_injector, entries, &dirs_injector, &extension_lang_map, &exclude_matcher, &gitignore_stack,
-                        &config, &mut local_metadata, &mut total_files, &mut relevant_files, &mut excluded_files)
+            // With the links followed, a dir may be reached more than once, even through a loop
+            if config.follow_symlinks && !is_first_visit(&dir.path, fs::metadata(&dir.path).ok().as_ref(), &context.seen_file_ids) {
+                continue;
+            }
+
+            match fs::read_dir(&dir.path) {
+                Ok(entries) => {
+                    // From here on, the stacks of the dir include its own files too
+                    if !config.no_gitignore {
+                        dir.gitignore_stack = GitignoreStack::extended(&dir.path, dir.gitignore_stack.take());
+                    }
+                    if !config.no_gitattributes {
+                        dir.gitattributes_stack = GitattributesStack::extended(&dir.path, dir.gitattributes_stack.take());
+                    }
+                    traverse_dir(entries, &dirs_injector, &dir, context, &mut local_metadata, &mut files_present, &mut local_traversal_errors)
+                },
+                Err(x) => local_traversal_errors.push(TraversalErrorDetails::new(dir.path.clone(), &x))
             }
         } else {
             if !should_terminate {
@@ -80,73 +86,167 @@ pub fn search_for_files(_id: usize, files_injector: Arc<Injector<ParsableFile>>,
     // print_thread_colored_msg(id, format!("Thread {} |  Exits with findings: {:?}",id,(total_files,relevant_files)));
     // print_thread_colored_msg(id, format!("Thread {} |  Slept {} times. ",id,times_slept));
 
+    if !local_traversal_errors.is_empty() {
+        context.traversal_errors.lock().unwrap().append(&mut local_traversal_errors);
+    }
+
     if !local_metadata.is_empty() {
-        let mut global_metadata_guard = languages_metadata_map.lock().unwrap();
+        let mut global_metadata_guard = context.languages_metadata_map.lock().unwrap();
         for (lang_name, metadata) in local_metadata.iter() {
             global_metadata_guard.get_mut(lang_name).unwrap().add_metadata(metadata);
         }
     }
 
-    (total_files,relevant_files,excluded_files)
+    files_present
 }
 
-fn traverse_dir(files_injector: &Arc<Injector<ParsableFile>>, entries: ReadDir, dirs_injector: &Arc<Injector<TraversedDir>>,
-        extension_lang_map: &HashMap<String, Arc<str>>, exclude_matcher: &globset::GlobSet, gitignore_stack: &Option<Arc<GitignoreStack>>,
-        config: &Configuration, local_metadata: &mut HashMap<String, LanguageMetadata>,
-        total_files: &mut usize, relevant_files: &mut usize, excluded_files: &mut usize)
+// The stacks of the dir include its own ignore files and .gitattributes
+fn traverse_dir(entries: ReadDir, dirs_injector: &Arc<Injector<TraversedDir>>, dir: &TraversedDir, context: &TraversalContext,
+        local_metadata: &mut HashMap<String, LanguageMetadata>, files_present: &mut FilesPresent, traversal_errors: &mut Vec<TraversalErrorDetails>)
 {
-    let mut local_total_files = 0;
-    let mut local_relevant_files = 0;
-    let mut local_excluded_files = 0;
-    for e in entries.flatten(){
-        if let Ok(ft) = e.file_type() {
-            if ft.is_file() {
-                local_total_files += 1;
-                let path_buf = e.path();
-                let Some(extension_name) = path_buf.extension().and_then(|x| x.to_str()) else { continue };
-                if let Some(lang_name) = find_language_of_extension(extension_lang_map, extension_name) {
-                    if !exclude_matcher.is_empty() && exclude_matcher.is_match(&path_buf) {
-                        local_excluded_files += 1;
+    let TraversalContext {config, extension_lang_map, exclude_matcher, include_matcher, ..} = context;
+    let (gitignore_stack, gitattributes_stack) = (&dir.gitignore_stack, &dir.gitattributes_stack);
+    let mut local_files_present = FilesPresent::default();
+    // The files of the subdirs are two levels deeper than the dir
+    let may_descend = config.max_depth.is_none_or(|x| dir.depth + 2 <= x);
+    for entry in entries {
+        // The errors of the entries themselves have no paths
+        let e = match entry {
+            Ok(x) => x,
+            Err(x) => {
+                traversal_errors.push(TraversalErrorDetails::new(dir.path.clone(), &x));
+                continue;
+            }
+        };
+        match e.file_type() {
+            Err(x) => traversal_errors.push(TraversalErrorDetails::new(e.path(), &x)),
+            Ok(mut ft) => {
+                let is_symlink = ft.is_symlink();
+                if is_symlink {
+                    if !config.follow_symlinks {
                         continue;
                     }
-                    if let Some(stack) = gitignore_stack && stack.is_ignored(&path_buf, false) {
-                        local_excluded_files += 1;
-                        continue;
+                    // The broken links are skipped
+                    match fs::metadata(e.path()) {
+                        Ok(x) => ft = x.file_type(),
+                        Err(_) => continue
                     }
+                }
+
+                if ft.is_file() {
+                    local_files_present.total_files += 1;
+                    let path_buf = e.path();
+                    let attributes = gitattributes_stack.as_ref().map(|x| x.attributes_of(&path_buf)).unwrap_or_default();
+                    if let Some(lang_name) = find_language_of_file(extension_lang_map, &path_buf, &attributes) {
+                        // The metadata of the entry is the one of the link itself
+                        let metadata = if is_symlink {fs::metadata(&path_buf)} else {e.metadata()}.ok();
+                        let bytes = metadata.as_ref().map_or(0, |x| x.len() as usize);
+                        // The dirs above the file have been checked while descending
+                        if let Some(reason) = context.exclusion_reason(&path_buf, &dir.path, gitignore_stack.as_deref(), &attributes, bytes,
+                                metadata.as_ref().and_then(|x| x.modified().ok()))
+                        {
+                            local_files_present.exclude(reason);
+                            continue;
+                        }
+                        let may_be_seen = config.follow_symlinks || metadata.as_ref().is_some_and(FileId::has_hard_links);
+                        if may_be_seen && !is_first_visit(&path_buf, metadata.as_ref(), &context.seen_file_ids) {
+                            local_files_present.duplicate_files += 1;
+                            continue;
+                        }
 
-                    local_relevant_files += 1;
-                    let bytes = match e.metadata() {
-                        Ok(x) => x.len() as usize,
-                        Err(_) => 0
-                    };
+                        local_files_present.relevant_files += 1;
 
-                    match local_metadata.get_mut(lang_name.as_ref()) {
-                        Some(metadata) => metadata.add_file_meta(bytes),
-                        None => { local_metadata.insert(lang_name.as_ref().to_owned(), LanguageMetadata::new(1, bytes)); }
+                        match local_metadata.get_mut(lang_name.as_ref()) {
+                            Some(metadata) => metadata.add_file_meta(bytes),
+                            None => { local_metadata.insert(lang_name.as_ref().to_owned(), LanguageMetadata::new(1, bytes)); }
+                        }
+
+                        context.files_injector.push(ParsableFile::new(path_buf, lang_name));
                     }
+                } else { //is directory
+                    if !may_descend { continue; }
+                    let file_name = e.file_name();
+                    let Some(dir_name) = file_name.to_str() else { continue };
+                    if !config.should_search_in_dotted && dir_name.starts_with('.') { continue; }
 
-                    files_injector.push(ParsableFile::new(path_buf, lang_name));
-                }
-            } else { //is directory
-                let file_name = e.file_name();
-                let Some(dir_name) = file_name.to_str() else { continue };
-                if !config.should_search_in_dotted && dir_name.starts_with('.') { continue; }
-
-                let pathbuf = e.path();
-                if !exclude_matcher.is_empty() && exclude_matcher.is_match(&pathbuf) {
-                    continue;
-                }
-                if let Some(stack) = gitignore_stack && stack.is_ignored(&pathbuf, true) {
-                    continue;
+                    let pathbuf = e.path();
+                    if !exclude_matcher.is_empty() && exclude_matcher.is_match(&pathbuf) {
+                        continue;
+                    }
+                    if !include_matcher.may_include_below(&pathbuf) {
+                        continue;
+                    }
+                    if let Some(stack) = gitignore_stack && stack.is_ignored(&pathbuf, true) {
+                        continue;
+                    }
+                    dirs_injector.push(TraversedDir::new(pathbuf, gitignore_stack.clone(), gitattributes_stack.clone(), dir.depth + 1));
                 }
-                dirs_injector.push(TraversedDir::new(pathbuf, gitignore_stack.clone()));
             }
         }
     }
 
-    *total_files += local_total_files;
-    *relevant_files += local_relevant_files;
-    *excluded_files += local_excluded_files;
+    files_present.add(&local_files_present);
+}
+
+// Adds the files of a list to the metadata of their languages and to the injector, instead of searching for them.
+// The relative paths are relative to the working dir, and the ones that can't be found are reported like the
+// unreadable dirs of a traversal.
+pub fn add_listed_files(paths: Vec<PathBuf>, context: &TraversalContext, files_present: &mut FilesPresent) {
+    let TraversalContext {config, extension_lang_map, ..} = context;
+    let working_dir = std::env::current_dir().unwrap_or_default();
+    let absolute = |path: &Path| working_dir.join(path).components().filter(|x| *x != std::path::Component::CurDir).collect::<PathBuf>();
+    let target_dirs = config.dirs.iter().map(|x| absolute(Path::new(x))).collect::<Vec<_>>();
+    // The ignore files and the linguist attributes of the dirs of the listed files, only when they are respected
+    let mut dir_stacks: HashMap<PathBuf, DirStacks> = HashMap::new();
+    let mut languages_metadata_map = context.languages_metadata_map.lock().unwrap();
+    for path in paths {
+        let path = absolute(&path);
+        let metadata = match fs::metadata(&path) {
+            Ok(x) => x,
+            Err(x) => {
+                context.traversal_errors.lock().unwrap().push(TraversalErrorDetails::new(path, &x));
+                continue;
+            }
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        files_present.total_files += 1;
+
+        let (gitignore_stack, gitattributes_stack) = match path.parent() {
+            Some(dir) if config.respect_ignores => dir_stacks.entry(dir.to_path_buf()).or_insert_with(|| (
+                if config.no_gitignore {None} else {GitignoreStack::of_dir(dir)},
+                if config.no_gitattributes {None} else {GitattributesStack::extended(dir, GitattributesStack::for_root_dir(dir))}
+            )).clone(),
+            _ => (None, None)
+        };
+        let attributes = gitattributes_stack.map(|x| x.attributes_of(&path)).unwrap_or_default();
+        let Some(lang_name) = find_language_of_file(extension_lang_map, &path, &attributes) else { continue };
+        // Unlike in the traversal, the dirs of the file inside its target dir haven't been checked already
+        let parent = path.parent().unwrap_or(&path);
+        let target_dir = target_dirs.iter().find(|x| parent.starts_with(x)).map_or(parent, PathBuf::as_path);
+        let bytes = metadata.len() as usize;
+        if let Some(reason) = context.exclusion_reason(&path, target_dir, gitignore_stack.as_deref(), &attributes, bytes, metadata.modified().ok()) {
+            files_present.exclude(reason);
+            continue;
+        }
+        if !is_first_visit(&path, Some(&metadata), &context.seen_file_ids) {
+            files_present.duplicate_files += 1;
+            continue;
+        }
+
+        files_present.relevant_files += 1;
+        languages_metadata_map.get_mut(lang_name.as_ref()).unwrap().add_file_meta(bytes);
+        context.files_injector.push(ParsableFile::new(path, lang_name));
+    }
+}
+
+// The files and the dirs whose id can't be found are always visited
+fn is_first_visit(path: &Path, metadata: Option<&fs::Metadata>, seen_file_ids: &FileIdsSetMut) -> bool {
+    match metadata.and_then(|x| FileId::of(path, x)) {
+        Some(id) => seen_file_ids.lock().unwrap().insert(id),
+        None => true
+    }
 }
 
 #[cfg(debug_assertions)]
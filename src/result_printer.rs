@@ -1,6 +1,12 @@
 use std::cmp::max;
 
 use crate::*;
+use crate::code_owners::Ownership;
+use crate::authors::{AuthorStats, Authorship};
+use crate::hotspots::Hotspot;
+use crate::duplicated_code::DuplicatedCode;
+use crate::diff::LanguageDiff;
+use crate::config_manager::{DiffTarget, HotspotMetric};
 
 type ColorFunc = Box<dyn Fn(&str) -> String>;
 
@@ -10,6 +16,22 @@ const NUM_OF_VERTICALS : usize = 50;
 const KEYWORD_LINE_OFFSET : usize = 19;
 const STANDARD_LINE_STATS_LEN : usize = 33;
 const DASH_LINE_OFFSET : usize = 47;
+// the upper bounds (exclusive) of the lines of the files in every histogram bar, except from the last bar that has no bound
+const FILE_LINES_BUCKETS : [usize; 5] = [50, 100, 250, 500, 1000];
+// the number of vertical lines of the longest histogram bar in the distribution section
+const MAX_HISTOGRAM_VERTICALS : usize = 25;
+// the max number of files that are listed for every hygiene problem
+const MAX_LISTED_HYGIENE_FILES : usize = 10;
+// the max number of groups of identical files that are listed in the duplicate files section
+const MAX_LISTED_DUPLICATE_GROUPS : usize = 10;
+// the max number of duplicated blocks that are listed in the duplicated code section
+const MAX_LISTED_DUPLICATED_BLOCKS : usize = 10;
+// the max number of unowned files that are listed in the ownership section
+const MAX_LISTED_UNOWNED_FILES : usize = 10;
+// the max number of authors that are listed overall and for each language in the authors section
+const MAX_LISTED_AUTHORS : usize = 10;
+// the max number of files that are listed in the hotspots section
+const MAX_LISTED_HOTSPOTS : usize = 15;
 
 //log file keys
 const FILES         : &str  = "Files:";
@@ -19,19 +41,89 @@ const EXTRA         : &str  = "Extra:";
 const TOTAL_SIZE    : &str  = "Total Size:";
 const AVERAGE_SIZE  : &str  = "Average Size:";
 
+// The data of the sections that are printed after the overview, when they are enabled
+pub struct ExtraSections<'a> {
+    pub files_details: &'a mut [FileDetails],
+    pub duplicate_file_groups: &'a [DuplicateFileGroup],
+    pub duplicated_code: Option<&'a DuplicatedCode>,
+    pub ownership: Option<&'a Ownership>,
+    pub authorship: Option<&'a Authorship>,
+    pub hotspots: Option<&'a Result<Vec<Hotspot>,String>>
+}
+
 pub fn format_and_print_results(content_info_map: &mut HashMap<String, LanguageContentInfo>, languages_metadata_map: &mut HashMap<String, LanguageMetadata>,
-        final_stats: &FinalStats, existing_log_content: &Option<String>, datetime_now: &DateTime<Local>, config: &Configuration) 
+        extra_sections: ExtraSections, final_stats: &FinalStats, existing_log_content: &Option<String>, datetime_now: &DateTime<Local>, config: &Configuration)
 {
+    let ExtraSections {files_details, duplicate_file_groups, duplicated_code, ownership, authorship, hotspots} = extra_sections;
     let mut sorted_language_names = get_language_names_as_sorted_vec_according_to_how_much_they_appeared(languages_metadata_map);
     let biggest_prefix_standard_spaces = get_biggest_prefix_standard_spaces(&sorted_language_names, languages_metadata_map);
 
     print_individually(&sorted_language_names, content_info_map, languages_metadata_map, biggest_prefix_standard_spaces, !config.no_keywords);
 
+    // Kept before the overview, that merges the less relevant languages into "others"
+    let languages_hygiene = if config.hygiene {
+        sorted_language_names.iter().map(|x| (x.clone(), content_info_map.get(x).unwrap().hygiene.clone())).collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    let languages_file_lines = if config.distribution {
+        sorted_language_names.iter().map(|x| {
+            let content_info = content_info_map.get(x).unwrap();
+            let mut file_lines = content_info.file_lines.clone();
+            file_lines.sort_unstable();
+            (x.clone(), file_lines, content_info.long_lines)
+        }).collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+    let languages_code_lines = if duplicated_code.is_some() {
+        sorted_language_names.iter().map(|x| (x.clone(), content_info_map.get(x).unwrap().code_lines)).collect::<Vec<_>>()
+    } else {
+        Vec::new()
+    };
+
+    if languages_metadata_map.len() > 1 {
+        print_sum(content_info_map, languages_metadata_map, final_stats, biggest_prefix_standard_spaces, !config.no_keywords);
+    }
+
     if languages_metadata_map.len() > 1 {
-        print_sum(content_info_map, final_stats, biggest_prefix_standard_spaces, !config.no_keywords);
         print_visual_overview(&mut sorted_language_names, content_info_map, languages_metadata_map, final_stats, config);
     }
 
+    if config.per_file > 0 {
+        print_per_file(files_details, config);
+    }
+
+    if config.hygiene {
+        print_hygiene(&languages_hygiene, files_details, config);
+    }
+
+    if config.distribution {
+        print_distribution(&languages_file_lines, config);
+    }
+
+    if config.duplicate_files || config.dedupe_files {
+        print_duplicate_files(duplicate_file_groups, config);
+    }
+
+    if let Some(duplicated_code) = duplicated_code {
+        print_duplicated_code(&languages_code_lines, duplicated_code, config);
+    }
+
+    if config.estimation {
+        print_estimation(final_stats, config);
+    }
+
+    if let Some(ownership) = ownership {
+        print_ownership(ownership, config);
+    }
+    if let Some(authorship) = authorship {
+        print_authors(authorship);
+    }
+    if let Some(hotspots) = hotspots {
+        print_hotspots(hotspots, config);
+    }
+
     if let Some(content) = existing_log_content && config.compare_level != 0 {
         print_comparison_to_previous_runs(final_stats, content,  config.compare_level, datetime_now);
     }
@@ -50,22 +142,21 @@ fn print_individually(sorted_languages: &[String], content_info_map: &HashMap<St
     }
 
     fn reconstruct_line(i: usize, max_line_stats_len: usize, titles_vec: &[String], lines_stats_vec: &[String],
-         lines_stats_len_vec: &[usize], size_stats_vec: &[String], keywords_stats_vec: &[String]) -> String
+         lines_stats_len_vec: &[usize], size_stats_vec: &[String], detail_lines_vec: &[Vec<String>]) -> String
     {
         let spaces = max_line_stats_len+1 - lines_stats_len_vec[i];
         let mut line = titles_vec[i].clone() + &lines_stats_vec[i] + &" ".repeat(spaces) + " |  " + &size_stats_vec[i];
-        //if run with --no-keywords
-        if !keywords_stats_vec.is_empty(){
-            line = line + "\n" + &keywords_stats_vec[i];
-        } 
+        for detail_line in &detail_lines_vec[i] {
+            line = line + "\n" + detail_line;
+        }
         line
     }
 
     println!("{}.\n", "Details".underline().bold());
     
     let mut max_line_stats_len = STANDARD_LINE_STATS_LEN;
-    let (mut titles_vec, mut lines_stats_vec, mut lines_stats_len_vec, mut size_stats_vec,
-            mut keywords_stats_vec) = (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let (mut titles_vec, mut lines_stats_vec, mut lines_stats_len_vec, mut size_stats_vec, mut detail_lines_vec)
+            = (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
 
     for lang_name in sorted_languages {
         let content_info = content_info_map.get(lang_name).unwrap();
@@ -91,15 +182,27 @@ fn print_individually(sorted_languages: &[String], content_info_map: &HashMap<St
         lines_stats_vec.push(format!("{} {} {{{} code ({:.2}%) + {} extra}}", colored_word("lines"), lines_str, code_lines_str,
                  code_lines_percentage, extra_lines_str));
         size_stats_vec.push(get_size_text(metadata));
-        
+
+        // The lines under the size stats, of which only the keywords line is kept even when empty
+        let mut detail_lines = [
+            get_functions_as_str(content_info.functions, content_info.average_code_lines_per_function(), biggest_prefix_standard_spaces),
+            get_complexity_as_str(content_info.complexity, metadata.files, biggest_prefix_standard_spaces),
+            get_nesting_as_str(&content_info.depth_histogram, biggest_prefix_standard_spaces),
+            get_tests_as_str(content_info.test_code_lines, content_info.code_lines, biggest_prefix_standard_spaces)
+        ].into_iter().filter(|x| !x.is_empty()).collect::<Vec<_>>();
         if should_print_keywords {
-            keywords_stats_vec.push(get_keywords_as_str(&content_info.keyword_occurences, biggest_prefix_standard_spaces));
+            detail_lines.push(get_keywords_as_str(&content_info.keyword_occurences, biggest_prefix_standard_spaces));
+            let comment_keywords_line = get_comment_keywords_as_str(&content_info.comment_keyword_occurences, biggest_prefix_standard_spaces);
+            if !comment_keywords_line.is_empty() {
+                detail_lines.push(comment_keywords_line);
+            }
         }
+        detail_lines_vec.push(detail_lines);
     }
 
     for i in 0..lines_stats_vec.len() {
         let line = reconstruct_line(i, max_line_stats_len, &titles_vec, &lines_stats_vec,
-                &lines_stats_len_vec, &size_stats_vec, &keywords_stats_vec);
+                &lines_stats_len_vec, &size_stats_vec, &detail_lines_vec);
                 
         if i == lines_stats_len_vec.len() - 1 {
             println!("{line}");
@@ -110,14 +213,39 @@ fn print_individually(sorted_languages: &[String], content_info_map: &HashMap<St
 }
 
 
-fn print_sum(content_info_map: &HashMap<String,LanguageContentInfo>, final_stats: &FinalStats, biggest_prefix_standard_spaces: usize,
-        should_print_keywords: bool) 
+fn print_sum(content_info_map: &HashMap<String,LanguageContentInfo>, languages_metadata_map: &HashMap<String, LanguageMetadata>,
+        final_stats: &FinalStats, biggest_prefix_standard_spaces: usize, should_print_keywords: bool) 
 {
     let (total_files_str, total_lines_str, total_code_lines_str, total_extra_lines_str) = 
             (with_seperators(final_stats.files),with_seperators(final_stats.lines),with_seperators(final_stats.code_lines), with_seperators(final_stats.extra_lines)); 
 
+    // Only the languages with functions are taken into account for the average
+    let (mut total_functions, mut code_lines_with_functions) = (0, 0);
+    content_info_map.values().filter(|x| x.functions > 0).for_each(|x| {total_functions += x.functions; code_lines_with_functions += x.code_lines});
+    let average_code_lines_per_function = if total_functions > 0 {Some(code_lines_with_functions as f64 / total_functions as f64)} else {None};
+    let functions_line = get_functions_as_str(total_functions, average_code_lines_per_function, biggest_prefix_standard_spaces);
+
+    // Same for the languages with branch tokens, for the average complexity
+    let (mut total_complexity, mut files_with_complexity) = (0, 0);
+    content_info_map.iter().filter(|(_,x)| x.complexity > 0).for_each(|(name,x)| {
+        total_complexity += x.complexity;
+        files_with_complexity += languages_metadata_map.get(name).map_or(0, |x| x.files);
+    });
+    let complexity_line = get_complexity_as_str(total_complexity, files_with_complexity, biggest_prefix_standard_spaces);
+
+    let mut total_depth_histogram = Vec::new();
+    content_info_map.values().for_each(|x| domain::merge_depth_histograms(&mut total_depth_histogram, &x.depth_histogram));
+    let nesting_line = get_nesting_as_str(&total_depth_histogram, biggest_prefix_standard_spaces);
+
+    let total_test_code_lines = content_info_map.values().map(|x| x.test_code_lines).sum();
+    let tests_line = get_tests_as_str(total_test_code_lines, final_stats.code_lines, biggest_prefix_standard_spaces);
+
     let keywords_sum_map = create_keyword_sum_map(content_info_map);
-    let keywords_line = get_keywords_as_str(&keywords_sum_map, biggest_prefix_standard_spaces);
+    let mut keywords_line = get_keywords_as_str(&keywords_sum_map, biggest_prefix_standard_spaces);
+    let comment_keywords_line = get_comment_keywords_as_str(&create_comment_keyword_sum_map(content_info_map), biggest_prefix_standard_spaces);
+    if !comment_keywords_line.is_empty() {
+        keywords_line = keywords_line + "\n" + &comment_keywords_line;
+    }
 
     let spaces = biggest_prefix_standard_spaces - (5 + total_files_str.len());
     let title = format!("{}   {}{} {}  -> ","Total".bold()," ".repeat(spaces),total_files_str,colored_word("files"));
@@ -129,8 +257,20 @@ fn print_sum(content_info_map: &HashMap<String,LanguageContentInfo>, final_stats
             final_stats.size.to_string().len() + final_stats.average_size.to_string().len() + DASH_LINE_OFFSET;
     println!("{} ","-".repeat(line_len));
 
-    let info = format!("{} {} {{{} code ({:.2}%) + {} extra}}  |  {}\n",colored_word("lines"), total_lines_str,total_code_lines_str,
+    let mut info = format!("{} {} {{{} code ({:.2}%) + {} extra}}  |  {}\n",colored_word("lines"), total_lines_str,total_code_lines_str,
             code_lines_percentage, total_extra_lines_str, size_text);
+    if !functions_line.is_empty() {
+        info = info + &functions_line + "\n";
+    }
+    if !complexity_line.is_empty() {
+        info = info + &complexity_line + "\n";
+    }
+    if !nesting_line.is_empty() {
+        info = info + &nesting_line + "\n";
+    }
+    if !tests_line.is_empty() {
+        info = info + &tests_line + "\n";
+    }
 
     if should_print_keywords {
         println!("{title}{info}{keywords_line}\n");
@@ -210,18 +350,419 @@ fn print_comparison_to_previous_runs(final_stats: &FinalStats, log_content: &str
         ));
     }
     print!("{comparison_str}");
+}
 
-    fn color_percentage(percentage: &str) -> ColoredString {
-        if percentage.starts_with('+') {
-            percentage.truecolor(201, 255, 189)
-        } else if percentage.starts_with('-') {
-            percentage.truecolor(219, 129, 129)
-        } else {
-            percentage.truecolor(255, 255, 255)
+pub fn print_diff(language_diffs: &[LanguageDiff], old: &DiffTarget, new: &DiffTarget) {
+    println!("\n{}.\n", "Diff".underline().bold());
+    println!("\"{}\" -> \"{}\"\n", diff_target_label(old), diff_target_label(new));
+
+    let mut diff_str = String::with_capacity(200);
+    let mut totals = [0; 9];
+    for language_diff in language_diffs {
+        let values = [language_diff.old_metadata.files, language_diff.new_metadata.files, language_diff.old_content_info.lines,
+                language_diff.new_content_info.lines, language_diff.old_content_info.code_lines, language_diff.new_content_info.code_lines,
+                language_diff.added_files, language_diff.removed_files, language_diff.changed_files];
+        totals.iter_mut().zip(values).for_each(|(total, x)| *total += x);
+        if language_diff.has_changes() {
+            diff_str.push_str(&format!("{} {}\n", "->".bold(), language_diff.language_name));
+            diff_str.push_str(&get_diff_as_str(&values));
+        }
+    }
+
+    if diff_str.is_empty() {
+        println!("No differences were found.");
+        return;
+    }
+    let unchanged_languages = language_diffs.iter().filter(|x| !x.has_changes()).count();
+    print!("{diff_str}");
+    print!("{} {}\n{}", "->".bold(), "Total".bold(), get_diff_as_str(&totals));
+    if unchanged_languages > 0 {
+        println!("{unchanged_languages} more language(s) without changes.");
+    }
+
+    fn diff_target_label(target: &DiffTarget) -> &str {
+        match target {
+            DiffTarget::Dir(x) | DiffTarget::Rev(x) => x
+        }
+    }
+
+    // [old files, new files, old lines, new lines, old code lines, new code lines, added, removed, changed]
+    fn get_diff_as_str(values: &[usize; 9]) -> String {
+        let [old_files, new_files, old_lines, new_lines, old_code_lines, new_code_lines, added, removed, changed] = *values;
+        let (old_extra_lines, new_extra_lines) = (old_lines - old_code_lines, new_lines - new_code_lines);
+        format!("     Files: {}({}) Lines: {}({}) {{Code: {}({}), Extra: {}({})}}\n     \
+                Added: {}, Removed: {}, Changed: {} files | Lines: {} {{Code: {}, Extra: {}}}\n\n",
+                with_seperators(new_files), color_percentage(&difference_as_signed_percentage_or_new(old_files, new_files)),
+                with_seperators(new_lines), color_percentage(&difference_as_signed_percentage_or_new(old_lines, new_lines)),
+                with_seperators(new_code_lines), color_percentage(&difference_as_signed_percentage_or_new(old_code_lines, new_code_lines)),
+                with_seperators(new_extra_lines), color_percentage(&difference_as_signed_percentage_or_new(old_extra_lines, new_extra_lines)),
+                with_seperators(added), with_seperators(removed), with_seperators(changed),
+                color_percentage(&signed_difference_str(old_lines, new_lines)), color_percentage(&signed_difference_str(old_code_lines, new_code_lines)),
+                color_percentage(&signed_difference_str(old_extra_lines, new_extra_lines)))
+    }
+}
+
+fn color_percentage(percentage: &str) -> ColoredString {
+    if percentage.starts_with('+') {
+        percentage.truecolor(201, 255, 189)
+    } else if percentage.starts_with('-') {
+        percentage.truecolor(219, 129, 129)
+    } else {
+        percentage.truecolor(255, 255, 255)
+    }
+}
+
+
+fn print_per_file(files_details: &mut [FileDetails], config: &Configuration) {
+    println!("\n{}.\n", "Per file".underline().bold());
+
+    if config.no_keywords {
+        println!("Comment markers are not counted when run with '--{}'.", config_manager::NO_KEYWORDS);
+    } else {
+        print_top_files(files_details, "Most comment markers:", "No comment markers found.", |x| x.comment_keywords, None, config);
+    }
+    println!();
+    print_top_files(files_details, "Most complex:", "No branch tokens found.", |x| x.complexity, None, config);
+    println!();
+    print_top_files(files_details, "Deepest nesting:", "No nested code found.", |x| x.max_depth,
+            Some(|x| format!("average {:.1}", x.average_depth)), config);
+}
+
+fn print_top_files(files_details: &mut [FileDetails], title: &str, empty_msg: &str, value_of: fn(&FileDetails) -> usize,
+        extra_info_of: Option<fn(&FileDetails) -> String>, config: &Configuration)
+{
+    files_details.sort_by(|a,b| value_of(b).cmp(&value_of(a)).then_with(|| a.path.cmp(&b.path)));
+    let top_files = files_details.iter().take(config.per_file).take_while(|x| value_of(x) > 0).collect::<Vec<_>>();
+    if top_files.is_empty() {
+        println!("{empty_msg}");
+        return;
+    }
+
+    println!("{}", colored_word(title));
+    let max_num_len = with_seperators(value_of(top_files[0])).len();
+    for details in top_files {
+        let num_str = with_seperators(value_of(details));
+        let extra_info = extra_info_of.map_or(String::new(), |f| String::from(", ") + &f(details));
+        println!("  {}{}  {}  ({}{})", " ".repeat(max_num_len - num_str.len()), num_str,
+                get_path_relative_to_target_dir(&details.path, &config.dirs), details.language_name, extra_info);
+    }
+}
+
+// The sorted lines of every file and the long lines of every language
+fn print_distribution(languages_file_lines: &[(String, Vec<usize>, usize)], config: &Configuration) {
+    println!("\n{}.\n", "Distribution".underline().bold());
+
+    let max_name_len = languages_file_lines.iter().map(|(name,_,_)| name.chars().count()).max().unwrap_or(0);
+    let offset = " ".repeat(max_name_len + 6);
+    let bar_color = config.colors.first().copied().unwrap_or(Color::Cyan);
+    for (lang_name, file_lines, long_lines) in languages_file_lines {
+        if file_lines.is_empty() {
+            continue;
+        }
+
+        println!("{}{}  -> {}: median {} , p90 {} , p99 {} , max {}", lang_name.bold(), " ".repeat(max_name_len - lang_name.chars().count()),
+                colored_word("lines per file"), with_seperators(get_percentile(file_lines, 50)), with_seperators(get_percentile(file_lines, 90)),
+                with_seperators(get_percentile(file_lines, 99)), with_seperators(file_lines[file_lines.len()-1]));
+        println!("{offset}{}: {}", colored_word(&format!("lines longer than {} columns", config.column_limit)),
+                with_seperators(*long_lines));
+
+        let buckets = get_file_lines_buckets(file_lines);
+        if config.no_visual {
+            println!("{offset}{} -> {}\n", "files".italic(), buckets.iter().map(|(label, files)| format!("{label}: {}", with_seperators(*files)))
+                    .collect::<Vec<_>>().join(" , "));
+            continue;
+        }
+
+        let max_files = buckets.iter().map(|x| x.1).max().unwrap_or(0);
+        let max_label_len = buckets.iter().map(|x| x.0.len()).max().unwrap_or(0);
+        for (label, files) in &buckets {
+            let verticals = if *files == 0 {0} else {((*files * MAX_HISTOGRAM_VERTICALS) as f64 / max_files as f64).round().max(1.0) as usize};
+            println!("{offset}{}{label}  [-{}{}-]  {}", " ".repeat(max_label_len - label.len()), "|".repeat(verticals).color(bar_color),
+                    " ".repeat(MAX_HISTOGRAM_VERTICALS - verticals), with_seperators(*files));
+        }
+        println!();
+    }
+}
+
+// The nearest-rank percentile, the values must be sorted and not empty
+fn get_percentile(sorted_values: &[usize], percentile: usize) -> usize {
+    let rank = (percentile * sorted_values.len()).div_ceil(100);
+    sorted_values[rank.max(1) - 1]
+}
+
+fn get_file_lines_buckets(file_lines: &[usize]) -> Vec<(String,usize)> {
+    let mut buckets = Vec::with_capacity(FILE_LINES_BUCKETS.len() + 1);
+    let mut lower_bound = 0;
+    for upper_bound in FILE_LINES_BUCKETS {
+        let files = file_lines.iter().filter(|x| **x >= lower_bound && **x < upper_bound).count();
+        buckets.push((format!("{lower_bound}-{}", upper_bound - 1), files));
+        lower_bound = upper_bound;
+    }
+    buckets.push((format!("{lower_bound}+"), file_lines.iter().filter(|x| **x >= lower_bound).count()));
+    buckets
+}
+
+fn print_hygiene(languages_hygiene: &[(String, LanguageHygiene)], files_details: &mut [FileDetails], config: &Configuration) {
+    println!("\n{}.\n", "Hygiene".underline().bold());
+
+    let max_name_len = languages_hygiene.iter().map(|(name,_)| name.chars().count()).max().unwrap_or(0);
+    let offset = " ".repeat(max_name_len + 6);
+    for (name, hygiene) in languages_hygiene {
+        println!("{}{}  -> {}: LF {} , CRLF {} , mixed {}", name.bold(), " ".repeat(max_name_len - name.chars().count()),
+                colored_word("line endings"), with_seperators(hygiene.lf_files), with_seperators(hygiene.crlf_files),
+                with_seperators(hygiene.mixed_line_ending_files));
+        println!("{offset}{}: spaces {} , tabs {} , mixed {}", colored_word("indentation"), with_seperators(hygiene.space_indented_files),
+                with_seperators(hygiene.tab_indented_files), with_seperators(hygiene.mixed_indentation_files));
+        println!("{offset}{}: {} , {}: {}\n", colored_word("trailing whitespace"), with_seperators(hygiene.trailing_whitespace_files),
+                colored_word("no final newline"), with_seperators(hygiene.no_final_newline_files));
+    }
+
+    let usual_conventions = languages_hygiene.iter()
+            .map(|(name, hygiene)| (name.as_str(), (hygiene.usual_line_ending(), hygiene.usual_indentation())))
+            .collect::<HashMap<_,_>>();
+    let (mut mixed_line_endings, mut unusual_line_endings, mut mixed_indentation, mut unusual_indentation,
+            mut trailing_whitespace, mut no_final_newline) = (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+
+    files_details.sort_by(|a,b| a.path.cmp(&b.path));
+    for details in files_details.iter() {
+        let Some(hygiene) = &details.hygiene else { continue };
+        let Some((usual_line_ending, usual_indentation)) = usual_conventions.get(details.language_name.as_ref()) else { continue };
+        let path = get_path_relative_to_target_dir(&details.path, &config.dirs);
+
+        match hygiene.line_ending() {
+            LineEnding::Mixed => mixed_line_endings.push(format!("{path}  ({} LF, {} CRLF lines)", hygiene.lf_lines, hygiene.crlf_lines)),
+            LineEnding::Unknown => (),
+            x if x != *usual_line_ending => unusual_line_endings.push(format!("{path}  ({x:?}, most {} files use {usual_line_ending:?})",
+                    details.language_name)),
+            _ => ()
+        }
+        match hygiene.indentation() {
+            Indentation::Mixed => mixed_indentation.push(format!("{path}  ({} space, {} tab indented lines)",
+                    hygiene.space_indented_lines, hygiene.tab_indented_lines)),
+            Indentation::Unknown => (),
+            x if x != *usual_indentation => unusual_indentation.push(format!("{path}  ({x:?}, most {} files use {usual_indentation:?})",
+                    details.language_name)),
+            _ => ()
+        }
+        if hygiene.trailing_whitespace_lines > 0 {
+            trailing_whitespace.push(format!("{path}  ({} of {} lines)", hygiene.trailing_whitespace_lines, details.lines));
+        }
+        if !hygiene.has_final_newline {
+            no_final_newline.push(path);
+        }
+    }
+
+    let offending_files = [("Mixed line endings:", mixed_line_endings), ("Unusual line endings:", unusual_line_endings),
+            ("Mixed indentation:", mixed_indentation), ("Unusual indentation:", unusual_indentation),
+            ("Trailing whitespace:", trailing_whitespace), ("No final newline:", no_final_newline)];
+    if offending_files.iter().all(|(_, files)| files.is_empty()) {
+        println!("No offending files found.");
+        return;
+    }
+
+    for (title, files) in offending_files.iter().filter(|(_, files)| !files.is_empty()) {
+        println!("{}", colored_word(title));
+        for file in files.iter().take(MAX_LISTED_HYGIENE_FILES) {
+            println!("  {file}");
+        }
+        if files.len() > MAX_LISTED_HYGIENE_FILES {
+            println!("  ... and {} more", with_seperators(files.len() - MAX_LISTED_HYGIENE_FILES));
         }
-    }   
+    }
+}
+
+fn print_duplicate_files(duplicate_file_groups: &[DuplicateFileGroup], config: &Configuration) {
+    println!("\n{}.\n", "Duplicate files".underline().bold());
+
+    if duplicate_file_groups.is_empty() {
+        println!("No duplicate files found.");
+        return;
+    }
+
+    let extra_copies = duplicate_file_groups.iter().map(|x| x.paths.len() - 1).sum::<usize>();
+    let (wasted_size, wasted_size_desc) = get_size_and_formatted_size_text(
+            duplicate_file_groups.iter().map(|x| x.wasted_bytes()).sum(), "wasted");
+    println!("{}: {} , {}: {} , {} {}", colored_word("groups of identical files"), with_seperators(duplicate_file_groups.len()),
+            colored_word("extra copies"), with_seperators(extra_copies), round_1(wasted_size), wasted_size_desc);
+    if config.dedupe_files {
+        println!("The extra copies are not counted in the stats.");
+    }
+
+    for group in duplicate_file_groups.iter().take(MAX_LISTED_DUPLICATE_GROUPS) {
+        let (wasted_size, wasted_size_desc) = get_size_and_formatted_size_text(group.wasted_bytes(), "wasted");
+        println!("\n{}  -> {} copies , {} {}", group.language_name.bold(), group.paths.len(), round_1(wasted_size), wasted_size_desc);
+        for path in &group.paths {
+            println!("  {}", get_path_relative_to_target_dir(path, &config.dirs));
+        }
+    }
+    if duplicate_file_groups.len() > MAX_LISTED_DUPLICATE_GROUPS {
+        println!("\n... and {} more groups", with_seperators(duplicate_file_groups.len() - MAX_LISTED_DUPLICATE_GROUPS));
+    }
+}
+
+fn print_duplicated_code(languages_code_lines: &[(String, usize)], duplicated_code: &DuplicatedCode, config: &Configuration) {
+    println!("\n{}.\n", "Duplicated code".underline().bold());
+
+    let max_name_len = languages_code_lines.iter().map(|(name,_)| name.chars().count()).max().unwrap_or(0);
+    for (name, code_lines) in languages_code_lines {
+        let duplicated_lines = duplicated_code.duplicated_lines.get(name).copied().unwrap_or(0);
+        let percentage = if *code_lines == 0 {0.0} else {duplicated_lines as f64 / *code_lines as f64 * 100.0};
+        println!("{}{}  -> {}: {} of {} ({}%)", name.bold(), " ".repeat(max_name_len - name.chars().count()),
+                colored_word("duplicated code lines"), with_seperators(duplicated_lines), with_seperators(*code_lines), round_2(percentage));
+    }
+
+    if duplicated_code.blocks.is_empty() {
+        println!("\nNo blocks of {} or more duplicated code lines found.", config.duplicates);
+        return;
+    }
+
+    println!("\n{}", colored_word("Biggest duplicated blocks:"));
+    for block in duplicated_code.blocks.iter().take(MAX_LISTED_DUPLICATED_BLOCKS) {
+        println!("  {} code lines , {} copies", block.code_lines, block.locations.len());
+        for location in &block.locations {
+            println!("    {}:{}-{}", get_path_relative_to_target_dir(&location.path, &config.dirs), location.start_line, location.end_line);
+        }
+    }
+    if duplicated_code.blocks.len() > MAX_LISTED_DUPLICATED_BLOCKS {
+        println!("  ... and {} more", with_seperators(duplicated_code.blocks.len() - MAX_LISTED_DUPLICATED_BLOCKS));
+    }
+}
+
+fn print_estimation(final_stats: &FinalStats, config: &Configuration) {
+    println!("\n{}.\n", "Estimation".underline().bold());
+
+    let estimation = Estimation::calculate(final_stats.code_lines, &config.cocomo, config.salary);
+    println!("{}: {} {}", colored_word("Effort"), round_2(estimation.effort), colored_word("person-months"));
+    println!("{}: {} {}", colored_word("Schedule"), round_2(estimation.schedule), colored_word("months"));
+    println!("{}: {}", colored_word("People"), round_2(estimation.people));
+    println!("{}: {}", colored_word("Cost"), with_seperators(estimation.cost.round() as usize));
+    println!("\n(basic COCOMO on {} code lines, with a = {} , b = {} , c = {} , d = {} , an annual salary of {} and an overhead of {})",
+            with_seperators(final_stats.code_lines), config.cocomo.a, config.cocomo.b, config.cocomo.c, config.cocomo.d,
+            with_seperators(config.salary), ESTIMATION_OVERHEAD);
+}
+
+fn print_ownership(ownership: &Ownership, config: &Configuration) {
+    println!("\n{}.\n", "Ownership".underline().bold());
+
+    if ownership.codeowners_paths.is_empty() {
+        println!("No CODEOWNERS file found in the target dirs.");
+        return;
+    }
+
+    for path in &ownership.codeowners_paths {
+        println!("{}: {}", colored_word("owners from"), path.to_string_lossy().replace('\\', "/"));
+    }
+    println!();
+
+    // A file with many owners counts for each one of them, so the percentages may add up to more than 100
+    let mut owners = ownership.owners.iter().collect::<Vec<_>>();
+    owners.sort_by(|a,b| b.1.code_lines.cmp(&a.1.code_lines).then_with(|| a.0.cmp(b.0)));
+    let max_name_len = owners.iter().map(|x| x.0.chars().count()).max().unwrap_or(0).max("unowned".len());
+    let print_row = |name: &str, stats: &code_owners::OwnerStats| {
+        let percentage = if ownership.total_code_lines == 0 {0.0} else {stats.code_lines as f64 / ownership.total_code_lines as f64 * 100.0};
+        println!("{}{}  -> {}: {} , {}: {} , {}: {} ({}%)", name.bold(), " ".repeat(max_name_len - name.chars().count()),
+                colored_word("files"), with_seperators(stats.files), colored_word("lines"), with_seperators(stats.lines),
+                colored_word("code lines"), with_seperators(stats.code_lines), round_2(percentage));
+    };
+    for (name, stats) in &owners {
+        print_row(name, stats);
+    }
+    print_row("unowned", &ownership.unowned);
+
+    if ownership.unowned_files.is_empty() {
+        println!("\nEvery file has an owner.");
+        return;
+    }
+
+    println!("\n{}", colored_word("Biggest unowned files:"));
+    for (path, code_lines) in ownership.unowned_files.iter().take(MAX_LISTED_UNOWNED_FILES) {
+        println!("  {}  -> {} code lines", get_path_relative_to_target_dir(path, &config.dirs), with_seperators(*code_lines));
+    }
+    if ownership.unowned_files.len() > MAX_LISTED_UNOWNED_FILES {
+        println!("  ... and {} more", with_seperators(ownership.unowned_files.len() - MAX_LISTED_UNOWNED_FILES));
+    }
+}
+
+fn print_authors(authorship: &Authorship) {
+    println!("\n{}.\n", "Authors".underline().bold());
+
+    if authorship.authors.is_empty() {
+        println!("No file could be blamed, since none of them is tracked by git.");
+        return;
+    }
+
+    let max_name_len = authorship.authors.iter().take(MAX_LISTED_AUTHORS).chain(authorship.languages.iter()
+            .flat_map(|x| x.1.iter().take(MAX_LISTED_AUTHORS))).map(|x| x.0.chars().count()).max().unwrap_or(0);
+    let print_authors_of = |authors: &[(String,AuthorStats)], total_code_lines: usize| {
+        for (name, stats) in authors.iter().take(MAX_LISTED_AUTHORS) {
+            let percentage = if total_code_lines == 0 {0.0} else {stats.code_lines as f64 / total_code_lines as f64 * 100.0};
+            println!("  {}{}  -> {}: {} ({}%) , {}: {}", name.bold(), " ".repeat(max_name_len - name.chars().count()),
+                    colored_word("code lines"), with_seperators(stats.code_lines), round_2(percentage),
+                    colored_word("extra lines"), with_seperators(stats.extra_lines));
+        }
+        if authors.len() > MAX_LISTED_AUTHORS {
+            println!("  ... and {} more", with_seperators(authors.len() - MAX_LISTED_AUTHORS));
+        }
+    };
+
+    println!("{}", colored_word("Overall:"));
+    print_authors_of(&authorship.authors, authorship.authors.iter().map(|x| x.1.code_lines).sum());
+    for (language_name, authors) in &authorship.languages {
+        println!("\n{}", colored_word(&format!("{language_name}:")));
+        print_authors_of(authors, authors.iter().map(|x| x.1.code_lines).sum());
+    }
+
+    if authorship.unblamed_files > 0 {
+        println!("\n{} files could not be blamed, like the untracked ones, so their lines are not attributed.", with_seperators(authorship.unblamed_files));
+    }
+}
+
+fn print_hotspots(hotspots: &Result<Vec<Hotspot>,String>, config: &Configuration) {
+    println!("\n{}.\n", "Hotspots".underline().bold());
+
+    let hotspots = match hotspots {
+        Ok(x) => x,
+        Err(x) => {
+            println!("The history of the files could not be read from git: {x}");
+            return;
+        }
+    };
+    let metric_title = match config.hotspots {
+        Some(HotspotMetric::Complexity) => "complexity",
+        _ => "code lines"
+    };
+    println!("(the changes of the last {} days, ranked by commits x {})\n", with_seperators(config.churn_window), metric_title);
+    if hotspots.is_empty() {
+        println!("No file changed in this period.");
+        return;
+    }
+
+    let titles = ["score", "commits", "added", "removed", metric_title];
+    let rows = hotspots.iter().take(MAX_LISTED_HOTSPOTS).map(|x| [x.score, x.churn.commits, x.churn.added_lines, x.churn.removed_lines,
+            x.metric_value].map(with_seperators)).collect::<Vec<_>>();
+    let widths = titles.map(|x| x.len()).into_iter().enumerate().map(|(i, title_len)| rows.iter().map(|x| x[i].len()).max().unwrap_or(0).max(title_len))
+            .collect::<Vec<_>>();
+    let header = titles.iter().zip(&widths).map(|(title, width)| format!("{}{}", " ".repeat(width - title.len()), title)).collect::<Vec<_>>();
+    println!("  {}  {}", colored_word(&header.join("  ")), colored_word("file"));
+    for (row, hotspot) in rows.iter().zip(hotspots) {
+        let cells = row.iter().zip(&widths).map(|(cell, width)| format!("{}{}", " ".repeat(width - cell.len()), cell)).collect::<Vec<_>>();
+        println!("  {}  {}  ({})", cells.join("  "), get_path_relative_to_target_dir(&hotspot.path, &config.dirs), hotspot.language_name);
+    }
+    if hotspots.len() > MAX_LISTED_HOTSPOTS {
+        println!("  ... and {} more", with_seperators(hotspots.len() - MAX_LISTED_HOTSPOTS));
+    }
 }
 
+// Files that were given directly as targets are shown with their name only
+fn get_path_relative_to_target_dir(path: &Path, target_dirs: &[String]) -> String {
+    for dir in target_dirs {
+        if let Ok(relative) = path.strip_prefix(dir) && !relative.as_os_str().is_empty() {
+            return relative.to_string_lossy().replace('\\', "/");
+        }
+    }
+
+    path.file_name().map_or(path.to_string_lossy(), |x| x.to_string_lossy()).into_owned()
+}
 
 fn split_minutes_to_D_H_M(mut minutes: i64) -> (i64, i64, i64) {
     let minutes_in_day = 60 * 24;
@@ -247,6 +788,23 @@ fn difference_as_signed_percentage_str_of_usize(older: usize, newer: usize) -> S
 }
 
 
+// Nothing can be a percentage of 0, so what appeared out of nothing is just new
+fn difference_as_signed_percentage_or_new(older: usize, newer: usize) -> String {
+    if older == 0 {
+        return if newer == 0 {"0%".to_owned()} else {"new".to_owned()};
+    }
+    difference_as_signed_percentage_str_of_usize(older, newer) + "%"
+}
+
+fn signed_difference_str(older: usize, newer: usize) -> String {
+    match newer.cmp(&older) {
+        std::cmp::Ordering::Greater => format!("+{}", with_seperators(newer - older)),
+        std::cmp::Ordering::Less => format!("-{}", with_seperators(older - newer)),
+        std::cmp::Ordering::Equal => "0".to_owned()
+    }
+}
+
+
 fn difference_as_signed_percentage_str_of_f64(older: f64, newer: f64) -> String {
     let (difference, sign) = if newer > older {(newer-older, "+".to_owned())} else if older > newer {(older-newer, "-".to_owned())} else {(0.0,String::new())};
     let mut percentage = (difference / older) * 100.0;
@@ -327,6 +885,69 @@ fn get_keywords_as_str(keyword_occurencies: &HashMap<String,usize>, max_files_nu
     keyword_info
 }
 
+fn get_functions_as_str(functions: usize, average_code_lines: Option<f64>, max_files_num_size: usize) -> String {
+    let Some(average_code_lines) = average_code_lines else { return String::new() };
+    format!("{}{}: {} , {}: {:.1}", " ".repeat(KEYWORD_LINE_OFFSET + max_files_num_size), colored_word("functions"),
+            with_seperators(functions), colored_word("code lines per function"), average_code_lines)
+}
+
+fn get_complexity_as_str(complexity: usize, files: usize, max_files_num_size: usize) -> String {
+    if complexity == 0 || files == 0 {
+        return String::new();
+    }
+    format!("{}{}: {} , {}: {:.1}", " ".repeat(KEYWORD_LINE_OFFSET + max_files_num_size), colored_word("complexity"),
+            with_seperators(complexity), colored_word("average per file"), complexity as f64 / files as f64)
+}
+
+fn get_nesting_as_str(depth_histogram: &[usize], max_files_num_size: usize) -> String {
+    let (Some(max_depth), Some(average_depth)) = (domain::max_depth_of(depth_histogram), domain::average_depth_of(depth_histogram)) else {
+        return String::new()
+    };
+
+    let offset = " ".repeat(KEYWORD_LINE_OFFSET + max_files_num_size);
+    let depths_str = domain::depth_histogram_entries(depth_histogram).iter()
+            .map(|(depth, lines)| format!("{}: {}", colored_word(depth), with_seperators(*lines))).collect::<Vec<_>>().join(" , ");
+    format!("{offset}{}: {} , {}: {:.1}\n{offset}{} -> {}", colored_word("max nesting"), max_depth, colored_word("average nesting"),
+            average_depth, "lines per depth".italic(), depths_str)
+}
+
+// The ratio is left out when all the code is test code
+fn get_tests_as_str(test_code_lines: usize, code_lines: usize, max_files_num_size: usize) -> String {
+    if test_code_lines == 0 {
+        return String::new();
+    }
+    let non_test_code_lines = code_lines - test_code_lines;
+    let mut tests_str = format!("{}{}: {} , {}: {}", " ".repeat(KEYWORD_LINE_OFFSET + max_files_num_size), colored_word("test code"),
+            with_seperators(test_code_lines), colored_word("non-test code"), with_seperators(non_test_code_lines));
+    if non_test_code_lines > 0 {
+        tests_str += &format!(" , {}: {:.2}", colored_word("test to code ratio"), test_code_lines as f64 / non_test_code_lines as f64);
+    }
+    tests_str
+}
+
+fn get_comment_keywords_as_str(comment_keyword_occurencies: &HashMap<String,usize>, max_files_num_size: usize) -> String {
+    if comment_keyword_occurencies.is_empty() {
+        return String::new();
+    }
+
+    let mut sorted_keywords = comment_keyword_occurencies.iter().collect::<Vec<_>>();
+    sorted_keywords.sort_unstable_by_key(|(name,_)| name.as_str());
+    let keywords_str = sorted_keywords.iter().map(|(name,occurancies)| format!("{}: {}", colored_word(name), with_seperators(**occurancies)))
+            .collect::<Vec<_>>().join(" , ");
+    format!("{}{} -> {}", " ".repeat(KEYWORD_LINE_OFFSET + max_files_num_size), "in comments".italic(), keywords_str)
+}
+
+fn create_comment_keyword_sum_map(content_info_map: &HashMap<String,LanguageContentInfo>) -> HashMap<String,usize> {
+    let mut collective_keywords_map : HashMap<String,usize> = HashMap::new();
+    for content_info in content_info_map.values() {
+        for (name, occurences) in content_info.comment_keyword_occurences.iter() {
+            *collective_keywords_map.entry(name.to_owned()).or_insert(0) += *occurences;
+        }
+    }
+
+    collective_keywords_map
+}
+
 fn create_keyword_sum_map(content_info_map: &HashMap<String,LanguageContentInfo>) -> HashMap<String,usize> {
     let mut collective_keywords_map : HashMap<String,usize> = HashMap::new();
     for content_info in content_info_map.values() {
@@ -827,6 +1448,20 @@ mod tests {
         assert_eq!(Some("entry three".to_owned()),log_entries[2].name);
     }
 
+    #[test]
+    fn test_distribution_of_file_lines() {
+        let file_lines = (1..=200).collect::<Vec<usize>>();
+        assert_eq!(100, get_percentile(&file_lines, 50));
+        assert_eq!(180, get_percentile(&file_lines, 90));
+        assert_eq!(198, get_percentile(&file_lines, 99));
+        assert_eq!(7, get_percentile(&[7], 50));
+        assert_eq!(7, get_percentile(&[3, 7], 99));
+
+        let buckets = get_file_lines_buckets(&[0, 49, 50, 260, 999, 1000, 5000]);
+        assert_eq!(vec![("0-49".to_owned(), 2), ("50-99".to_owned(), 1), ("100-249".to_owned(), 0), ("250-499".to_owned(), 1),
+                ("500-999".to_owned(), 1), ("1000+".to_owned(), 2)], buckets);
+    }
+
     #[test]
     fn test_log_creation_and_reading() -> std::io::Result<()> {
         let test_log_dir = LOCAL_APP_PATHS.test_log_dir.clone() + "test2";
@@ -835,10 +1470,18 @@ mod tests {
         }
 
         let mut config = Configuration::new(vec!["./".to_owned()]);
-        config.set_log_option(LogOption::new(Some("test name".to_owned())));
+        config.set_log_option(LogOption::new(Some("test name".to_owned()))).set_estimation(true);
         let final_stats = FinalStats::new(10, 1000, 100, 100);
 
-        log_stats(&test_log_dir, &None, &final_stats, &chrono::DateTime::from_str("2021-09-12 04:00:00 +03:00").unwrap(), &config).unwrap();
+        let mut content_info = LanguageContentInfo::dummy(1000);
+        content_info.comment_keyword_occurences = hashmap!("TODO".to_owned() => 3, "FIXME".to_owned() => 1);
+        content_info.depth_histogram = vec![40, 30, 20, 5, 2, 1, 1, 0, 0, 0, 1];
+        content_info.code_lines = 100;
+        content_info.test_code_lines = 25;
+        let content_info_map = hashmap!("Rust".to_owned() => content_info);
+
+        log_stats(&test_log_dir, &None, &content_info_map, &final_stats, &chrono::DateTime::from_str("2021-09-12 04:00:00 +03:00").unwrap(),
+                &config).unwrap();
 
         let contents = utils::extract_file_contents(&test_log_dir).unwrap();
         let log_entries = parse_N_previous_entries(&contents, 1);
@@ -854,6 +1497,11 @@ mod tests {
         assert_eq!(10.0, log_entries[0].stats.average_size);
         assert_eq!("Bytes".to_owned(), log_entries[0].stats.average_size_measurement);
         assert_eq!(Some("test name".to_owned()),log_entries[0].name);
+        assert!(contents.contains("    Comment markers: 4\n        FIXME: 1\n        TODO: 3\n"));
+        assert!(contents.contains("    Max nesting: 10\n        Average nesting: 1.14\n        Depth 0: 40\n        Depth 1: 30\n"));
+        assert!(contents.contains("        Depth 7: 0\n        Depth 8+: 1\n    Test code lines: 25\n        Non-test code lines: 75\n"));
+        assert!(contents.contains("        Rust: 25 test , 75 non-test\n    Estimated effort: 0.21\n        Schedule: 1.39\n"));
+        assert!(contents.contains("        People: 0.15\n        Cost: 2408\n\n"));
 
         Ok(())
     }
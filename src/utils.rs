@@ -56,6 +56,16 @@ pub fn parse_usize_value(s: &str, min: usize, max: usize) -> Option<usize> {
     }
 }
 
+// A number of bytes, optionally followed by a unit of B, KB, MB or GB, that are multiples of 1000 like the printed sizes
+pub fn parse_size_value(s: &str) -> Option<usize> {
+    let s = s.trim().to_lowercase();
+    let (number, multiplier) = [("gb", 1e9), ("mb", 1e6), ("kb", 1e3), ("b", 1.0)].iter()
+            .find_map(|(unit, multiplier)| s.strip_suffix(unit).map(|x| (x, *multiplier)))
+            .unwrap_or((&s, 1.0));
+    let value = number.trim().parse::<f64>().ok().filter(|x| x.is_finite() && *x >= 0.0)?;
+    Some((value * multiplier).round() as usize)
+}
+
 pub fn parse_two_usize_values(s: &str, min1: usize, max1: usize, min2: usize, max2: usize) -> Option<(usize,usize)> {
     let elements = s.split_whitespace().filter_map(get_trimmed_if_not_empty).collect::<Vec<_>>();
     if elements.len() != 2 {
@@ -71,6 +81,13 @@ pub fn parse_two_usize_values(s: &str, min1: usize, max1: usize, min2: usize, ma
     None
 }
 
+// Exactly the given number of positive values, separated by whitespace
+pub fn parse_positive_f64_values(s: &str, count: usize) -> Option<Vec<f64>> {
+    let values = s.split_whitespace().map(|x| x.parse::<f64>().ok().filter(|x| x.is_finite() && *x > 0.0))
+            .collect::<Option<Vec<_>>>()?;
+    (values.len() == count).then_some(values)
+}
+
 pub fn get_trimmed_if_not_empty(str: &str) -> Option<String> {
     let str = str.trim();
     if str.is_empty() {None}
@@ -190,6 +207,67 @@ pub fn build_exclude_matcher(exclude_patterns: &[String]) -> Result<globset::Glo
     builder.build()
 }
 
+// The files that the include patterns select. Unlike the exclude patterns, a pattern with slashes is anchored at
+// every target dir, like in a .gitignore, and a pattern that matches a dir matches every file below it too.
+#[derive(Debug)]
+pub struct IncludeMatcher {
+    glob_set: globset::GlobSet,
+    // The leading components of every anchored pattern that have no glob syntax
+    literal_prefixes: Vec<PathBuf>
+}
+
+impl IncludeMatcher {
+    // Every file is included when there are no patterns
+    pub fn is_empty(&self) -> bool {
+        self.glob_set.is_empty()
+    }
+
+    pub fn includes(&self, file: &Path) -> bool {
+        self.is_empty() || self.glob_set.is_match(file)
+    }
+
+    // False when no pattern could match a file below the dir, so that it doesn't need to be traversed
+    pub fn may_include_below(&self, dir: &Path) -> bool {
+        self.is_empty() || self.literal_prefixes.iter().any(|x| x.starts_with(dir) || dir.starts_with(x))
+    }
+}
+
+pub fn build_include_matcher(include_patterns: &[String], target_dirs: &[String]) -> Result<IncludeMatcher, globset::Error> {
+    let mut builder = globset::GlobSetBuilder::new();
+    let mut literal_prefixes = Vec::new();
+    for pattern in include_patterns {
+        let normalized = pattern.trim().replace('\\', "/");
+        let normalized = normalized.trim_end_matches('/');
+        let normalized = normalized.strip_prefix("./").unwrap_or(normalized);
+        let is_absolute = Path::new(normalized).is_absolute();
+        let relative = if is_absolute || normalized.contains('/') {
+            normalized.to_owned()
+        } else {
+            format!("**/{normalized}")
+        };
+        // Validated on its own too, since there may be no target dirs to anchor it at
+        globset::GlobBuilder::new(&relative).literal_separator(true).build()?;
+        let literal_end = match relative.find(['*', '?', '[', '{']) {
+            Some(x) => relative[..x].rfind('/').unwrap_or(0),
+            None => relative.len()
+        };
+
+        let anchors = if is_absolute {
+            vec![(String::new(), String::new())]
+        } else {
+            target_dirs.iter().map(|x| x.trim_end_matches('/')).map(|x| (globset::escape(x) + "/", x.to_owned() + "/")).collect()
+        };
+        for (escaped_anchor, anchor) in anchors {
+            for glob in [format!("{escaped_anchor}{relative}"), format!("{escaped_anchor}{relative}/**")] {
+                builder.add(globset::GlobBuilder::new(&glob).literal_separator(true).build()?);
+            }
+            literal_prefixes.push(PathBuf::from(anchor + &relative[..literal_end]));
+        }
+    }
+
+    Ok(IncludeMatcher { glob_set: builder.build()?, literal_prefixes })
+}
+
 pub fn extract_file_contents(file_path: &str) -> Option<String> {
     if Path::new(&file_path).is_file() {
         let mut contents = String::with_capacity(700);
@@ -204,6 +282,16 @@ pub fn extract_file_contents(file_path: &str) -> Option<String> {
     }
 }
 
+// The path of a file inside the deepest target dir that contains it, or its name if it is a target itself.
+// The paths that are outside of every target dir, like the ones of a files list, are returned unchanged.
+pub fn get_path_inside_target_dir<'a>(path: &'a Path, target_dirs: &[String]) -> &'a Path {
+    match target_dirs.iter().filter(|dir| path.starts_with(dir)).max_by_key(|dir| dir.len()) {
+        Some(dir) if path != Path::new(dir) => path.strip_prefix(dir).unwrap_or(path),
+        Some(_) => path.file_name().map_or(path, Path::new),
+        None => path
+    }
+}
+
 pub fn get_file_extension(path: &Path) -> Option<&str> {
     match path.extension() {
         Some(x) => x.to_str(),
@@ -314,6 +402,25 @@ mod Tests{
         assert_eq!(Some((1,1)),parse_two_usize_values("     1       1  ", 1, 4, 1, 12));
         assert_eq!(Some((4,12)),parse_two_usize_values("4 12", 1, 4, 1, 12));
         assert_eq!(Some((2,6)),parse_two_usize_values("2 6", 1, 4, 1, 12));
+
+        assert_eq!(None,parse_positive_f64_values("", 2));
+        assert_eq!(None,parse_positive_f64_values("1.5", 2));
+        assert_eq!(None,parse_positive_f64_values("1.5 2 3", 2));
+        assert_eq!(None,parse_positive_f64_values("1.5 A", 2));
+        assert_eq!(None,parse_positive_f64_values("1.5 0", 2));
+        assert_eq!(None,parse_positive_f64_values("1.5 -2", 2));
+        assert_eq!(None,parse_positive_f64_values("1.5 inf", 2));
+        assert_eq!(Some(vec![1.5,2.0]),parse_positive_f64_values("  1.5   2 ", 2));
+
+        assert_eq!(None,parse_size_value(""));
+        assert_eq!(None,parse_size_value("MB"));
+        assert_eq!(None,parse_size_value("-1kb"));
+        assert_eq!(None,parse_size_value("1 TB"));
+        assert_eq!(Some(100),parse_size_value(" 100 "));
+        assert_eq!(Some(100),parse_size_value("100b"));
+        assert_eq!(Some(2_500),parse_size_value("2.5KB"));
+        assert_eq!(Some(3_000_000),parse_size_value("3 mb"));
+        assert_eq!(Some(1_000_000_000),parse_size_value("1gb"));
     }
 }
 #[cfg(test)]
@@ -418,6 +525,28 @@ mod exclude_matcher_tests {
         assert!(matcher.is_match("D:/dev/proj/target"));
     }
 
+    #[test]
+    fn test_include_patterns_are_anchored_at_the_target_dirs() {
+        let patterns = ["src/**".to_owned(), "lib/**/*.rs".to_owned(), "*.md".to_owned(), "./docs/".to_owned()];
+        let matcher = build_include_matcher(&patterns, &["/work/src/proj".to_owned(), "/other/".to_owned()]).unwrap();
+
+        assert!(matcher.includes(Path::new("/work/src/proj/src/main.rs")));
+        assert!(matcher.includes(Path::new("/other/src/a/b.py")));
+        assert!(!matcher.includes(Path::new("/work/src/proj/main.rs")));
+        assert!(!matcher.includes(Path::new("/work/src/proj/app/src/a.py")));
+        assert!(matcher.includes(Path::new("/work/src/proj/lib/a/b.rs")));
+        assert!(!matcher.includes(Path::new("/work/src/proj/lib/a/b.py")));
+        assert!(matcher.includes(Path::new("/work/src/proj/tests/notes.md")));
+        assert!(matcher.includes(Path::new("/other/docs/a/b.txt")));
+
+        let matcher = build_include_matcher(&patterns[..2], &["/work/src/proj".to_owned()]).unwrap();
+        assert!(matcher.may_include_below(Path::new("/work/src/proj/lib/a")));
+        assert!(!matcher.may_include_below(Path::new("/work/src/proj/tests")));
+        assert!(!matcher.may_include_below(Path::new("/work/src/proj/lib2")));
+        assert!(build_include_matcher(&patterns, &[]).unwrap().includes(Path::new("/any/file.py")));
+        assert!(build_include_matcher(&["src/[invalid".to_owned()], &[]).is_err());
+    }
+
     #[test]
     fn test_parse_colors_to_vec() {
         assert_eq!(Some(vec![Color::TrueColor{r:255,g:0,b:0}]), parse_colors_to_vec("ff0000"));
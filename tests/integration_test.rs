@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use crossbeam_deque::{Injector, Worker};
@@ -17,10 +18,8 @@ fn test_whole_workflow () {
 
     let config = Arc::new(config);
     let mut files_present = FilesPresent::default();
-    let faulty_files_ref : FaultyFilesListMut  = Arc::new(Mutex::new(Vec::new()));
     let finish_condition_ref = Arc::new(AtomicBool::new(false));
     let language_map = Arc::new(language_map);
-    let languages_content_info_ref = Arc::new(Mutex::new(make_language_stats(language_map.clone())));
     let files_injector = Arc::new(Injector::new());
     let dirs_injector = Arc::new(Injector::new());
     let idle_producers = Arc::new(std::sync::atomic::AtomicUsize::new(0));
@@ -31,15 +30,14 @@ fn test_whole_workflow () {
     let extension_lang_map: ExtensionLangMap = Arc::new(make_extension_language_map(&language_map));
     calculate_single_file_stats_or_add_to_injector(&config, &dirs_injector, &files_injector, &mut files_present, &extension_lang_map, &languages_metadata_map);
 
-    let exclude_matcher = Arc::new(build_exclude_matcher(&config.exclude_dirs).unwrap());
-    let (total_files_num, relevant_files_num, _) = producer::search_for_files(0, files_injector.clone(), dirs_injector.clone(),
-         Worker::new_fifo(), idle_producers, extension_lang_map, exclude_matcher, languages_metadata_map.clone(), config.clone());
+    let traversal_context = TraversalContext::new(config.clone(), extension_lang_map, languages_metadata_map.clone(), files_injector.clone());
+    let files_present = producer::search_for_files(0, dirs_injector.clone(), Worker::new_fifo(), idle_producers, &traversal_context);
 
     finish_condition_ref.store(true, Ordering::Relaxed);
-    consumer::start_parsing_files(0, files_injector, faulty_files_ref.clone(), finish_condition_ref, languages_content_info_ref.clone(),
-         language_map.clone(), config);
+    let parsing_context = ParsingContext::new(config, language_map.clone());
+    consumer::start_parsing_files(0, files_injector, finish_condition_ref, &parsing_context);
     
-    let mut content_info_map_guard = languages_content_info_ref.lock();
+    let mut content_info_map_guard = parsing_context.languages_content_info.lock();
     let content_info_map = content_info_map_guard.as_deref_mut().unwrap();
 
     let mut languages_metadata_map_guard = languages_metadata_map.lock();
@@ -47,10 +45,10 @@ fn test_whole_workflow () {
 
     remove_languages_with_0_files(content_info_map, languages_metadata_map);
     
-    assert!(relevant_files_num != 0 && total_files_num != 0);
+    assert!(files_present.relevant_files != 0 && files_present.total_files != 0);
     let first_lang_metadata = languages_metadata_map.iter().next().unwrap().1;
     assert!(first_lang_metadata.files != 0 && first_lang_metadata.bytes != 0);
-    assert!(faulty_files_ref.clone().lock().unwrap().is_empty());
+    assert!(parsing_context.faulty_files.lock().unwrap().is_empty());
 
     let mut keyword_num = 0;
     for content_info in content_info_map.iter() {
@@ -70,9 +68,8 @@ fn count_files_of(target: &str, extra_args: &str) -> (usize, usize, usize, Vec<S
     let mut files_present = FilesPresent::default();
     calculate_single_file_stats_or_add_to_injector(&config, &dirs_injector, &files_injector, &mut files_present, &extension_lang_map, &languages_metadata_map);
 
-    let exclude_matcher = Arc::new(build_exclude_matcher(&config.exclude_dirs).unwrap());
-    let (total, relevant, excluded) = producer::search_for_files(0, files_injector.clone(), dirs_injector,
-         Worker::new_fifo(), idle_producers, extension_lang_map, exclude_matcher, languages_metadata_map, config);
+    let traversal_context = TraversalContext::new(config, extension_lang_map, languages_metadata_map, files_injector.clone());
+    let files_present = producer::search_for_files(0, dirs_injector, Worker::new_fifo(), idle_producers, &traversal_context);
 
     let mut found_files = Vec::new();
     while let crossbeam_deque::Steal::Success(f) = files_injector.steal() {
@@ -80,7 +77,7 @@ fn count_files_of(target: &str, extra_args: &str) -> (usize, usize, usize, Vec<S
     }
     found_files.sort();
 
-    (total, relevant, excluded, found_files)
+    (files_present.total_files, files_present.relevant_files, files_present.excluded_files, found_files)
 }
 
 #[test]
@@ -143,3 +140,441 @@ fn test_gitignore_traversal() {
     std::fs::remove_dir_all(&root).unwrap();
 }
 
+#[test]
+fn test_the_other_ignore_files_are_respected_too() {
+    let root = std::env::temp_dir().join("mezura_ignore_files_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join(".git").join("info")).unwrap();
+    std::fs::create_dir_all(root.join("generated")).unwrap();
+    std::fs::create_dir_all(root.join("sub")).unwrap();
+    std::fs::write(root.join(".git").join("info").join("exclude"), "excluded.rs\n").unwrap();
+    std::fs::write(root.join(".gitignore"), "generated/\n*.py\n").unwrap();
+    // The .ignore takes precedence over the .gitignore of the same dir
+    std::fs::write(root.join(".ignore"), "!generated/\n").unwrap();
+    std::fs::write(root.join("sub").join(".mezuraignore"), "d.rs\n").unwrap();
+    std::fs::write(root.join("a.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("excluded.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("c.py"), "x = 1\n").unwrap();
+    std::fs::write(root.join("generated").join("b.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("sub").join("d.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("sub").join("e.rs"), "fn main() {}\n").unwrap();
+
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    let (_, _, excluded, found_files) = count_files_of(&root_str, "");
+    assert_eq!(3, excluded);
+    assert_eq!(vec!["a.rs", "b.rs", "e.rs"], found_files);
+
+    let (_, _, _, found_files) = count_files_of(&format!("{root_str}/sub"), "");
+    assert_eq!(vec!["e.rs"], found_files);
+
+    let (_, _, excluded, _) = count_files_of(&root_str, "--no-gitignore");
+    assert_eq!(0, excluded);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_the_linguist_attributes_exclude_files_and_override_languages() {
+    let root = std::env::temp_dir().join("mezura_gitattributes_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join(".git")).unwrap();
+    std::fs::create_dir_all(root.join("vendor").join("ours")).unwrap();
+    std::fs::write(root.join(".gitattributes"), "vendor/** linguist-vendored\n*.pb.rs linguist-generated=true\n*.tmpl linguist-language=python\n").unwrap();
+    std::fs::write(root.join("vendor").join("ours").join(".gitattributes"), "* -linguist-vendored\n").unwrap();
+    std::fs::write(root.join("a.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("a.pb.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("script.tmpl"), "x = 1\n").unwrap();
+    std::fs::write(root.join("vendor").join("b.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("vendor").join("ours").join("c.rs"), "fn main() {}\n").unwrap();
+
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    let (_, relevant, excluded, found_files) = count_files_of(&root_str, "");
+    assert_eq!((3, 2), (relevant, excluded));
+    assert_eq!(vec!["a.rs", "c.rs", "script.tmpl"], found_files);
+
+    let (_, relevant, excluded, found_files) = count_files_of(&root_str, "--no-gitattributes");
+    assert_eq!((4, 0), (relevant, excluded));
+    assert_eq!(vec!["a.pb.rs", "a.rs", "b.rs", "c.rs"], found_files);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_only_the_included_files_are_counted() {
+    let root = std::env::temp_dir().join("mezura_include_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("src").join("deep")).unwrap();
+    std::fs::create_dir_all(root.join("lib").join("x")).unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::write(root.join("f.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("src").join("a.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("src").join("deep").join("b.py"), "x = 1\n").unwrap();
+    std::fs::write(root.join("lib").join("x").join("c.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("lib").join("d.py"), "x = 1\n").unwrap();
+    std::fs::write(root.join("tests").join("e.rs"), "fn main() {}\n").unwrap();
+
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    // The tests dir is never traversed, so its file isn't found at all
+    let (total, relevant, excluded, found_files) = count_files_of(&root_str, "--include src/**,lib/**/*.rs");
+    assert_eq!((5, 3, 2), (total, relevant, excluded));
+    assert_eq!(vec!["a.rs", "b.py", "c.rs"], found_files);
+
+    let (_, _, _, found_files) = count_files_of(&root_str, "--include *.py --exclude deep");
+    assert_eq!(vec!["d.py"], found_files);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+
+#[test]
+fn test_the_depth_size_and_modification_time_filters() {
+    let root = std::env::temp_dir().join("mezura_traversal_filters_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("sub").join("deep")).unwrap();
+    std::fs::write(root.join("a.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("big.rs"), "fn main() {}\n".repeat(100)).unwrap();
+    std::fs::write(root.join("sub").join("b.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("sub").join("deep").join("c.rs"), "fn main() {}\n").unwrap();
+    let old_file = std::fs::File::create(root.join("old.rs")).unwrap();
+    old_file.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(10 * 24 * 3600)).unwrap();
+
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    // The dirs below the maximum depth aren't traversed, so their files aren't found at all
+    let (total, _, _, found_files) = count_files_of(&root_str, "--max-depth 1");
+    assert_eq!(3, total);
+    assert_eq!(vec!["a.rs", "big.rs", "old.rs"], found_files);
+    let (_, _, _, found_files) = count_files_of(&root_str, "--max-depth 2");
+    assert_eq!(vec!["a.rs", "b.rs", "big.rs", "old.rs"], found_files);
+
+    let (total, relevant, excluded, found_files) = count_files_of(&root_str, "--max-file-size 1kb --min-file-size 1");
+    assert_eq!((5, 3, 2), (total, relevant, excluded));
+    assert_eq!(vec!["a.rs", "b.rs", "c.rs"], found_files);
+
+    let (_, _, excluded, found_files) = count_files_of(&root_str, "--modified-since 1w");
+    assert_eq!(1, excluded);
+    assert_eq!(vec!["a.rs", "b.rs", "big.rs", "c.rs"], found_files);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_the_unreadable_dirs_are_reported_instead_of_skipped() {
+    let root = std::env::temp_dir().join("mezura_traversal_errors_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("locked")).unwrap();
+    std::fs::write(root.join("a.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("locked").join("b.rs"), "fn main() {}\n").unwrap();
+    // The permissions don't stop the root user, so the locked dir is only expected to fail when they do
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o000)).unwrap();
+    }
+    let is_locked_dir_unreadable = std::fs::read_dir(root.join("locked")).is_err();
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    let config = Arc::new(config_manager::create_config_from_args(&format!("{root_str} --threads 1 1")).unwrap());
+    let language_map = Arc::new(io_handler::parse_supported_languages_to_map(&LOCAL_APP_PATHS.languages_dir).unwrap().0);
+    let files_injector = Arc::new(Injector::new());
+    let dirs_injector = Arc::new(Injector::new());
+    let languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(&language_map)));
+    let extension_lang_map: ExtensionLangMap = Arc::new(make_extension_language_map(&language_map));
+    calculate_single_file_stats_or_add_to_injector(&config, &dirs_injector, &files_injector, &mut FilesPresent::default(),
+            &extension_lang_map, &languages_metadata_map);
+    // Like a dir that is removed while the traversal is running
+    dirs_injector.push(TraversedDir::new(root.join("removed"), None, None, 1));
+    let traversal_context = TraversalContext::new(config, extension_lang_map, languages_metadata_map, files_injector);
+    let files_present = producer::search_for_files(0, dirs_injector, Worker::new_fifo(), Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            &traversal_context);
+
+    let mut traversal_errors = traversal_context.traversal_errors.lock().unwrap();
+    traversal_errors.sort_by(|a, b| a.path.cmp(&b.path));
+    if is_locked_dir_unreadable {
+        assert_eq!(1, files_present.relevant_files);
+        assert_eq!(2, traversal_errors.len());
+        assert_eq!(root.join("locked"), traversal_errors[0].path);
+        assert_eq!(std::io::ErrorKind::PermissionDenied, traversal_errors[0].error_kind);
+    } else {
+        assert_eq!(2, files_present.relevant_files);
+        assert_eq!(1, traversal_errors.len());
+    }
+    let removed_dir_error = traversal_errors.last().unwrap();
+    assert_eq!(root.join("removed"), removed_dir_error.path);
+    assert_eq!(std::io::ErrorKind::NotFound, removed_dir_error.error_kind);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(root.join("locked"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_the_symlinks_are_followed_only_on_demand_and_every_file_is_counted_once() {
+    let root = std::env::temp_dir().join("mezura_symlinks_test");
+    let outside = std::env::temp_dir().join("mezura_symlinks_outside_test");
+    let _ = std::fs::remove_dir_all(&root);
+    let _ = std::fs::remove_dir_all(&outside);
+    std::fs::create_dir_all(root.join("sub")).unwrap();
+    std::fs::create_dir_all(outside.join("lib")).unwrap();
+    std::fs::write(root.join("a.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("sub").join("b.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(outside.join("e.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(outside.join("lib").join("f.rs"), "fn main() {}\n").unwrap();
+    std::fs::hard_link(root.join("a.rs"), root.join("hard.rs")).unwrap();
+    std::os::unix::fs::symlink(root.join("a.rs"), root.join("c.rs")).unwrap();
+    std::os::unix::fs::symlink(root.join("sub"), root.join("link_to_sub")).unwrap();
+    // A loop back to the target dir
+    std::os::unix::fs::symlink(&root, root.join("sub").join("loop")).unwrap();
+    std::os::unix::fs::symlink(outside.join("e.rs"), root.join("e.rs")).unwrap();
+    std::os::unix::fs::symlink(outside.join("lib"), root.join("lib")).unwrap();
+    std::os::unix::fs::symlink(root.join("missing.rs"), root.join("broken.rs")).unwrap();
+
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    // One of the hard links is skipped, while the symlinks are not even found
+    let (total, relevant, _, found_files) = count_files_of(&root_str, "");
+    assert_eq!((3, 2), (total, relevant));
+    assert_eq!(2, found_files.len());
+    assert!(found_files.contains(&"b.rs".to_owned()));
+
+    let (_, relevant, _, found_files) = count_files_of(&root_str, "--follow-symlinks");
+    assert_eq!(4, relevant);
+    assert!(found_files.contains(&"e.rs".to_owned()) && found_files.contains(&"f.rs".to_owned()) && found_files.contains(&"b.rs".to_owned()));
+
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_dir_all(&outside).unwrap();
+}
+
+#[test]
+fn test_the_listed_files_are_counted_without_traversing() {
+    let root = std::env::temp_dir().join("mezura_files_from_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("sub")).unwrap();
+    std::fs::create_dir_all(root.join("build")).unwrap();
+    std::fs::create_dir_all(root.join("vendor")).unwrap();
+    std::fs::write(root.join(".gitignore"), "ignored.rs\nbuild/\n").unwrap();
+    std::fs::write(root.join(".gitattributes"), "vendor/** linguist-vendored\n").unwrap();
+    std::fs::write(root.join("ignored.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("a.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("a.py"), "x = 1\n").unwrap();
+    std::fs::write(root.join("sub").join("b.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("build").join("c.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("vendor").join("d.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(root.join("not_listed.rs"), "fn main() {}\n").unwrap();
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+    let list_path = root.join("list.txt");
+    std::fs::write(&list_path, format!("{root_str}/ignored.rs\n{root_str}/a.rs\n{root_str}/./a.rs\n{root_str}/a.py\n{root_str}/sub/b.rs\n\
+            {root_str}/missing.rs\n{root_str}/sub\n{root_str}/build/c.rs\n{root_str}/vendor/d.rs\n")).unwrap();
+
+    let count_listed_files = |extra_args: &str| {
+        let config = config_manager::create_config_from_args(&format!("{root_str} --files-from {} --exclude sub --languages rust {extra_args}",
+                list_path.to_str().unwrap())).unwrap();
+        let language_map = io_handler::parse_supported_languages_to_map(&LOCAL_APP_PATHS.languages_dir).unwrap().0;
+        let language_map = Arc::new(language_map.into_iter().filter(|x| x.0 == "Rust").collect::<HashMap<_,_>>());
+        let languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(&language_map)));
+        let extension_lang_map = Arc::new(make_extension_language_map(&language_map));
+        let paths = io_handler::read_files_list(config.files_from.as_ref().unwrap()).unwrap();
+        let context = TraversalContext::new(Arc::new(config), extension_lang_map, languages_metadata_map, Arc::new(Injector::new()));
+        let mut files_present = FilesPresent::default();
+        producer::add_listed_files(paths, &context, &mut files_present);
+
+        let mut found_files = Vec::new();
+        while let crossbeam_deque::Steal::Success(f) = context.files_injector.steal() {
+            found_files.push(f.path);
+        }
+        // The missing file is reported, while the dir isn't counted at all
+        let traversal_errors = context.traversal_errors.lock().unwrap();
+        assert_eq!(1, traversal_errors.len());
+        assert_eq!((root.join("missing.rs"), std::io::ErrorKind::NotFound), (traversal_errors[0].path.clone(), traversal_errors[0].error_kind));
+        (found_files, files_present)
+    };
+
+    // By default the ignore files and the linguist attributes don't apply, and the duplicate is skipped
+    let (found_files, files_present) = count_listed_files("");
+    assert_eq!(vec![root.join("ignored.rs"), root.join("a.rs"), root.join("build").join("c.rs"), root.join("vendor").join("d.rs")], found_files);
+    assert_eq!((7, 4, 1, 1), (files_present.total_files, files_present.relevant_files, files_present.excluded_files, files_present.duplicate_files));
+
+    // The ignored dirs above a listed file are matched too
+    let (found_files, files_present) = count_listed_files("--respect-ignores");
+    assert_eq!(vec![root.join("a.rs")], found_files);
+    assert_eq!((7, 1, 4, 1), (files_present.total_files, files_present.relevant_files, files_present.excluded_files, files_present.duplicate_files));
+    assert_eq!([1, 0, 2, 1, 0, 0], files_present.excluded_by);
+
+    let (_, files_present) = count_listed_files("--respect-ignores --no-gitignore --no-gitattributes");
+    assert_eq!(4, files_present.relevant_files);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_the_files_of_the_target_archives_are_read_in_place() {
+    use std::io::Write;
+    let root = std::env::temp_dir().join("mezura_archives_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    let entries = [("./src/a.rs", "fn main() {}\n"), ("vendor/b.rs", "fn main() {}\n"), ("c.py", "x = 1\n"), ("../d.rs", "fn main() {}\n"),
+            ("skipped/e.rs", "fn main() {}\n"), ("src/gen.rs", "fn main() {}\n")];
+    // Like if the archives were extracted in place, while an ignored archive is still read
+    std::fs::write(root.join(".gitignore"), "skipped/\n*.zip\n").unwrap();
+    std::fs::write(root.join(".gitattributes"), "gen.rs linguist-generated\n").unwrap();
+
+    let mut tar_builder = tar::Builder::new(flate2::write::GzEncoder::new(std::fs::File::create(root.join("a.tar.gz")).unwrap(),
+            flate2::Compression::default()));
+    for (path, contents) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        // The builder refuses the paths that lead outside of the archive, so they are written directly
+        header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+        header.set_cksum();
+        tar_builder.append(&header, contents.as_bytes()).unwrap();
+    }
+    tar_builder.into_inner().unwrap().finish().unwrap();
+    let mut zip_writer = zip::ZipWriter::new(std::fs::File::create(root.join("b.zip")).unwrap());
+    for (path, contents) in entries {
+        zip_writer.start_file(path.trim_start_matches("./"), zip::write::SimpleFileOptions::default()).unwrap();
+        zip_writer.write_all(contents.as_bytes()).unwrap();
+    }
+    zip_writer.finish().unwrap();
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    let config = config_manager::create_config_from_args(&format!("{root_str}/a.tar.gz,{root_str}/b.zip --exclude vendor --languages rust")).unwrap();
+    let language_map = io_handler::parse_supported_languages_to_map(&LOCAL_APP_PATHS.languages_dir).unwrap().0;
+    let language_map = Arc::new(language_map.into_iter().filter(|x| x.0 == "Rust").collect::<HashMap<_,_>>());
+    let languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(&language_map)));
+    let extension_lang_map = Arc::new(make_extension_language_map(&language_map));
+    let context = TraversalContext::new(Arc::new(config), extension_lang_map.clone(), languages_metadata_map.clone(), Arc::new(Injector::new()));
+    let mut files_present = FilesPresent::default();
+    archives::find_archive_files(&context, &mut files_present).unwrap();
+
+    // The entries that lead outside of the archives are skipped
+    let mut found_files = Vec::new();
+    while let crossbeam_deque::Steal::Success(f) = context.files_injector.steal() {
+        found_files.push((f.path, f.contents.unwrap()));
+    }
+    assert_eq!(vec![(root.join("a.tar.gz").join("src/a.rs"), b"fn main() {}\n".to_vec()), (root.join("b.zip").join("src/a.rs"), b"fn main() {}\n".to_vec())],
+            found_files);
+    assert_eq!((10, 2, 6), (files_present.total_files, files_present.relevant_files, files_present.excluded_files));
+    assert_eq!([2, 0, 2, 2, 0, 0], files_present.excluded_by);
+    assert_eq!(LanguageMetadata::new(2, 26), languages_metadata_map.lock().unwrap()["Rust"]);
+
+    std::fs::write(root.join("c.zip"), "not a zip").unwrap();
+    let config = config_manager::create_config_from_args(&format!("{root_str}/c.zip")).unwrap();
+    let context = TraversalContext::new(Arc::new(config), extension_lang_map, languages_metadata_map, Arc::new(Injector::new()));
+    assert!(archives::find_archive_files(&context, &mut files_present).is_err());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_duplicate_files_are_grouped_and_counted_once() {
+    let root = std::env::temp_dir().join("mezura_duplicates_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("vendor")).unwrap();
+    std::fs::write(root.join("a.rs"), "fn main() {\n}\n").unwrap();
+    std::fs::write(root.join("b.rs"), "fn other() {\n    let x = 1;\n}\n").unwrap();
+    std::fs::write(root.join("vendor").join("a.rs"), "fn main() {\n}\n").unwrap();
+    std::fs::write(root.join("vendor").join("c.rs"), "fn main() {\n}\n").unwrap();
+    // Same contents, but another language
+    std::fs::write(root.join("a.go"), "fn main() {\n}\n").unwrap();
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    let config = Arc::new(config_manager::create_config_from_args(&format!("{root_str} --dedupe-files --threads 1 1")).unwrap());
+    let language_map = Arc::new(io_handler::parse_supported_languages_to_map(&LOCAL_APP_PATHS.languages_dir).unwrap().0);
+    let files_injector = Arc::new(Injector::new());
+    let dirs_injector = Arc::new(Injector::new());
+    let languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(&language_map)));
+    let extension_lang_map: ExtensionLangMap = Arc::new(make_extension_language_map(&language_map));
+    calculate_single_file_stats_or_add_to_injector(&config, &dirs_injector, &files_injector, &mut FilesPresent::default(),
+            &extension_lang_map, &languages_metadata_map);
+    let traversal_context = TraversalContext::new(config.clone(), extension_lang_map, languages_metadata_map.clone(), files_injector.clone());
+    producer::search_for_files(0, dirs_injector, Worker::new_fifo(), Arc::new(std::sync::atomic::AtomicUsize::new(0)), &traversal_context);
+    let parsing_context = ParsingContext::new(config, language_map);
+    consumer::start_parsing_files(0, files_injector, Arc::new(AtomicBool::new(true)), &parsing_context);
+
+    let duplicate_file_groups = make_duplicate_file_groups(&parsing_context.file_hashes.lock().unwrap());
+    assert_eq!(1, duplicate_file_groups.len());
+    assert_eq!("Rust", duplicate_file_groups[0].language_name.as_ref());
+    assert_eq!(vec![root.join("a.rs"), root.join("vendor").join("a.rs"), root.join("vendor").join("c.rs")], duplicate_file_groups[0].paths);
+    assert_eq!(28, duplicate_file_groups[0].wasted_bytes());
+
+    let mut languages_metadata_map = languages_metadata_map.lock().unwrap();
+    remove_duplicate_files_stats(&duplicate_file_groups, &mut languages_metadata_map);
+    assert_eq!(LanguageMetadata::new(2, 14 + 30), languages_metadata_map["Rust"]);
+    assert_eq!(1, languages_metadata_map["GO"].files);
+    assert_eq!(2 + 3, parsing_context.languages_content_info.lock().unwrap()["Rust"].lines);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_test_files_and_test_blocks_are_counted_as_test_code() {
+    let root = std::env::temp_dir().join("mezura_test_code_test");
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::create_dir_all(root.join("spec")).unwrap();
+    std::fs::write(root.join("main.go"), "func main() {\n    run()\n}\n").unwrap();
+    std::fs::write(root.join("main_test.go"), "func TestMain() {\n    run()\n}\n").unwrap();
+    std::fs::write(root.join("lib.rs"), "fn run() {}\n#[cfg(test)]\nmod tests {\n    fn a() {}\n}\n").unwrap();
+    std::fs::write(root.join("tests").join("it.rs"), "fn it() {\n    run();\n}\n").unwrap();
+    std::fs::write(root.join("spec").join("other.rs"), "fn other() {}\n").unwrap();
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    let config = Arc::new(config_manager::create_config_from_args(&format!("{root_str} --test-patterns **/tests/**,spec/* --threads 1 1")).unwrap());
+    let language_map = Arc::new(io_handler::parse_supported_languages_to_map(&LOCAL_APP_PATHS.languages_dir).unwrap().0);
+    let files_injector = Arc::new(Injector::new());
+    let dirs_injector = Arc::new(Injector::new());
+    let languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(&language_map)));
+    let extension_lang_map: ExtensionLangMap = Arc::new(make_extension_language_map(&language_map));
+    calculate_single_file_stats_or_add_to_injector(&config, &dirs_injector, &files_injector, &mut FilesPresent::default(),
+            &extension_lang_map, &languages_metadata_map);
+    let traversal_context = TraversalContext::new(config.clone(), extension_lang_map, languages_metadata_map, files_injector.clone());
+    producer::search_for_files(0, dirs_injector, Worker::new_fifo(), Arc::new(std::sync::atomic::AtomicUsize::new(0)), &traversal_context);
+    let parsing_context = ParsingContext::new(config, language_map);
+    consumer::start_parsing_files(0, files_injector, Arc::new(AtomicBool::new(true)), &parsing_context);
+
+    let content_info_map = parsing_context.languages_content_info.lock().unwrap();
+    assert_eq!((2, 4), (content_info_map["GO"].test_code_lines, content_info_map["GO"].code_lines));
+    // 3 lines of the test block in lib.rs, 2 of tests/it.rs and 1 of spec/other.rs
+    assert_eq!((6, 7), (content_info_map["Rust"].test_code_lines, content_info_map["Rust"].code_lines));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_the_dirs_above_the_target_dir_dont_make_its_files_test_code() {
+    let parent = std::env::temp_dir().join("mezura_parent_tests_test");
+    let root = parent.join("tests").join("proj");
+    let _ = std::fs::remove_dir_all(&parent);
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("tests")).unwrap();
+    std::fs::write(root.join("src").join("main.rs"), "fn main() {\n    run();\n}\n").unwrap();
+    std::fs::write(root.join("tests").join("it.rs"), "fn it() {}\n").unwrap();
+    let root_str = root.to_str().unwrap().replace('\\', "/");
+
+    let config = Arc::new(config_manager::create_config_from_args(&format!("{root_str} --threads 1 1")).unwrap());
+    let language_map = Arc::new(io_handler::parse_supported_languages_to_map(&LOCAL_APP_PATHS.languages_dir).unwrap().0);
+    let files_injector = Arc::new(Injector::new());
+    let dirs_injector = Arc::new(Injector::new());
+    let languages_metadata_map = Arc::new(Mutex::new(make_language_metadata(&language_map)));
+    let extension_lang_map: ExtensionLangMap = Arc::new(make_extension_language_map(&language_map));
+    calculate_single_file_stats_or_add_to_injector(&config, &dirs_injector, &files_injector, &mut FilesPresent::default(),
+            &extension_lang_map, &languages_metadata_map);
+    let traversal_context = TraversalContext::new(config.clone(), extension_lang_map, languages_metadata_map, files_injector.clone());
+    producer::search_for_files(0, dirs_injector, Worker::new_fifo(), Arc::new(std::sync::atomic::AtomicUsize::new(0)), &traversal_context);
+    let parsing_context = ParsingContext::new(config, language_map);
+    consumer::start_parsing_files(0, files_injector, Arc::new(AtomicBool::new(true)), &parsing_context);
+
+    // Only the tests dir inside the target dir holds test code
+    let content_info_map = parsing_context.languages_content_info.lock().unwrap();
+    assert_eq!((1, 3), (content_info_map["Rust"].test_code_lines, content_info_map["Rust"].code_lines));
+
+    std::fs::remove_dir_all(&parent).unwrap();
+}